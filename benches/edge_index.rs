@@ -0,0 +1,127 @@
+//! Times max-flow on grid-derived graphs with the edge lists that `Graph`
+//! keeps up to date, against the previous approach of rebuilding and sorting
+//! every node's entering edges before each path search.
+//!
+//! Run with `cargo bench --bench edge_index`.
+
+use moma_simulation_engine::{Cell, Graph, Grid, Point};
+use ordered_float::OrderedFloat;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// How many times each measurement is repeated; the fastest run is reported.
+const RUNS: usize = 5;
+
+/// An open grid graph with a source feeding its left column and a sink
+/// draining its right column, so max-flow needs one augmentation per row.
+fn grid_graph(size: usize) -> Graph {
+    let mut graph = Graph::from_grid(&Grid::new(size, size, Cell::Free), 1, 1.0);
+    let (source, sink) = (Point::new(size, 0), Point::new(size + 1, 0));
+    for y in 0..size {
+        graph.add_edge(source, Point::new(0, y), 1, 0.0);
+        graph.add_edge(Point::new(size - 1, y), sink, 1, 0.0);
+    }
+    graph.source = source;
+    graph.sink = sink;
+    graph
+}
+
+/// A residual move along the `index`-th edge out of `from`, or back against it.
+#[derive(Clone, Copy)]
+struct Arc {
+    from: Point,
+    index: usize,
+    backward: bool,
+}
+
+/// Max-flow by repeated cheapest-path searches, rebuilding the entering-edge
+/// lists from `adj` before every search as `Graph` used to.
+fn max_flow_rebuilding(graph: &mut Graph) -> u64 {
+    let mut max_flow = 0;
+    loop {
+        let mut incoming: HashMap<Point, Vec<(Point, usize)>> = HashMap::new();
+        for (&from, edges) in &graph.adj {
+            for (index, edge) in edges.iter().enumerate() {
+                incoming.entry(edge.to).or_default().push((from, index));
+            }
+        }
+        for entering in incoming.values_mut() {
+            entering.sort();
+        }
+
+        let mut distances = HashMap::from([(graph.source, 0.0)]);
+        let mut parents: HashMap<Point, (Point, Arc)> = HashMap::new();
+        let mut settled = HashSet::new();
+        let mut queue = BinaryHeap::from([(OrderedFloat(-0.0), graph.source)]);
+        while let Some((cost, u)) = queue.pop() {
+            let cost = -cost.into_inner();
+            if !settled.insert(u) || u == graph.sink {
+                continue;
+            }
+            let forward = (0..graph.get_edges(&u).len()).map(|index| Arc { from: u, index, backward: false });
+            let backward = incoming.get(&u).into_iter().flatten().map(|&(from, index)| Arc { from, index, backward: true });
+            for arc in forward.chain(backward) {
+                let edge = &graph.adj[&arc.from][arc.index];
+                let (v, spare, step) = if arc.backward {
+                    (arc.from, edge.flow, -edge.cost)
+                } else {
+                    (edge.to, edge.capacity - edge.flow, edge.cost)
+                };
+                if spare > 0 && !settled.contains(&v) && cost + step < *distances.get(&v).unwrap_or(&f64::MAX) {
+                    distances.insert(v, cost + step);
+                    parents.insert(v, (u, arc));
+                    queue.push((OrderedFloat(-(cost + step)), v));
+                }
+            }
+        }
+        if !parents.contains_key(&graph.sink) {
+            return max_flow;
+        }
+
+        let mut path = Vec::new();
+        let mut current = graph.sink;
+        while current != graph.source {
+            let (prev, arc) = parents[&current];
+            path.push(arc);
+            current = prev;
+        }
+        let spare = |arc: &Arc, graph: &Graph| {
+            let edge = &graph.adj[&arc.from][arc.index];
+            if arc.backward { edge.flow } else { edge.capacity - edge.flow }
+        };
+        let path_flow = path.iter().map(|arc| spare(arc, graph)).min().unwrap();
+        for arc in path {
+            let edge = &mut graph.adj.get_mut(&arc.from).unwrap()[arc.index];
+            if arc.backward {
+                edge.flow -= path_flow;
+            } else {
+                edge.flow += path_flow;
+            }
+        }
+        max_flow += path_flow;
+    }
+}
+
+/// Runs `flow` on a fresh graph `RUNS` times, returning the flow and the fastest time.
+fn time(size: usize, flow: impl Fn(&mut Graph) -> u64) -> (u64, Duration) {
+    let mut best = Duration::MAX;
+    let mut result = 0;
+    for _ in 0..RUNS {
+        let mut graph = grid_graph(size);
+        let started = Instant::now();
+        result = black_box(flow(&mut graph));
+        best = best.min(started.elapsed());
+    }
+    (result, best)
+}
+
+fn main() {
+    println!("{:>6} {:>6} {:>14} {:>14}", "grid", "flow", "maintained", "rebuilt");
+    for size in [16, 32, 48] {
+        let (flow, maintained) = time(size, Graph::edmonds_karp);
+        let (rebuilt_flow, rebuilt) = time(size, max_flow_rebuilding);
+        assert_eq!(flow, rebuilt_flow, "both searches must find the same maximum flow");
+        println!("{:>6} {:>6} {:>14.2?} {:>14.2?}", format!("{size}x{size}"), flow, maintained, rebuilt);
+    }
+}
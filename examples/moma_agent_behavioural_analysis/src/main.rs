@@ -9,13 +9,12 @@
 // The final output is a report comparing the "personality" of the agent
 // under each strategic configuration.
 
-use moma::core::{MomaRing, OriginStrategy};
+use moma::core::MomaRing;
 use moma::strategy;
 use moma_simulation_engine::automaton::Moma2dAutomaton;
+use moma_simulation_engine::gowers::{path_to_turning_sequence, u2_norm};
 use moma_simulation_engine::grid::Point;
-use moma_simulation_engine::pathfinding::{manhattan_distance, Node};
-use rustfft::{num_complex::Complex as FftComplex, FftPlanner};
-use std::collections::{BinaryHeap, HashMap};
+use moma_simulation_engine::pathfinding::a_star_moma;
 use std::time::Instant;
 
 // --- Experiment Configuration ---
@@ -118,12 +117,8 @@ fn main() {
 
                 for _ in 0..SIMULATION_STEPS {
                     automaton.step();
-                    if let Some(path) = a_star_moma_cost(&automaton, &cost_ring, start, goal) {
-                        let maze_path_coords: Vec<(i32, i32)> =
-                            path.iter().map(|p| (p.x as i32, p.y as i32)).collect();
-
-                        let mut complex_sequence = path_to_complex_sequence_fft(&maze_path_coords);
-                        let gowers_norm = calculate_u2_norm_fft(&mut complex_sequence);
+                    if let Some(path) = a_star_moma(&automaton, &cost_ring, start, goal, 0.0) {
+                        let gowers_norm = u2_norm(&path_to_turning_sequence(&path));
 
                         experiment.record_sample(path.len() as u64, gowers_norm);
                     }
@@ -144,12 +139,8 @@ fn main() {
 
                 for _ in 0..SIMULATION_STEPS {
                     automaton.step();
-                    if let Some(path) = a_star_moma_cost(&automaton, &cost_ring, start, goal) {
-                        let maze_path_coords: Vec<(i32, i32)> =
-                            path.iter().map(|p| (p.x as i32, p.y as i32)).collect();
-
-                        let mut complex_sequence = path_to_complex_sequence_fft(&maze_path_coords);
-                        let gowers_norm = calculate_u2_norm_fft(&mut complex_sequence);
+                    if let Some(path) = a_star_moma(&automaton, &cost_ring, start, goal, 0.0) {
+                        let gowers_norm = u2_norm(&path_to_turning_sequence(&path));
 
                         experiment.record_sample(path.len() as u64, gowers_norm);
                     }
@@ -170,12 +161,8 @@ fn main() {
 
                 for _ in 0..SIMULATION_STEPS {
                     automaton.step();
-                    if let Some(path) = a_star_moma_cost(&automaton, &cost_ring, start, goal) {
-                        let maze_path_coords: Vec<(i32, i32)> =
-                            path.iter().map(|p| (p.x as i32, p.y as i32)).collect();
-
-                        let mut complex_sequence = path_to_complex_sequence_fft(&maze_path_coords);
-                        let gowers_norm = calculate_u2_norm_fft(&mut complex_sequence);
+                    if let Some(path) = a_star_moma(&automaton, &cost_ring, start, goal, 0.0) {
+                        let gowers_norm = u2_norm(&path_to_turning_sequence(&path));
 
                         experiment.record_sample(path.len() as u64, gowers_norm);
                     }
@@ -198,104 +185,3 @@ fn main() {
     }
     println!("--------------------------------------------------");
 }
-
-// NOTE: The A*, Gowers, and helper functions are included below and are unchanged.
-// They are needed for the headless simulation to run.
-
-fn a_star_moma_cost(
-    automaton: &Moma2dAutomaton<impl OriginStrategy>,
-    cost_ring: &MomaRing<impl OriginStrategy>,
-    start: Point,
-    goal: Point,
-) -> Option<Vec<Point>> {
-    let mut frontier = BinaryHeap::new();
-    let mut came_from: HashMap<Point, Point> = HashMap::new();
-    let mut cost_so_far: HashMap<Point, u64> = HashMap::new();
-
-    cost_so_far.insert(start, 0);
-    frontier.push(Node {
-        point: start,
-        cost: 0,
-        heuristic: manhattan_distance(start, goal),
-    });
-
-    while let Some(current) = frontier.pop() {
-        if current.point == goal {
-            let mut path = vec![goal];
-            let mut curr = goal;
-            while curr != start {
-                curr = came_from[&curr];
-                path.push(curr);
-            }
-            path.reverse();
-            return Some(path);
-        }
-
-        let neighbors = [(-1, 0), (1, 0), (0, -1), (0, 1)]
-            .iter()
-            .filter_map(|&(dx, dy)| {
-                let nx = current.point.x as isize + dx;
-                let ny = current.point.y as isize + dy;
-                if nx >= 0
-                    && nx < automaton.width as isize
-                    && ny >= 0
-                    && ny < automaton.height as isize
-                {
-                    Some(Point {
-                        x: nx as usize,
-                        y: ny as usize,
-                    })
-                } else {
-                    None
-                }
-            });
-
-        for next_point in neighbors {
-            let current_val = automaton.state[current.point.y * automaton.width + current.point.x];
-            let next_val = automaton.state[next_point.y * automaton.width + next_point.x];
-            let move_cost = cost_ring.residue(current_val, next_val) + 1;
-            let new_cost = cost_so_far[&current.point] + move_cost;
-
-            if !cost_so_far.contains_key(&next_point) || new_cost < cost_so_far[&next_point] {
-                cost_so_far.insert(next_point, new_cost);
-                let priority = manhattan_distance(next_point, goal);
-                frontier.push(Node {
-                    point: next_point,
-                    cost: new_cost as u32,
-                    heuristic: priority,
-                });
-                came_from.insert(next_point, current.point);
-            }
-        }
-    }
-    None
-}
-
-fn calculate_u2_norm_fft(sequence: &mut Vec<FftComplex<f64>>) -> f64 {
-    let n = sequence.len();
-    if n == 0 {
-        return 0.0;
-    }
-    let mut planner = FftPlanner::new();
-    let fft = planner.plan_fft_forward(n);
-    fft.process(sequence);
-    let sum_of_magnitudes_pow4: f64 = sequence
-        .iter()
-        .map(|c| c.norm_sqr().powi(2))
-        .sum();
-    (sum_of_magnitudes_pow4 / (n as f64).powi(4)).powf(1.0 / 4.0)
-}
-
-fn path_to_complex_sequence_fft(path: &[(i32, i32)]) -> Vec<FftComplex<f64>> {
-    if path.len() < 2 {
-        return Vec::new();
-    }
-    let mut complex_sequence: Vec<FftComplex<f64>> = Vec::with_capacity(path.len() - 1);
-    for p in 1..path.len() {
-        let dx = path[p].0 - path[p - 1].0;
-        let dy = path[p].1 - path[p - 1].1;
-        let angle = (dy as f64).atan2(dx as f64);
-        complex_sequence.push(FftComplex::new(angle.cos(), angle.sin()));
-    }
-    complex_sequence
-}
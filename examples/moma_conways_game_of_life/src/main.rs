@@ -5,6 +5,8 @@
 
 use moma::core::MomaRing;
 use moma::strategy;
+use moma_simulation_engine::life::LifeAutomaton;
+use moma_simulation_engine::patterns::{stamp, Pattern};
 use pixels::{Error, Pixels, SurfaceTexture};
 use rand::prelude::*;
 use rand_chacha::ChaCha8Rng;
@@ -21,21 +23,20 @@ const HEIGHT: u32 = 240;
 
 /// Represents the state of the Game of Life simulation.
 struct World {
-    cells: Vec<u8>,
+    life: LifeAutomaton,
 }
 
 impl World {
     /// Creates a new World with a MOMA-seeded initial state of gliders.
     fn new() -> Self {
-        let mut cells = vec![0; (WIDTH * HEIGHT) as usize];
+        let mut life = LifeAutomaton::new(WIDTH as usize, HEIGHT as usize);
         let mut rng = ChaCha8Rng::from_seed([42; 32]);
 
         // MOMA rings to generate (x, y) coordinates for the gliders.
         let ring_x = MomaRing::new(WIDTH as u64, strategy::CompositeMass);
         let ring_y = MomaRing::new(HEIGHT as u64, strategy::PrimeGap);
 
-        // A glider pattern. It's a 3x3 shape.
-        let glider: [(isize, isize); 5] = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+        let glider = Pattern::glider();
 
         let mut p = 3;
         // Place 50 gliders on the grid.
@@ -46,75 +47,25 @@ impl World {
             let sig_y = ring_y.signature(p) as isize;
             p = moma::primes::next_prime(p + 1);
 
-            // 2. Get a random orientation (0-3 for normal, flipped, etc.).
-            let orientation = rng.gen_range(0..4);
-
-            // 3. "Stamp" the glider pattern onto the grid cells.
-            for (mut dx, mut dy) in glider {
-                // Apply a random rotation/flip to the glider pattern
-                match orientation {
-                    1 => dx = -dx, // Flipped horizontally
-                    2 => dy = -dy, // Flipped vertically
-                    3 => { (dx, dy) = (dy, dx) } // Rotated
-                    _ => {}
-                }
-
-                let x = (sig_x + dx + WIDTH as isize) as u32 % WIDTH;
-                let y = (sig_y + dy + HEIGHT as isize) as u32 % HEIGHT;
-                let index = (y * WIDTH + x) as usize;
-                cells[index] = 1;
-            }
+            // 2. Get a random orientation (4 rotations, each optionally mirrored).
+            let orientation = rng.gen_range(0..8);
+
+            // 3. "Stamp" the glider pattern onto the grid cells, wrapping at the edges.
+            stamp(life.cells_mut(), WIDTH as usize, HEIGHT as usize, glider.cells(), (sig_x, sig_y), orientation);
         }
 
-        Self { cells }
+        Self { life }
     }
 
-    /// Updates the simulation by one step.
+    /// Updates the simulation by one step using Conway's B3/S23 rules.
     fn update(&mut self) {
-        let mut next = self.cells.clone();
-        for y in 0..HEIGHT as i32 {
-            for x in 0..WIDTH as i32 {
-                let idx = (y * WIDTH as i32 + x) as usize;
-                let neighbors = self.count_neighbors(x, y);
-
-                let next_cell = match (self.cells[idx], neighbors) {
-                    // Rule 1: Any live cell with fewer than two live neighbours dies.
-                    (1, n) if n < 2 => 0,
-                    // Rule 2: Any live cell with two or three live neighbours lives on.
-                    (1, 2) | (1, 3) => 1,
-                    // Rule 3: Any live cell with more than three live neighbours dies.
-                    (1, n) if n > 3 => 0,
-                    // Rule 4: Any dead cell with exactly three live neighbours becomes a live cell.
-                    (0, 3) => 1,
-                    // All other cells remain in the same state.
-                    (otherwise, _) => otherwise,
-                };
-                next[idx] = next_cell;
-            }
-        }
-        self.cells = next;
-    }
-
-    /// Counts the live neighbors of a given cell.
-    fn count_neighbors(&self, x: i32, y: i32) -> u8 {
-        let mut count = 0;
-        for dy in [-1, 0, 1] {
-            for dx in [-1, 0, 1] {
-                if dx == 0 && dy == 0 { continue; }
-
-                let nx = (x + dx + WIDTH as i32) as u32 % WIDTH;
-                let ny = (y + dy + HEIGHT as i32) as u32 % HEIGHT;
-                let idx = (ny * WIDTH + nx) as usize;
-                count += self.cells[idx];
-            }
-        }
-        count
+        self.life.step();
     }
 
     /// Draws the world to the pixel buffer.
     fn draw(&self, frame: &mut [u8]) {
-        for (i, pixel) in frame.chunks_exact_mut(4).enumerate() {
-            let color = if self.cells[i] == 1 {
+        for (pixel, &cell) in frame.chunks_exact_mut(4).zip(self.life.cells()) {
+            let color = if cell == 1 {
                 [0x5e, 0x48, 0xe8, 0xff] // A nice purple
             } else {
                 [0x48, 0xb2, 0xe8, 0xff] // A soft blue
@@ -6,57 +6,35 @@
 // towards a target level of structural complexity (Gowers norm).
 
 
+use moma_simulation_engine::control::PidController;
+use moma_simulation_engine::gowers::{path_to_turning_sequence, u2_norm};
 use moma_simulation_engine::network_graph::Graph;
 use moma_simulation_engine::grid::Point;
-use rustfft::{num_complex::Complex as FftComplex, FftPlanner};
+use rustfft::num_complex::Complex as FftComplex;
 use std::collections::BTreeMap;
 
 // --- Simulation Parameters ---
 const SIMULATION_STEPS: u32 = 10;
 
-<<<<<<< HEAD
-=======
-
-
-
-// my original path analyzer.
->>>>>>> a2a8cb71ed35c9964da56dd971fb7d16ec94d9f7
-fn path_to_complex_sequence_fft(path: &[Point]) -> Vec<FftComplex<f64>> {
-    if path.len() < 2 { return Vec::new(); }
-    let mut complex_sequence = Vec::new();
-    for i in 1..path.len() {
-        let p1 = path[i-1];
-        let p2 = path[i];
-        let dx = p2.x as i64 - p1.x as i64;
-        let dy = p2.y as i64 - p1.y as i64;
-        let angle = (dy as f64).atan2(dx as f64);
-        complex_sequence.push(FftComplex::new(angle.cos(), angle.sin()));
-    }
-    complex_sequence
-}
-
 fn main() {
     println!("--- MOMA Network Flow Manager ---");
     
     // --- Controller Tuning ---
-<<<<<<< HEAD
-    const TARGET_GOWERS_NORM: f64 = 0.85;    
-=======
     const TARGET_GOWERS_NORM: f64 = 0.85;
-    
-    // We can use a smaller gain now that fractional penalties work.
->>>>>>> a2a8cb71ed35c9964da56dd971fb7d16ec94d9f7
     const COST_ADJUSTMENT_GAIN: f64 = 50.0; // Changed from 5.0
     const COST_DECAY_RATE: f64 = 0.95; // Decay rate is now more effective
 
     let source = Point { x: 0, y: 1 };
     let sink = Point { x: 3, y: 1 };
     let mut graph = create_diamond_graph(source, sink);
+    // A norm above the target should raise the cost, hence the negative gain.
+    let mut controller = PidController::proportional(-COST_ADJUSTMENT_GAIN);
 
     for i in 0..SIMULATION_STEPS {
         println!("\n--- Step {} ---", i + 1);
 
         // --- Apply Cost Decay ---
+        // `adj` holds only the edges we added; residual moves are derived from their flow.
         for edges in graph.adj.values_mut() {
             for edge in edges {
                 // Cost decays towards the base cost of 1.0
@@ -68,16 +46,14 @@ fn main() {
         println!("  - Flow Routed This Step: {}", flow_this_step);
 
         if let Some(path) = path_opt {
-            let mut complex_sequence = path_to_complex_sequence_fft(&path);
-            let gowers_norm = calculate_u2_norm_fft(&mut complex_sequence);
+            let gowers_norm = u2_norm(&path_to_turning_sequence(&path));
             println!("  - Gowers Norm of Path: {:.4}", gowers_norm);
 
             let error = gowers_norm - TARGET_GOWERS_NORM;
             println!("  - Norm Error: {:.4}", error);
 
-            if error > 0.0 {
-                // The crucial fix: NO integer cast.
-                let adjustment = error * COST_ADJUSTMENT_GAIN; 
+            let adjustment = controller.update(gowers_norm, TARGET_GOWERS_NORM);
+            if adjustment > 0.0 {
                 println!("  - Policy: Path is too simple. Applying cost penalty of {:.3}.", adjustment);
                 
                 let first_edge_from = path[0];
@@ -131,17 +107,6 @@ fn _flow_to_sequence(graph: &Graph) -> Vec<f64> {
     sequence
 }
 
-// --- Gowers Norm Calculation Functions (Unchanged) ---
-fn calculate_u2_norm_fft(sequence: &mut Vec<FftComplex<f64>>) -> f64 {
-    let n = sequence.len();
-    if n == 0 { return 0.0; }
-    let mut planner = FftPlanner::new();
-    let fft = planner.plan_fft_forward(n);
-    fft.process(sequence);
-    let sum_of_magnitudes_pow4: f64 = sequence.iter().map(|c| c.norm_sqr().powi(2)).sum();
-    (sum_of_magnitudes_pow4 / (n as f64).powi(4)).powf(1.0 / 4.0)
-}
-
 fn _values_to_complex_sequence_fft(values: &[f64]) -> Vec<FftComplex<f64>> {
     // This function is now more generic: it converts any sequence of f64 values.
     // We'll normalize by the max value to keep numbers between 0 and 1.
@@ -0,0 +1,128 @@
+//! # Analysis Module
+//
+// Provides offline tools for studying how pathfinding policies shape the
+// structure of the paths they produce, so controller parameters can be tuned
+// without running a live feedback loop.
+
+use crate::gowers::{path_to_turning_sequence, u2_norm};
+use crate::grid::{Grid, Point};
+use crate::pathfinding::manhattan_distance;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+type Cost = u32;
+
+/// The four moves available on the grid: Left, Right, Up, Down.
+const DIRECTIONS: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+/// Builds a cost profile relating a turn penalty to the structure of the
+/// resulting path.
+///
+/// For each penalty, a turn-penalized A* search is run from `start` to `goal`
+/// and the Gowers U2 norm of the path's heading sequence is measured. Users can
+/// then pick the penalty that achieves a target norm.
+///
+/// # Returns
+/// A `(penalty, u2_norm)` pair for each penalty, in the order given. Penalties
+/// for which no path exists are left out.
+pub fn cost_profile(grid: &Grid, start: Point, goal: Point, penalties: &[u32]) -> Vec<(u32, f64)> {
+    penalties
+        .iter()
+        .filter_map(|&penalty| {
+            let path = a_star_turn_penalized(grid, start, goal, penalty)?;
+            Some((penalty, u2_norm(&path_to_turning_sequence(&path))))
+        })
+        .collect()
+}
+
+/// A* over the grid where every change of direction costs `turn_penalty` on
+/// top of the usual cost of 1 per step.
+///
+/// The search state is a point plus the direction used to reach it, so the
+/// penalty is applied exactly rather than estimated from the first route found.
+fn a_star_turn_penalized(grid: &Grid, start: Point, goal: Point, turn_penalty: Cost) -> Option<Vec<Point>> {
+    // `None` is the direction of the start state, which has not moved yet.
+    type State = (Point, Option<usize>);
+
+    let mut frontier = BinaryHeap::new();
+    let mut came_from: HashMap<State, State> = HashMap::new();
+    let mut cost_so_far: HashMap<State, Cost> = HashMap::new();
+
+    let start_state: State = (start, None);
+    cost_so_far.insert(start_state, 0);
+    frontier.push(Reverse((manhattan_distance(start, goal), 0, start_state)));
+
+    while let Some(Reverse((_, cost, current))) = frontier.pop() {
+        if cost > cost_so_far[&current] {
+            continue; // A cheaper route to this state has already been expanded.
+        }
+
+        let (point, direction) = current;
+        if point == goal {
+            let mut path = vec![goal];
+            let mut state = current;
+            while let Some(&prev) = came_from.get(&state) {
+                path.push(prev.0);
+                state = prev;
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        for next_point in grid.neighbors(point) {
+            let step = (next_point.x as isize - point.x as isize, next_point.y as isize - point.y as isize);
+            let next_direction = DIRECTIONS.iter().position(|&d| d == step);
+            let turned = direction.is_some() && direction != next_direction;
+
+            let new_cost = cost + 1 + if turned { turn_penalty } else { 0 };
+            let next: State = (next_point, next_direction);
+
+            if cost_so_far.get(&next).is_none_or(|&c| new_cost < c) {
+                cost_so_far.insert(next, new_cost);
+                frontier.push(Reverse((new_cost + manhattan_distance(next_point, goal), new_cost, next)));
+                came_from.insert(next, current);
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pathfinding::turn_count;
+
+    /// A grid where the shortest route from the top-left corner to (3, 3) is a
+    /// staircase, while a route four steps longer takes only two turns.
+    fn staircase_or_detour() -> Grid {
+        Grid::from_ascii("......\n#..##.\n##..#.\n###...").unwrap()
+    }
+
+    #[test]
+    fn a_large_turn_penalty_trades_length_for_fewer_turns() {
+        let grid = staircase_or_detour();
+        let (start, goal) = (Point::new(0, 0), Point::new(3, 3));
+
+        let shortest = a_star_turn_penalized(&grid, start, goal, 0).unwrap();
+        assert_eq!(shortest.len(), 7);
+        assert!(turn_count(&shortest) >= 3);
+
+        let straightest = a_star_turn_penalized(&grid, start, goal, 10).unwrap();
+        assert_eq!(straightest.len(), 11);
+        assert_eq!(turn_count(&straightest), 2);
+    }
+
+    #[test]
+    fn cost_profile_has_one_entry_per_reachable_penalty() {
+        let grid = staircase_or_detour();
+        let profile = cost_profile(&grid, Point::new(0, 0), Point::new(3, 3), &[0, 1, 10]);
+        let penalties: Vec<u32> = profile.iter().map(|&(penalty, _)| penalty).collect();
+        assert_eq!(penalties, [0, 1, 10]);
+        assert!(profile.iter().all(|&(_, norm)| norm > 0.0 && norm <= 1.0 + 1e-9));
+        // The two routes differ, and so do their norms.
+        assert!((profile[0].1 - profile[2].1).abs() > 1e-6);
+
+        assert!(cost_profile(&grid, Point::new(0, 0), Point::new(0, 3), &[0, 10]).is_empty());
+    }
+}
@@ -2,15 +2,123 @@
 //!
 //! Provides a 2D cellular automaton that uses a MOMA ring as its update rule.
 
+use crate::grid::{reflect, Point};
+use crate::history::History;
 use moma::core::{MomaRing, OriginStrategy};
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::HashMap;
+use std::fmt;
+
+/// The characters `render` uses, from the lowest cell state to the highest.
+pub const DEFAULT_RAMP: [char; 10] = [' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'];
+
+/// Errors reported when building an automaton from an explicit state.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AutomatonError {
+    /// The supplied state does not have one value per cell.
+    StateLengthMismatch { expected: usize, actual: usize },
+}
+
+impl fmt::Display for AutomatonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AutomatonError::StateLengthMismatch { expected, actual } => {
+                write!(f, "state has {} cells but the automaton needs {}", actual, expected)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AutomatonError {}
+
+/// A representation of a single automaton cell.
+///
+/// The MOMA update rule works on `u64` residues, so any cell type can drive an
+/// automaton as long as it converts to and from a residue.
+pub trait CellState: Copy {
+    /// Converts the cell into the residue the MOMA ring operates on.
+    fn to_residue(self) -> u64;
+    /// Builds a cell from a residue produced by the MOMA ring.
+    fn from_residue(residue: u64) -> Self;
+}
+
+impl CellState for u64 {
+    fn to_residue(self) -> u64 {
+        self
+    }
+
+    fn from_residue(residue: u64) -> Self {
+        residue
+    }
+}
+
+/// A two-state cell: any nonzero residue is alive.
+impl CellState for bool {
+    fn to_residue(self) -> u64 {
+        self as u64
+    }
+
+    fn from_residue(residue: u64) -> Self {
+        residue != 0
+    }
+}
+
+/// How an automaton treats neighbors that fall outside its edges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BoundaryCondition {
+    /// Edges wrap around to the opposite side.
+    #[default]
+    Toroidal,
+    /// Out-of-bounds neighbors contribute the given constant value.
+    Fixed(u64),
+    /// Out-of-bounds neighbors mirror the in-bounds cell across the edge.
+    Reflecting,
+}
+
+impl BoundaryCondition {
+    /// Returns the value of the neighbor at `(x, y)`, which may be at most one
+    /// step outside the `width` x `height` grid stored row by row in `state`.
+    fn neighbor<C: CellState>(self, state: &[C], width: usize, height: usize, x: isize, y: isize) -> u64 {
+        match (self.resolve(x, width), self.resolve(y, height)) {
+            (Some(nx), Some(ny)) => state[ny * width + nx].to_residue(),
+            _ => match self {
+                BoundaryCondition::Fixed(value) => value,
+                _ => unreachable!("only a fixed boundary leaves a neighbor unresolved"),
+            },
+        }
+    }
+
+    /// Maps a coordinate back into `0..len`, or returns `None` if it lies on a
+    /// fixed boundary.
+    fn resolve(self, coord: isize, len: usize) -> Option<usize> {
+        if (0..len as isize).contains(&coord) {
+            return Some(coord as usize);
+        }
+        match self {
+            BoundaryCondition::Toroidal => Some(coord.rem_euclid(len as isize) as usize),
+            BoundaryCondition::Fixed(_) => None,
+            BoundaryCondition::Reflecting => Some(reflect(coord, len)),
+        }
+    }
+}
 
 /// Represents a 1D Cellular Automaton whose rules are governed by MOMA.
-pub struct CellularAutomaton<S: OriginStrategy> {
+///
+/// Cells are `u64` residues by default; any other `CellState` can be used by
+/// building the automaton with `from_cells`.
+pub struct CellularAutomaton<S: OriginStrategy, C: CellState = u64> {
     /// The current state of all cells.
-    state: Vec<u64>,
+    state: Vec<C>,
     /// The width of the automaton.
     width: usize,
+    /// The modulus of the MOMA ring, which bounds the state of a cell.
+    modulus: u64,
+    /// How the cells at either end see their missing neighbor.
+    boundary: BoundaryCondition,
+    /// The recorded generations, if recording has been started.
+    history: Option<History>,
     /// The MOMA ring that defines the update rules.
     ring: MomaRing<S>,
 }
@@ -23,120 +131,1082 @@ impl<S: OriginStrategy + Clone> CellularAutomaton<S> {
     /// * `modulus` - The modulus for the MOMA ring. This also defines the max state of a cell.
     /// * `strategy` - The MOMA strategy to use for the update rules.
     pub fn new(width: usize, modulus: u64, strategy: S) -> Self {
-        let mut rng = rand::rng();
-        let state = (0..width).map(|_| rng.random_range(0..modulus)).collect();
+        Self::with_rng(width, modulus, strategy, &mut rand::rng())
+    }
+
+    /// Creates a new CellularAutomaton whose initial state is drawn from a
+    /// `ChaCha8Rng` seeded with `seed`, so the same seed always produces the
+    /// same starting cells.
+    pub fn new_seeded(width: usize, modulus: u64, strategy: S, seed: u64) -> Self {
+        Self::with_rng(width, modulus, strategy, &mut ChaCha8Rng::seed_from_u64(seed))
+    }
+
+    /// Creates a new CellularAutomaton with an explicit initial state, such as
+    /// a single live cell.
+    ///
+    /// # Errors
+    /// Returns `AutomatonError::StateLengthMismatch` if `state` does not hold
+    /// exactly `width` cells.
+    pub fn from_state(width: usize, modulus: u64, strategy: S, state: Vec<u64>) -> Result<Self, AutomatonError> {
+        Self::from_cells(width, modulus, strategy, state)
+    }
+}
+
+impl<S: OriginStrategy + Clone, C: CellState> CellularAutomaton<S, C> {
+    /// Creates a new CellularAutomaton from cells of any `CellState` type.
+    ///
+    /// # Errors
+    /// Returns `AutomatonError::StateLengthMismatch` if `cells` does not hold
+    /// exactly `width` cells.
+    pub fn from_cells(width: usize, modulus: u64, strategy: S, cells: Vec<C>) -> Result<Self, AutomatonError> {
+        if cells.len() != width {
+            return Err(AutomatonError::StateLengthMismatch { expected: width, actual: cells.len() });
+        }
+
+        Ok(Self {
+            state: cells,
+            width,
+            modulus,
+            boundary: BoundaryCondition::default(),
+            history: None,
+            ring: MomaRing::new(modulus, strategy),
+        })
+    }
+
+    /// Creates a new CellularAutomaton with its initial state drawn from `rng`.
+    fn with_rng<R: Rng + ?Sized>(width: usize, modulus: u64, strategy: S, rng: &mut R) -> Self {
+        let state = (0..width).map(|_| C::from_residue(rng.random_range(0..modulus))).collect();
 
         Self {
             state,
             width,
+            modulus,
+            boundary: BoundaryCondition::default(),
+            history: None,
             ring: MomaRing::new(modulus, strategy),
         }
     }
 
+    /// Sets how the automaton treats the neighbors beyond its ends. The
+    /// default is `BoundaryCondition::Toroidal`.
+    pub fn with_boundary(mut self, boundary: BoundaryCondition) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    /// Returns the state of the cell at `x`.
+    ///
+    /// # Panics
+    /// Panics if `x` is out of bounds.
+    pub fn get(&self, x: usize) -> C {
+        assert!(x < self.width, "Cell {} is outside an automaton of width {}.", x, self.width);
+        self.state[x]
+    }
+
+    /// Sets the state of the cell at `x`.
+    ///
+    /// # Panics
+    /// Panics if `x` is out of bounds.
+    pub fn set(&mut self, x: usize, value: C) {
+        assert!(x < self.width, "Cell {} is outside an automaton of width {}.", x, self.width);
+        self.state[x] = value;
+    }
+
+    /// Starts recording every generation, beginning with the current state.
+    /// Any previous recording is discarded.
+    pub fn start_recording(&mut self) {
+        self.history = Some(History::new(&residues(&self.state)));
+    }
+
+    /// Stops recording and returns the generations recorded so far.
+    pub fn stop_recording(&mut self) -> Option<History> {
+        self.history.take()
+    }
+
+    /// Returns the generations recorded so far, if recording is active.
+    pub fn history(&self) -> Option<&History> {
+        self.history.as_ref()
+    }
+
     /// Advances the simulation by one time step.
     ///
     /// It calculates the next state for each cell based on its current state and the
     /// state of its immediate neighbors, using the MOMA update rule.
     pub fn step(&mut self) {
+        let next_state = self.next_state(|center, context| self.ring.residue(center, context));
+        self.advance_to(next_state);
+    }
+
+    /// Tabulates the MOMA update rule: `table[center][context]` is the new
+    /// value of a cell holding `center` whose neighbors sum to `context`.
+    ///
+    /// Rows are not indexed by `context % modulus`: the moving origin depends
+    /// on the context's full value, so two contexts that agree modulo the
+    /// modulus can give different results, and a reduced table could not
+    /// reproduce `step`. Instead, since two neighbors below the modulus sum to
+    /// at most `2 * (modulus - 1)`, each row has `2 * modulus - 1` entries and
+    /// the table covers every context a step can produce once all cells hold
+    /// residues. It grows with the square of the modulus, so it suits small
+    /// rings best.
+    pub fn rule_table(&self) -> Vec<Vec<u64>> {
+        let contexts = (2 * self.modulus).saturating_sub(1);
+        (0..self.modulus)
+            .map(|center| (0..contexts).map(|context| self.ring.residue(center, context)).collect())
+            .collect()
+    }
+
+    /// Advances the simulation by one time step like `step`, reading the new
+    /// values from a table built by `rule_table` instead of evaluating the
+    /// ring for every cell.
+    ///
+    /// Inputs outside the table, such as an initial state above the modulus,
+    /// fall back to the ring.
+    pub fn step_with_table(&mut self, table: &[Vec<u64>]) {
+        let next_state = self.next_state(|center, context| {
+            let entry = usize::try_from(center).ok().zip(usize::try_from(context).ok());
+            match entry.and_then(|(center, context)| table.get(center)?.get(context)) {
+                Some(&value) => value,
+                None => self.ring.residue(center, context),
+            }
+        });
+        self.advance_to(next_state);
+    }
+
+    /// Computes the next state, passing each cell's value and the sum of its
+    /// neighbors to `rule`.
+    fn next_state(&self, rule: impl Fn(u64, u64) -> u64) -> Vec<C> {
         let mut next_state = self.state.clone();
 
         for i in 0..self.width {
-            // Get the states of the left, center, and right cells, wrapping around the edges.
-            let left = self.state[(i + self.width - 1) % self.width];
-            let center = self.state[i];
-            let right = self.state[(i + 1) % self.width];
+            // Get the states of the left, center, and right cells, resolving the
+            // edges according to the boundary condition.
+            let left = self.boundary.neighbor(&self.state, self.width, 1, i as isize - 1, 0);
+            let center = self.state[i].to_residue();
+            let right = self.boundary.neighbor(&self.state, self.width, 1, i as isize + 1, 0);
 
             // The MOMA Update Rule:
             // The "context" for the moving origin is the sum of the neighbors.
             // This simulates an environmental influence on the cell's evolution.
             let context = left.wrapping_add(right);
-            let new_value = self.ring.residue(center, context);
+            let new_value = rule(center, context);
 
-            next_state[i] = new_value;
+            next_state[i] = C::from_residue(new_value);
         }
 
+        next_state
+    }
+
+    /// Replaces the state with `next_state` and records it if recording.
+    fn advance_to(&mut self, next_state: Vec<C>) {
         self.state = next_state;
+
+        if let Some(history) = &mut self.history {
+            history.record(&residues(&self.state));
+        }
+    }
+
+    /// Advances the simulation by `n` time steps.
+    pub fn step_n(&mut self, n: usize) {
+        for _ in 0..n {
+            self.step();
+        }
+    }
+
+    /// Advances the simulation by one time step, then calls `observer` with
+    /// the new state.
+    pub fn step_with_observer(&mut self, observer: &mut impl FnMut(&[C])) {
+        self.step();
+        observer(&self.state);
+    }
+
+    /// Advances the simulation by `n` time steps, calling `observer` with the
+    /// new state after each one.
+    pub fn step_n_with_observer(&mut self, n: usize, observer: &mut impl FnMut(&[C])) {
+        for _ in 0..n {
+            self.step_with_observer(observer);
+        }
+    }
+
+    /// Steps the simulation until its state repeats, for at most `max_steps`
+    /// steps.
+    ///
+    /// # Returns
+    /// The generation index (0 being the state before the call) of the first
+    /// state that recurs: the fixed point, or the start of the cycle. Returns
+    /// `None` if no state repeats within `max_steps`.
+    pub fn run_until_stable(&mut self, max_steps: usize) -> Option<usize> {
+        let initial = residues(&self.state);
+        first_repeat(initial, max_steps, || {
+            self.step();
+            residues(&self.state)
+        })
+    }
+
+    /// Returns the Shannon entropy, in bits, of the current distribution of
+    /// cell values. A state where every cell holds the same value has entropy 0.
+    pub fn entropy(&self) -> f64 {
+        shannon_entropy(&residues(&self.state))
+    }
+
+    /// Returns the fraction of cells whose value differs from `prev`, a
+    /// previous state of this automaton.
+    ///
+    /// # Panics
+    /// Panics if `prev` does not have one value per cell.
+    pub fn activity(&self, prev: &[C]) -> f64 {
+        activity(&self.state, prev)
+    }
+
+    /// Advances the simulation by one time step and returns the Shannon entropy
+    /// (in bits) of the new state's value histogram.
+    ///
+    /// A falling entropy means the pattern is settling into order; a value near
+    /// `log2(modulus)` means the cells are spread evenly across all states.
+    pub fn step_with_entropy(&mut self) -> f64 {
+        self.step();
+        self.entropy()
     }
 
     /// Renders the current state of the automaton as a string for display.
     ///
-    /// It maps each cell's numerical state to a character for visualization.
+    /// It maps each cell's numerical state to a character of `DEFAULT_RAMP`
+    /// for visualization.
     pub fn render(&self) -> String {
-        self.state
-            .iter()
-            .map(|&val| {
-                // Map the cell's value to a character.
-                // This creates a simple grayscale-like visualization.
-                match val % 10 {
-                    0 => ' ',
-                    1 => '.',
-                    2 => ':',
-                    3 => '-',
-                    4 => '=',
-                    5 => '+',
-                    6 => '*',
-                    7 => '#',
-                    8 => '%',
-                    _ => '@',
-                }
-            })
-            .collect()
+        self.render_with(&DEFAULT_RAMP)
+    }
+
+    /// Renders the current state using a custom character ramp, ordered from
+    /// the lowest state to the highest. The ramp is stretched across the
+    /// automaton's modulus, so every state maps to a character whatever the
+    /// ramp's length.
+    ///
+    /// # Panics
+    /// Panics if `ramp` is empty.
+    pub fn render_with(&self, ramp: &[char]) -> String {
+        self.state.iter().map(|&val| ramp_char(val.to_residue(), self.modulus, ramp)).collect()
+    }
+
+    /// Runs the automaton and returns its spacetime diagram: one row of RGBA
+    /// pixels per generation, oldest at the top, shaded from cool blue (0) to
+    /// warm red (`modulus - 1`).
+    ///
+    /// The first row is the current state, and the automaton is advanced
+    /// `steps - 1` times to produce the rest, so the result has `steps` rows
+    /// of `width` pixels.
+    pub fn spacetime_image(&mut self, steps: usize) -> Vec<Vec<[u8; 4]>> {
+        if steps == 0 {
+            return Vec::new();
+        }
+
+        let mut generations = History::new(&residues(&self.state));
+        for _ in 1..steps {
+            self.step();
+            generations.record(&residues(&self.state));
+        }
+        generations.to_image_rows(self.modulus)
+    }
+}
+
+/// Maps a cell's value to a character of `ramp`, scaling `0..modulus` across
+/// the whole ramp. This creates a simple grayscale-like visualization.
+fn ramp_char(val: u64, modulus: u64, ramp: &[char]) -> char {
+    assert!(!ramp.is_empty(), "The render ramp must contain at least one character.");
+    let index = (val as u128 * ramp.len() as u128 / modulus.max(1) as u128) as usize;
+    ramp[index.min(ramp.len() - 1)]
+}
+
+/// Advances a simulation from `initial` with `step` until it yields a state seen before,
+/// returning the generation index at which that state first appeared.
+fn first_repeat(initial: Vec<u64>, max_steps: usize, mut step: impl FnMut() -> Vec<u64>) -> Option<usize> {
+    let mut seen: HashMap<Vec<u64>, usize> = HashMap::new();
+    seen.insert(initial, 0);
+
+    for generation in 1..=max_steps {
+        let state = step();
+        if let Some(&first) = seen.get(&state) {
+            return Some(first);
+        }
+        seen.insert(state, generation);
+    }
+    None
+}
+
+/// Converts cells into the residues the MOMA ring operates on.
+fn residues<C: CellState>(cells: &[C]) -> Vec<u64> {
+    cells.iter().map(|&cell| cell.to_residue()).collect()
+}
+
+/// Calculates the fraction of cells that differ between two states.
+fn activity<C: CellState>(state: &[C], prev: &[C]) -> f64 {
+    assert_eq!(prev.len(), state.len(), "Previous state does not match the automaton's size.");
+    if state.is_empty() {
+        return 0.0;
     }
+
+    let changed = state.iter().zip(prev).filter(|(a, b)| a.to_residue() != b.to_residue()).count();
+    changed as f64 / state.len() as f64
 }
 
+/// Calculates the Shannon entropy, in bits, of the distribution of cell values.
+fn shannon_entropy(state: &[u64]) -> f64 {
+    if state.is_empty() {
+        return 0.0;
+    }
+
+    let mut counts: HashMap<u64, usize> = HashMap::new();
+    for &value in state {
+        *counts.entry(value).or_insert(0) += 1;
+    }
+
+    let total = state.len() as f64;
+    counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / total;
+            p * (1.0 / p).log2()
+        })
+        .sum()
+}
+
+/// Yields the odd primes in increasing order: 3, 5, 7, 11, ...
+fn odd_primes() -> impl Iterator<Item = u64> {
+    (3u64..).step_by(2).filter(|&n| (3..).step_by(2).take_while(|d| d * d <= n).all(|d| n % d != 0))
+}
 
 /// Represents a 2D Cellular Automaton whose rules are governed by MOMA.
-pub struct Moma2dAutomaton<S: OriginStrategy> {
+///
+/// Cells are `u64` residues by default; any other `CellState` can be used by
+/// building the automaton with `from_cells`.
+pub struct Moma2dAutomaton<S: OriginStrategy, C: CellState = u64> {
     /// The current state of all cells, stored in a flat vector.
-    pub state: Vec<u64>,
+    pub state: Vec<C>,
     /// The width of the automaton grid.
     pub width: usize,
     /// The height of the automaton grid.
     pub height: usize,
+    /// The modulus of the MOMA ring, i.e. the number of states per cell.
+    modulus: u64,
+    /// How cells on the edges see their out-of-bounds neighbors.
+    boundary: BoundaryCondition,
+    /// The recorded generations, if recording has been started.
+    history: Option<History>,
     /// The MOMA ring that defines the update rules.
     ring: MomaRing<S>,
 }
 
+/// A saved copy of a whole simulation session: the 2D automaton state plus the
+/// current path and controller weight from the interactive pathfinding loop.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimulationSnapshot {
+    pub state: Vec<u64>,
+    pub width: usize,
+    pub height: usize,
+    pub modulus: u64,
+    pub path: Option<Vec<Point>>,
+    pub structure_penalty_weight: f64,
+}
+
 impl<S: OriginStrategy + Clone> Moma2dAutomaton<S> {
     /// Creates a new 2D Automaton with a random initial state.
     pub fn new(width: usize, height: usize, modulus: u64, strategy: S) -> Self {
-        let mut rng = rand::rng();
+        Self::with_rng(width, height, modulus, strategy, &mut rand::rng())
+    }
+
+    /// Creates a new 2D Automaton whose initial state is drawn from a
+    /// `ChaCha8Rng` seeded with `seed`, so the same seed always produces the
+    /// same starting grid.
+    pub fn new_seeded(width: usize, height: usize, modulus: u64, strategy: S, seed: u64) -> Self {
+        Self::with_rng(width, height, modulus, strategy, &mut ChaCha8Rng::seed_from_u64(seed))
+    }
+
+    /// Creates a new 2D Automaton with an explicit initial state, stored row
+    /// by row (`y * width + x`).
+    ///
+    /// # Errors
+    /// Returns `AutomatonError::StateLengthMismatch` if `state` does not hold
+    /// exactly `width * height` cells.
+    pub fn from_state(width: usize, height: usize, modulus: u64, strategy: S, state: Vec<u64>) -> Result<Self, AutomatonError> {
+        Self::from_cells(width, height, modulus, strategy, state)
+    }
+
+    /// Creates a new 2D Automaton that is zero everywhere except at
+    /// `num_seeds` cells placed by `seed_ring`, giving structured and
+    /// reproducible initial conditions instead of uniform noise.
+    ///
+    /// Each seed reads the ring's signature at three successive primes,
+    /// starting from 3: the first gives its column, the second its row, and
+    /// the third its value in `1..modulus`. Seeds that land on the same cell
+    /// overwrite each other, so fewer than `num_seeds` cells may be set.
+    ///
+    /// # Panics
+    /// Panics if `modulus` is below 2, since no nonzero value would fit, or
+    /// if the grid has no cells while `num_seeds` is not zero.
+    pub fn new_moma_seeded<T: OriginStrategy>(
+        width: usize,
+        height: usize,
+        modulus: u64,
+        strategy: S,
+        seed_ring: MomaRing<T>,
+        num_seeds: usize,
+    ) -> Self {
+        assert!(modulus >= 2, "A modulus of at least 2 is needed for nonzero seeds.");
+        assert!(num_seeds == 0 || width * height > 0, "Cannot place seeds on an empty grid.");
+
+        let mut state = vec![0; width * height];
+        let mut primes = odd_primes();
+        let mut next_signature = || seed_ring.signature(primes.next().expect("there are infinitely many primes"));
+        for _ in 0..num_seeds {
+            let x = (next_signature() % width as u64) as usize;
+            let y = (next_signature() % height as u64) as usize;
+            state[y * width + x] = 1 + next_signature() % (modulus - 1);
+        }
+
+        Self {
+            state,
+            width,
+            height,
+            modulus,
+            boundary: BoundaryCondition::default(),
+            history: None,
+            ring: MomaRing::new(modulus, strategy),
+        }
+    }
+}
+
+impl<S: OriginStrategy + Clone, C: CellState> Moma2dAutomaton<S, C> {
+    /// Creates a new 2D Automaton from cells of any `CellState` type, stored
+    /// row by row (`y * width + x`).
+    ///
+    /// # Errors
+    /// Returns `AutomatonError::StateLengthMismatch` if `cells` does not hold
+    /// exactly `width * height` cells.
+    pub fn from_cells(width: usize, height: usize, modulus: u64, strategy: S, cells: Vec<C>) -> Result<Self, AutomatonError> {
+        if cells.len() != width * height {
+            return Err(AutomatonError::StateLengthMismatch { expected: width * height, actual: cells.len() });
+        }
+
+        Ok(Self {
+            state: cells,
+            width,
+            height,
+            modulus,
+            boundary: BoundaryCondition::default(),
+            history: None,
+            ring: MomaRing::new(modulus, strategy),
+        })
+    }
+
+    /// Creates a new 2D Automaton with its initial state drawn from `rng`.
+    fn with_rng<R: Rng + ?Sized>(width: usize, height: usize, modulus: u64, strategy: S, rng: &mut R) -> Self {
         let size = width * height;
-        let state = (0..size).map(|_| rng.random_range(0..modulus)).collect();
+        let state = (0..size).map(|_| C::from_residue(rng.random_range(0..modulus))).collect();
 
         Self {
             state,
             width,
             height,
+            modulus,
+            boundary: BoundaryCondition::default(),
+            history: None,
             ring: MomaRing::new(modulus, strategy),
         }
     }
 
+    /// Sets how the automaton treats the neighbors beyond its edges. The
+    /// default is `BoundaryCondition::Toroidal`.
+    pub fn with_boundary(mut self, boundary: BoundaryCondition) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    /// Returns the state of the cell at `(x, y)`.
+    ///
+    /// # Panics
+    /// Panics if `(x, y)` is out of bounds.
+    pub fn get(&self, x: usize, y: usize) -> C {
+        assert!(x < self.width && y < self.height, "Cell ({}, {}) is outside a {}x{} automaton.", x, y, self.width, self.height);
+        self.state[y * self.width + x]
+    }
+
+    /// Sets the state of the cell at `(x, y)`.
+    ///
+    /// # Panics
+    /// Panics if `(x, y)` is out of bounds.
+    pub fn set(&mut self, x: usize, y: usize, value: C) {
+        assert!(x < self.width && y < self.height, "Cell ({}, {}) is outside a {}x{} automaton.", x, y, self.width, self.height);
+        self.state[y * self.width + x] = value;
+    }
+
+    /// Returns the Shannon entropy, in bits, of the current distribution of
+    /// cell values. A state where every cell holds the same value has entropy 0.
+    pub fn entropy(&self) -> f64 {
+        shannon_entropy(&residues(&self.state))
+    }
+
+    /// Returns the fraction of cells whose value differs from `prev`, a
+    /// previous state of this automaton.
+    ///
+    /// # Panics
+    /// Panics if `prev` does not have one value per cell.
+    pub fn activity(&self, prev: &[C]) -> f64 {
+        activity(&self.state, prev)
+    }
+
+    /// Renders the current state of the automaton as a string for display,
+    /// one line per row, using the same characters as the 1D automaton.
+    pub fn render(&self) -> String {
+        self.render_with(&DEFAULT_RAMP)
+    }
+
+    /// Renders the current state one line per row using a custom character
+    /// ramp, stretched across the modulus as in `CellularAutomaton::render_with`.
+    ///
+    /// # Panics
+    /// Panics if `ramp` is empty.
+    pub fn render_with(&self, ramp: &[char]) -> String {
+        let mut output = String::with_capacity((self.width + 1) * self.height);
+        for row in self.state.chunks(self.width) {
+            output.extend(row.iter().map(|&val| ramp_char(val.to_residue(), self.modulus, ramp)));
+            output.push('\n');
+        }
+        output
+    }
+
+    /// Returns a copy of the state as a list of rows, indexed `[y][x]`.
+    pub fn to_grid(&self) -> Vec<Vec<C>> {
+        self.state.chunks(self.width).map(|row| row.to_vec()).collect()
+    }
+
+    /// Captures the automaton state together with the session's path and
+    /// controller weight.
+    pub fn snapshot(&self, path: Option<Vec<Point>>, structure_penalty_weight: f64) -> SimulationSnapshot {
+        SimulationSnapshot {
+            state: residues(&self.state),
+            width: self.width,
+            height: self.height,
+            modulus: self.modulus,
+            path,
+            structure_penalty_weight,
+        }
+    }
+
+    /// Restores the automaton state from a snapshot. The path and controller
+    /// weight are left for the caller to read back from the snapshot.
+    ///
+    /// # Panics
+    /// Panics if the snapshot was taken with a different modulus or its state
+    /// does not match its dimensions.
+    pub fn restore_snapshot(&mut self, snapshot: &SimulationSnapshot) {
+        assert_eq!(snapshot.modulus, self.modulus, "Snapshot modulus does not match the automaton.");
+        assert_eq!(snapshot.state.len(), snapshot.width * snapshot.height, "Snapshot state does not match its dimensions.");
+
+        self.state = snapshot.state.iter().map(|&residue| C::from_residue(residue)).collect();
+        self.width = snapshot.width;
+        self.height = snapshot.height;
+    }
+
+    /// Starts recording every generation, beginning with the current state.
+    /// Any previous recording is discarded.
+    pub fn start_recording(&mut self) {
+        self.history = Some(History::new(&residues(&self.state)));
+    }
+
+    /// Stops recording and returns the generations recorded so far.
+    pub fn stop_recording(&mut self) -> Option<History> {
+        self.history.take()
+    }
+
+    /// Returns the generations recorded so far, if recording is active.
+    pub fn history(&self) -> Option<&History> {
+        self.history.as_ref()
+    }
+
     /// Advances the simulation by one time step.
     pub fn step(&mut self) {
         let mut next_state = self.state.clone();
 
         for y in 0..self.height {
             for x in 0..self.width {
-                // Get the sum of the Moore neighborhood (8 neighbors), wrapping around the edges.
+                // Get the sum of the Moore neighborhood (8 neighbors), resolving the
+                // edges according to the boundary condition.
                 let mut neighbor_sum = 0;
                 for dy in [-1, 0, 1] {
                     for dx in [-1, 0, 1] {
                         if dx == 0 && dy == 0 { continue; }
-                        let nx = (x as isize + dx + self.width as isize) as usize % self.width;
-                        let ny = (y as isize + dy + self.height as isize) as usize % self.height;
-                        neighbor_sum += self.state[ny * self.width + nx];
+                        let nx = x as isize + dx;
+                        let ny = y as isize + dy;
+                        neighbor_sum += self.boundary.neighbor(&self.state, self.width, self.height, nx, ny);
                     }
                 }
 
                 let current_index = y * self.width + x;
-                let center_value = self.state[current_index];
+                let center_value = self.state[current_index].to_residue();
 
                 // The MOMA Update Rule: The cell's next state is a function of its
                 // current state and the influence of its neighbors.
-                next_state[current_index] = self.ring.residue(center_value, neighbor_sum);
+                next_state[current_index] = C::from_residue(self.ring.residue(center_value, neighbor_sum));
             }
         }
         self.state = next_state;
+
+        if let Some(history) = &mut self.history {
+            history.record(&residues(&self.state));
+        }
+    }
+
+    /// Advances the simulation by `n` time steps.
+    pub fn step_n(&mut self, n: usize) {
+        for _ in 0..n {
+            self.step();
+        }
+    }
+
+    /// Advances the simulation by one time step, then calls `observer` with
+    /// the new state.
+    pub fn step_with_observer(&mut self, observer: &mut impl FnMut(&[C])) {
+        self.step();
+        observer(&self.state);
+    }
+
+    /// Advances the simulation by `n` time steps, calling `observer` with the
+    /// new state after each one.
+    pub fn step_n_with_observer(&mut self, n: usize, observer: &mut impl FnMut(&[C])) {
+        for _ in 0..n {
+            self.step_with_observer(observer);
+        }
+    }
+
+    /// Steps the simulation until its state repeats, for at most `max_steps`
+    /// steps.
+    ///
+    /// # Returns
+    /// The generation index (0 being the state before the call) of the first
+    /// state that recurs: the fixed point, or the start of the cycle. Returns
+    /// `None` if no state repeats within `max_steps`.
+    pub fn run_until_stable(&mut self, max_steps: usize) -> Option<usize> {
+        let initial = residues(&self.state);
+        first_repeat(initial, max_steps, || {
+            self.step();
+            residues(&self.state)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::to_color_ramp;
+    use moma::strategy;
+
+    #[test]
+    fn all_zero_state_steps_to_zero_entropy() {
+        let mut automaton = CellularAutomaton::from_state(8, 4, strategy::PrimeGap, vec![0; 8]).unwrap();
+        // Every cell sees the same neighbors, so they all stay equal.
+        assert_eq!(automaton.step_with_entropy(), 0.0);
+    }
+
+    #[test]
+    fn uniformly_varied_state_has_maximal_entropy() {
+        let automaton = CellularAutomaton::from_state(16, 8, strategy::PrimeGap, (0..16).map(|i| i % 8).collect()).unwrap();
+        assert!((automaton.entropy() - 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn step_with_entropy_reports_the_new_state() {
+        let mut automaton = CellularAutomaton::new_seeded(64, 8, strategy::CompositeMass, 5);
+        let entropy = automaton.step_with_entropy();
+        assert!((entropy - automaton.entropy()).abs() < 1e-12);
+        assert!((0.0..=3.0 + 1e-12).contains(&entropy));
+    }
+
+    #[test]
+    fn restoring_a_snapshot_reproduces_subsequent_steps() {
+        let mut original = Moma2dAutomaton::new_seeded(5, 4, 7, strategy::PrimeGap, 11);
+        let snapshot = original.snapshot(Some(vec![Point::new(1, 1), Point::new(2, 1)]), 0.5);
+        original.step_n(3);
+
+        let mut restored = Moma2dAutomaton::new_seeded(5, 4, 7, strategy::PrimeGap, 99);
+        restored.restore_snapshot(&snapshot);
+        restored.step_n(3);
+        assert_eq!(restored.state, original.state);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn snapshot_survives_a_json_round_trip() {
+        let automaton = Moma2dAutomaton::new_seeded(4, 3, 5, strategy::CompositeMass, 3);
+        let snapshot = automaton.snapshot(Some(vec![Point::new(0, 2)]), 1.25);
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let decoded: SimulationSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, snapshot);
+
+        let mut restored = Moma2dAutomaton::new_seeded(4, 3, 5, strategy::CompositeMass, 4);
+        restored.restore_snapshot(&decoded);
+        assert_eq!(restored.state, automaton.state);
+    }
+
+    /// Steps a 3x3 automaton with the given boundary and returns its top-left
+    /// cell along with the value the ring gives for `neighbor_sum`.
+    fn corner_after_step(boundary: BoundaryCondition, neighbor_sum: u64) -> (u64, u64) {
+        let state = vec![1, 2, 3, 4, 5, 6, 0, 1, 2];
+        let mut automaton = Moma2dAutomaton::from_state(3, 3, 7, strategy::PrimeGap, state).unwrap().with_boundary(boundary);
+        automaton.step();
+        (automaton.get(0, 0), MomaRing::new(7, strategy::PrimeGap).residue(1, neighbor_sum))
+    }
+
+    #[test]
+    fn corner_neighbors_follow_the_boundary_condition() {
+        // Wrapping reaches the far row and column.
+        let (actual, expected) = corner_after_step(BoundaryCondition::Toroidal, 2 + 1 + 3 + 2 + 6 + 4 + 5);
+        assert_eq!(actual, expected);
+        // The five neighbors beyond the edges each contribute the constant.
+        let (actual, expected) = corner_after_step(BoundaryCondition::Fixed(5), 5 * 5 + 2 + 4 + 5);
+        assert_eq!(actual, expected);
+        // Mirroring repeats the cells just inside the edges.
+        let (actual, expected) = corner_after_step(BoundaryCondition::Reflecting, 5 + 4 + 5 + 2 + 2 + 5 + 4 + 5);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn fixed_boundary_feeds_the_ends_of_a_1d_automaton() {
+        let mut automaton = CellularAutomaton::from_state(4, 7, strategy::PrimeGap, vec![1, 2, 3, 4])
+            .unwrap()
+            .with_boundary(BoundaryCondition::Fixed(3));
+        automaton.step();
+        let ring = MomaRing::new(7, strategy::PrimeGap);
+        assert_eq!(automaton.get(0), ring.residue(1, 3 + 2));
+        assert_eq!(automaton.get(3), ring.residue(4, 3 + 3));
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_initial_state() {
+        let a = Moma2dAutomaton::new_seeded(8, 8, 11, strategy::PrimeGap, 42);
+        let b = Moma2dAutomaton::new_seeded(8, 8, 11, strategy::PrimeGap, 42);
+        let c = Moma2dAutomaton::new_seeded(8, 8, 11, strategy::PrimeGap, 43);
+        assert_eq!(a.state, b.state);
+        assert_ne!(a.state, c.state);
+        assert!(a.state.iter().all(|&cell| cell < 11));
+
+        let a = CellularAutomaton::new_seeded(32, 11, strategy::PrimeGap, 1);
+        let b = CellularAutomaton::new_seeded(32, 11, strategy::PrimeGap, 1);
+        assert_eq!(a.state, b.state);
+    }
+
+    #[test]
+    fn from_state_rejects_a_state_of_the_wrong_length() {
+        let result = Moma2dAutomaton::from_state(3, 3, 7, strategy::PrimeGap, vec![0; 8]);
+        assert_eq!(result.err(), Some(AutomatonError::StateLengthMismatch { expected: 9, actual: 8 }));
+        assert!(CellularAutomaton::from_state(4, 7, strategy::PrimeGap, vec![0; 5]).is_err());
+    }
+
+    #[test]
+    fn a_single_seeded_cell_reaches_exactly_its_neighbors() {
+        let mut automaton = Moma2dAutomaton::from_state(5, 5, 7, strategy::PrimeGap, vec![0; 25]).unwrap();
+        automaton.set(2, 2, 3);
+        assert_eq!(automaton.get(2, 2), 3);
+        automaton.step();
+
+        let ring = MomaRing::new(7, strategy::PrimeGap);
+        for y in 0..5usize {
+            for x in 0..5usize {
+                let expected = match (x.abs_diff(2), y.abs_diff(2)) {
+                    (0, 0) => ring.residue(3, 0),
+                    (0..=1, 0..=1) => ring.residue(0, 3),
+                    _ => ring.residue(0, 0),
+                };
+                assert_eq!(automaton.get(x, y), expected, "cell ({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "outside")]
+    fn get_panics_outside_the_automaton() {
+        CellularAutomaton::from_state(4, 7, strategy::PrimeGap, vec![0; 4]).unwrap().get(4);
+    }
+
+    #[test]
+    fn render_draws_one_line_per_row() {
+        let automaton = Moma2dAutomaton::from_state(3, 3, 7, strategy::PrimeGap, (0..9).collect()).unwrap();
+        // The ramp is stretched over 0..7, and values past it clamp to the last character.
+        assert_eq!(automaton.render(), " .:\n=+#\n%@@\n");
+        assert_eq!(automaton.to_grid(), vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]]);
+    }
+
+    #[test]
+    fn first_repeat_reports_where_the_cycle_starts() {
+        // 0 -> 1 -> 2 -> 3 -> 1 -> ...: generation 1 is the first to recur.
+        let mut states = [1, 2, 3, 1].into_iter().map(|v| vec![v]);
+        assert_eq!(first_repeat(vec![0], 10, || states.next().unwrap()), Some(1));
+
+        let mut counter = 0;
+        assert_eq!(first_repeat(vec![0], 10, || { counter += 1; vec![counter] }), None);
+    }
+
+    #[test]
+    fn uniform_state_settles_within_one_pass_over_the_residues() {
+        // Every cell sees the same neighbors, so the grid stays uniform and can
+        // only take one of `modulus` states.
+        let mut automaton = Moma2dAutomaton::from_state(3, 3, 7, strategy::PrimeGap, vec![0; 9]).unwrap();
+        let start = automaton.run_until_stable(50).unwrap();
+        assert!(start < 7);
+        assert!(automaton.state.iter().all(|&cell| cell == automaton.state[0]));
+    }
+
+    #[test]
+    fn step_n_matches_repeated_steps() {
+        let mut stepped = CellularAutomaton::new_seeded(16, 5, strategy::PrimeGap, 3);
+        let mut batched = CellularAutomaton::new_seeded(16, 5, strategy::PrimeGap, 3);
+        for _ in 0..4 {
+            stepped.step();
+        }
+        batched.step_n(4);
+        assert_eq!(stepped.state, batched.state);
+    }
+
+    #[test]
+    fn recording_keeps_the_initial_state_and_every_step() {
+        let mut automaton = Moma2dAutomaton::new_seeded(3, 3, 7, strategy::PrimeGap, 1);
+        automaton.step();
+        assert!(automaton.history().is_none());
+
+        automaton.start_recording();
+        let initial = automaton.state.clone();
+        automaton.step_n(5);
+        let history = automaton.stop_recording().unwrap();
+        assert_eq!(history.len(), 6);
+        assert_eq!(history.generations()[0], initial);
+        assert_eq!(history.generations()[5], automaton.state);
+        assert!(automaton.history().is_none());
+
+        let mut automaton = CellularAutomaton::new_seeded(4, 7, strategy::PrimeGap, 1);
+        automaton.start_recording();
+        automaton.step_n(3);
+        assert_eq!(automaton.history().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn entropy_is_zero_for_a_uniform_grid_and_log2_of_distinct_values_otherwise() {
+        let uniform = Moma2dAutomaton::from_state(2, 2, 7, strategy::PrimeGap, vec![3; 4]).unwrap();
+        assert_eq!(uniform.entropy(), 0.0);
+        let distinct = Moma2dAutomaton::from_state(2, 2, 7, strategy::PrimeGap, vec![0, 1, 2, 3]).unwrap();
+        assert!((distinct.entropy() - 2.0).abs() < 1e-12);
+        let random = CellularAutomaton::new_seeded(10_000, 8, strategy::PrimeGap, 5);
+        assert!(random.entropy() > 2.99);
+    }
+
+    #[test]
+    fn activity_is_the_fraction_of_changed_cells() {
+        let automaton = Moma2dAutomaton::from_state(2, 2, 7, strategy::PrimeGap, vec![3; 4]).unwrap();
+        assert_eq!(automaton.activity(&[3, 3, 0, 0]), 0.5);
+        assert_eq!(automaton.activity(&[3; 4]), 0.0);
+    }
+
+    /// A two-state cell type defined outside the crate's own implementations.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Life {
+        Dead,
+        Alive,
+    }
+
+    impl CellState for Life {
+        fn to_residue(self) -> u64 {
+            (self == Life::Alive) as u64
+        }
+
+        fn from_residue(residue: u64) -> Self {
+            if residue % 2 == 1 { Life::Alive } else { Life::Dead }
+        }
+    }
+
+    #[test]
+    fn custom_cell_types_step_like_their_residues() {
+        let residues = vec![0, 1, 1, 0, 0, 1, 0, 0, 1];
+        let cells: Vec<Life> = residues.iter().map(|&r| Life::from_residue(r)).collect();
+
+        let mut numeric = Moma2dAutomaton::from_state(3, 3, 2, strategy::PrimeGap, residues).unwrap();
+        let mut custom = Moma2dAutomaton::from_cells(3, 3, 2, strategy::PrimeGap, cells).unwrap();
+        numeric.step_n(3);
+        custom.step_n(3);
+        let expected: Vec<Life> = numeric.state.iter().map(|&r| Life::from_residue(r)).collect();
+        assert_eq!(custom.state, expected);
+    }
+
+    #[test]
+    fn boolean_cells_step_like_zero_and_one() {
+        let mut numeric = CellularAutomaton::from_state(4, 2, strategy::PrimeGap, vec![1, 0, 0, 1]).unwrap();
+        let mut boolean = CellularAutomaton::from_cells(4, 2, strategy::PrimeGap, vec![true, false, false, true]).unwrap();
+        numeric.step();
+        boolean.step();
+        for x in 0..4 {
+            assert_eq!(boolean.get(x), numeric.get(x) != 0);
+        }
+    }
+
+    #[test]
+    fn spacetime_image_has_one_row_of_width_pixels_per_step() {
+        let mut automaton = CellularAutomaton::new_seeded(24, 5, strategy::PrimeGap, 8);
+        let image = automaton.spacetime_image(10);
+        assert_eq!(image.len(), 10);
+        assert!(image.iter().all(|row| row.len() == 24));
+    }
+
+    #[test]
+    fn spacetime_image_starts_with_the_current_state_and_ends_with_the_last_step() {
+        let initial: Vec<u64> = (0..12).map(|i| i % 5).collect();
+        let mut automaton = CellularAutomaton::from_state(12, 5, strategy::CompositeMass, initial.clone()).unwrap();
+        let mut reference = CellularAutomaton::from_state(12, 5, strategy::CompositeMass, initial.clone()).unwrap();
+
+        let image = automaton.spacetime_image(4);
+        let expected_first: Vec<[u8; 4]> = initial.iter().map(|&v| to_color_ramp(v, 5)).collect();
+        assert_eq!(image[0], expected_first);
+
+        reference.step_n(3);
+        assert_eq!(automaton.state, reference.state);
+        let expected_last: Vec<[u8; 4]> = reference.state.iter().map(|&v| to_color_ramp(v, 5)).collect();
+        assert_eq!(image[3], expected_last);
+    }
+
+    #[test]
+    fn spacetime_image_of_zero_steps_is_empty_and_leaves_the_state_alone() {
+        let mut automaton = CellularAutomaton::new_seeded(8, 3, strategy::PrimeGap, 1);
+        let before = automaton.state.clone();
+        assert!(automaton.spacetime_image(0).is_empty());
+        assert_eq!(automaton.state, before);
+    }
+
+    #[test]
+    fn render_with_gives_each_state_its_own_character_when_the_ramp_matches_the_modulus() {
+        let automaton = CellularAutomaton::from_state(4, 4, strategy::PrimeGap, vec![0, 1, 2, 3]).unwrap();
+        assert_eq!(automaton.render_with(&['a', 'b', 'c', 'd']), "abcd");
+    }
+
+    #[test]
+    fn render_stretches_the_default_ramp_across_a_small_modulus() {
+        let automaton = CellularAutomaton::from_state(3, 3, strategy::PrimeGap, vec![0, 1, 2]).unwrap();
+        let rendered: Vec<char> = automaton.render().chars().collect();
+        assert_eq!(rendered[0], DEFAULT_RAMP[0]);
+        assert_eq!(rendered[2], DEFAULT_RAMP[6]);
+        assert!(rendered[0] != rendered[1] && rendered[1] != rendered[2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one character")]
+    fn render_with_rejects_an_empty_ramp() {
+        CellularAutomaton::from_state(2, 2, strategy::PrimeGap, vec![0, 1]).unwrap().render_with(&[]);
+    }
+
+    #[test]
+    fn observer_sees_every_state_of_a_1d_run() {
+        let mut automaton = CellularAutomaton::new_seeded(32, 7, strategy::PrimeGap, 9);
+        let mut reference = CellularAutomaton::new_seeded(32, 7, strategy::PrimeGap, 9);
+
+        let mut sums = Vec::new();
+        automaton.step_n_with_observer(12, &mut |state| sums.push(state.iter().sum::<u64>()));
+        assert_eq!(sums.len(), 12);
+
+        for &sum in &sums {
+            reference.step();
+            assert_eq!(sum, reference.state.iter().sum::<u64>());
+        }
+        assert_eq!(automaton.state, reference.state);
+    }
+
+    #[test]
+    fn observer_receives_the_2d_state_after_each_step() {
+        let mut automaton = Moma2dAutomaton::new_seeded(6, 5, 5, strategy::CompositeMass, 4);
+        let mut seen = Vec::new();
+        automaton.step_with_observer(&mut |state| seen.push(state.to_vec()));
+        automaton.step_n_with_observer(3, &mut |state| seen.push(state.to_vec()));
+        assert_eq!(seen.len(), 4);
+        assert_eq!(seen[3], automaton.state);
+    }
+
+    #[test]
+    fn rule_table_holds_the_ring_residue_for_every_center_and_context() {
+        let automaton = CellularAutomaton::new_seeded(8, 6, strategy::PrimeGap, 1);
+        let ring = MomaRing::new(6, strategy::PrimeGap);
+        let table = automaton.rule_table();
+        assert_eq!(table.len(), 6);
+        for (center, row) in table.iter().enumerate() {
+            assert_eq!(row.len(), 11);
+            for (context, &value) in row.iter().enumerate() {
+                assert_eq!(value, ring.residue(center as u64, context as u64));
+            }
+        }
+    }
+
+    #[test]
+    fn table_stepping_matches_direct_stepping() {
+        for (modulus, seed) in [(2, 3), (7, 5), (16, 9)] {
+            for boundary in [BoundaryCondition::Toroidal, BoundaryCondition::Reflecting, BoundaryCondition::Fixed(1)] {
+                let automaton = || CellularAutomaton::new_seeded(40, modulus, strategy::CompositeMass, seed).with_boundary(boundary);
+                let (mut direct, mut tabled) = (automaton(), automaton());
+                let table = tabled.rule_table();
+                for generation in 0..25 {
+                    direct.step();
+                    tabled.step_with_table(&table);
+                    assert_eq!(tabled.state, direct.state, "modulus {}, generation {}", modulus, generation);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn table_stepping_falls_back_to_the_ring_outside_the_table() {
+        let state = vec![40, 3, 0, 99, 5];
+        let mut direct = CellularAutomaton::from_state(5, 8, strategy::PrimeGap, state.clone()).unwrap();
+        let mut tabled = CellularAutomaton::from_state(5, 8, strategy::PrimeGap, state).unwrap();
+        let table = tabled.rule_table();
+        direct.step();
+        tabled.step_with_table(&table);
+        assert_eq!(tabled.state, direct.state);
+    }
+
+    #[test]
+    fn odd_primes_start_at_three_and_skip_composites() {
+        assert_eq!(odd_primes().take(10).collect::<Vec<_>>(), vec![3, 5, 7, 11, 13, 17, 19, 23, 29, 31]);
+    }
+
+    #[test]
+    fn new_moma_seeded_sets_exactly_the_cells_the_ring_signatures_pick() {
+        let (width, height) = (16, 12);
+        let automaton = Moma2dAutomaton::new_moma_seeded(width, height, 7, strategy::PrimeGap, MomaRing::new(11, strategy::CompositeMass), 2);
+
+        // Signatures at 3, 5, 7 are 7, 10, 8 and at 11, 13, 17 are 10, 10, 0.
+        let mut expected = vec![0; width * height];
+        for (x, y, value) in [(7, 10, 3), (10, 10, 1)] {
+            expected[y * width + x] = value;
+        }
+        assert_eq!(automaton.state, expected);
+    }
+
+    #[test]
+    fn new_moma_seeded_is_reproducible() {
+        let seeded = || Moma2dAutomaton::new_moma_seeded(20, 20, 7, strategy::PrimeGap, MomaRing::new(13, strategy::PrimeGap), 8);
+        assert_eq!(seeded().state, seeded().state);
+        assert!(seeded().state.iter().any(|&v| v != 0));
+    }
+
+    #[test]
+    fn new_moma_seeded_without_seeds_is_all_zero() {
+        let automaton = Moma2dAutomaton::new_moma_seeded(4, 3, 5, strategy::PrimeGap, MomaRing::new(7, strategy::PrimeGap), 0);
+        assert_eq!(automaton.state, vec![0; 12]);
+        let empty = Moma2dAutomaton::new_moma_seeded(0, 0, 5, strategy::PrimeGap, MomaRing::new(7, strategy::PrimeGap), 0);
+        assert!(empty.state.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "modulus of at least 2")]
+    fn new_moma_seeded_rejects_a_modulus_with_no_nonzero_values() {
+        Moma2dAutomaton::new_moma_seeded(4, 4, 1, strategy::PrimeGap, MomaRing::new(7, strategy::PrimeGap), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "empty grid")]
+    fn new_moma_seeded_rejects_seeds_on_an_empty_grid() {
+        Moma2dAutomaton::new_moma_seeded(0, 5, 5, strategy::PrimeGap, MomaRing::new(7, strategy::PrimeGap), 1);
     }
 }
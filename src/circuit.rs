@@ -1,15 +1,177 @@
 //! Represents a quantum circuit with multiple qubits.
+use std::collections::HashMap;
 use std::fmt;
 use num_complex::Complex;
 use rand::Rng;
 use crate::gates;
+use crate::qasm;
 
 // Re-using our type alias for 64-bit floats
 type F = f64;
 
+/// The tolerance used when checking that a user-supplied gate is unitary.
+const UNITARY_TOLERANCE: F = 1e-9;
+
+/// Errors reported by the checked circuit operations.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CircuitError {
+    /// The supplied gate matrix is not unitary.
+    NotUnitary,
+    /// A Pauli string names the same qubit more than once.
+    DuplicateQubit(usize),
+    /// A Pauli string contains an operator other than `X`, `Y`, `Z` or `I`.
+    UnknownPauli(char),
+}
+
+impl fmt::Display for CircuitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CircuitError::NotUnitary => write!(f, "gate matrix is not unitary"),
+            CircuitError::DuplicateQubit(qubit) => write!(f, "qubit {} appears more than once", qubit),
+            CircuitError::UnknownPauli(op) => write!(f, "unknown Pauli operator '{}'", op),
+        }
+    }
+}
+
+impl std::error::Error for CircuitError {}
+
+/// A single operation recorded in a circuit's op log.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operation {
+    /// Hadamard on a target qubit.
+    H(usize),
+    /// Pauli-X on a target qubit.
+    X(usize),
+    /// Pauli-Y on a target qubit.
+    Y(usize),
+    /// Pauli-Z on a target qubit.
+    Z(usize),
+    /// Rx(θ) on a target qubit.
+    Rx(usize, F),
+    /// Ry(θ) on a target qubit.
+    Ry(usize, F),
+    /// Rz(θ) on a target qubit.
+    Rz(usize, F),
+    /// Phase gate diag(1, e^{iλ}) on a target qubit.
+    Phase(usize, F),
+    /// An arbitrary 2x2 gate on a target qubit.
+    Unitary(usize, [[Complex<F>; 2]; 2]),
+    /// An arbitrary 2x2 gate on a target qubit, applied only when a control
+    /// qubit is 1. Fields are the control, target and matrix.
+    ControlledUnitary(usize, usize, [[Complex<F>; 2]; 2]),
+    /// CNOT with a control and a target.
+    Cnot(usize, usize),
+    /// Controlled-Z with a control and a target.
+    Cz(usize, usize),
+    /// SWAP of two qubits.
+    Swap(usize, usize),
+    /// Controlled-phase e^{iλ} with a control and a target.
+    ControlledPhase(usize, usize, F),
+    /// Toffoli with two controls and a target.
+    Toffoli(usize, usize, usize),
+    /// Multi-controlled Z with a list of controls and a target.
+    Mcz(Vec<usize>, usize),
+    /// An arbitrary 4x4 gate on a pair of qubits.
+    TwoQubit(usize, usize, [[Complex<F>; 4]; 4]),
+    /// Reset of a qubit to |0⟩.
+    Reset(usize),
+    /// Measurement of a single qubit.
+    Measure(usize),
+    /// Measurement of the whole register.
+    MeasureAll,
+}
+
+impl Operation {
+    /// Returns a copy of the operation with every qubit index moved up by `offset`.
+    fn shifted(&self, offset: usize) -> Operation {
+        self.remapped(|q| q + offset)
+    }
+
+    /// Returns the operation that undoes this one, or `None` for resets and
+    /// measurements, which are not reversible.
+    fn adjoint(&self) -> Option<Operation> {
+        let adjoint = match *self {
+            Operation::H(_)
+            | Operation::X(_)
+            | Operation::Y(_)
+            | Operation::Z(_)
+            | Operation::Cnot(..)
+            | Operation::Cz(..)
+            | Operation::Swap(..)
+            | Operation::Toffoli(..)
+            | Operation::Mcz(..) => self.clone(),
+            Operation::Rx(t, theta) => Operation::Rx(t, -theta),
+            Operation::Ry(t, theta) => Operation::Ry(t, -theta),
+            Operation::Rz(t, theta) => Operation::Rz(t, -theta),
+            Operation::Phase(t, lambda) => Operation::Phase(t, -lambda),
+            Operation::Unitary(t, ref matrix) => Operation::Unitary(t, gates::dagger(matrix)),
+            Operation::ControlledUnitary(c, t, ref matrix) => Operation::ControlledUnitary(c, t, gates::dagger(matrix)),
+            Operation::ControlledPhase(c, t, lambda) => Operation::ControlledPhase(c, t, -lambda),
+            Operation::TwoQubit(q1, q2, ref matrix) => Operation::TwoQubit(q1, q2, gates::dagger(matrix)),
+            Operation::Reset(_) | Operation::Measure(_) | Operation::MeasureAll => return None,
+        };
+        Some(adjoint)
+    }
+
+    /// Returns every qubit index the operation acts on. `MeasureAll` names no
+    /// particular qubit, so it returns none.
+    fn qubits(&self) -> Vec<usize> {
+        match *self {
+            Operation::H(t)
+            | Operation::X(t)
+            | Operation::Y(t)
+            | Operation::Z(t)
+            | Operation::Rx(t, _)
+            | Operation::Ry(t, _)
+            | Operation::Rz(t, _)
+            | Operation::Phase(t, _)
+            | Operation::Unitary(t, _)
+            | Operation::Reset(t)
+            | Operation::Measure(t) => vec![t],
+            Operation::ControlledUnitary(q1, q2, _)
+            | Operation::Cnot(q1, q2)
+            | Operation::Cz(q1, q2)
+            | Operation::Swap(q1, q2)
+            | Operation::ControlledPhase(q1, q2, _)
+            | Operation::TwoQubit(q1, q2, _) => vec![q1, q2],
+            Operation::Toffoli(c1, c2, t) => vec![c1, c2, t],
+            Operation::Mcz(ref controls, t) => controls.iter().copied().chain([t]).collect(),
+            Operation::MeasureAll => Vec::new(),
+        }
+    }
+
+    /// Returns a copy of the operation with every qubit index passed through `map`.
+    fn remapped(&self, map: impl Fn(usize) -> usize) -> Operation {
+        match *self {
+            Operation::H(t) => Operation::H(map(t)),
+            Operation::X(t) => Operation::X(map(t)),
+            Operation::Y(t) => Operation::Y(map(t)),
+            Operation::Z(t) => Operation::Z(map(t)),
+            Operation::Rx(t, theta) => Operation::Rx(map(t), theta),
+            Operation::Ry(t, theta) => Operation::Ry(map(t), theta),
+            Operation::Rz(t, theta) => Operation::Rz(map(t), theta),
+            Operation::Phase(t, lambda) => Operation::Phase(map(t), lambda),
+            Operation::Unitary(t, matrix) => Operation::Unitary(map(t), matrix),
+            Operation::ControlledUnitary(c, t, matrix) => Operation::ControlledUnitary(map(c), map(t), matrix),
+            Operation::Cnot(c, t) => Operation::Cnot(map(c), map(t)),
+            Operation::Cz(c, t) => Operation::Cz(map(c), map(t)),
+            Operation::Swap(q1, q2) => Operation::Swap(map(q1), map(q2)),
+            Operation::ControlledPhase(c, t, lambda) => Operation::ControlledPhase(map(c), map(t), lambda),
+            Operation::Toffoli(c1, c2, t) => Operation::Toffoli(map(c1), map(c2), map(t)),
+            Operation::Mcz(ref controls, t) => Operation::Mcz(controls.iter().map(|&c| map(c)).collect(), map(t)),
+            Operation::TwoQubit(q1, q2, matrix) => Operation::TwoQubit(map(q1), map(q2), matrix),
+            Operation::Reset(t) => Operation::Reset(map(t)),
+            Operation::Measure(t) => Operation::Measure(map(t)),
+            Operation::MeasureAll => Operation::MeasureAll,
+        }
+    }
+}
+
 pub struct QuantumCircuit {
     num_qubits: usize,
     state_vector: Vec<Complex<F>>,
+    /// Every operation applied so far, in order.
+    operations: Vec<Operation>,
 }
 
 impl QuantumCircuit {
@@ -27,39 +189,490 @@ impl QuantumCircuit {
         Self {
             num_qubits,
             state_vector,
+            operations: Vec::new(),
+        }
+    }
+
+    /// Creates a 2-qubit circuit prepared in the Bell state (|00⟩ + |11⟩)/√2.
+    pub fn bell_pair() -> Self {
+        Self::ghz(2)
+    }
+
+    /// Creates an `n`-qubit circuit prepared in the GHZ state
+    /// (|0…0⟩ + |1…1⟩)/√2, using a Hadamard on qubit 0 followed by a chain of
+    /// CNOTs.
+    ///
+    /// # Panics
+    /// Panics if `n` is 0.
+    pub fn ghz(n: usize) -> Self {
+        assert!(n > 0, "A GHZ state needs at least one qubit.");
+
+        let mut circuit = Self::new(n);
+        circuit.h(0);
+        for target in 1..n {
+            circuit.cnot(target - 1, target);
+        }
+        circuit
+    }
+
+    /// Combines two registers into one whose state is the tensor (Kronecker)
+    /// product of their states.
+    ///
+    /// `self`'s qubits keep their indices in the low bits, and `other`'s qubits
+    /// follow in the high bits, so `other`'s qubit `q` becomes qubit
+    /// `self.num_qubits() + q` of the result.
+    pub fn tensor(&self, other: &QuantumCircuit) -> QuantumCircuit {
+        let low_len = self.state_vector.len();
+        let mut state_vector = vec![Complex::new(0.0, 0.0); low_len * other.state_vector.len()];
+
+        for (high, &high_amplitude) in other.state_vector.iter().enumerate() {
+            for (low, &low_amplitude) in self.state_vector.iter().enumerate() {
+                state_vector[high * low_len + low] = high_amplitude * low_amplitude;
+            }
+        }
+
+        // The combined log replays `self`'s operations, then `other`'s shifted
+        // onto the high qubits.
+        let offset = self.num_qubits;
+        let mut operations = self.operations.clone();
+        operations.extend(other.operations.iter().map(|op| op.shifted(offset)));
+
+        Self {
+            num_qubits: self.num_qubits + other.num_qubits,
+            state_vector,
+            operations,
+        }
+    }
+
+    /// Returns a new circuit, starting from |00...0⟩, that applies the adjoint
+    /// of every recorded operation in reverse order. Running a circuit's
+    /// operations followed by its inverse's leaves the state unchanged.
+    ///
+    /// # Panics
+    /// Panics if the circuit has recorded a reset or measurement, since those
+    /// cannot be undone.
+    pub fn inverse(&self) -> QuantumCircuit {
+        let adjoints: Vec<Operation> = self
+            .operations
+            .iter()
+            .rev()
+            .map(|op| op.adjoint().expect("Cannot invert a circuit containing resets or measurements."))
+            .collect();
+
+        let mut inverse = QuantumCircuit::new(self.num_qubits);
+        for op in adjoints {
+            inverse.apply_operation(op);
+        }
+        inverse
+    }
+
+    /// Replays `other`'s recorded operations onto this circuit, with `other`'s
+    /// qubit `q` acting on qubit `qubit_map[q]`. This lets small circuits,
+    /// such as an adder or a QFT block, be reused as subroutines.
+    ///
+    /// Only the operations are replayed, not `other`'s state. A `MeasureAll`
+    /// in `other` measures just the mapped qubits.
+    ///
+    /// # Panics
+    /// Panics if `qubit_map` does not have one entry per qubit of `other`, or
+    /// if it maps two qubits to the same index or to one outside this circuit.
+    pub fn append(&mut self, other: &QuantumCircuit, qubit_map: &[usize]) -> &mut Self {
+        self.append_with_rng(other, qubit_map, &mut rand::rng())
+    }
+
+    /// Replays `other`'s operations like `append`, drawing the random samples
+    /// for any measurements and resets it contains from `rng`, so the result
+    /// is reproducible.
+    ///
+    /// # Panics
+    /// Panics under the same conditions as `append`.
+    pub fn append_with_rng<R: Rng + ?Sized>(&mut self, other: &QuantumCircuit, qubit_map: &[usize], rng: &mut R) -> &mut Self {
+        assert_eq!(qubit_map.len(), other.num_qubits, "Expected one mapped qubit per qubit of the appended circuit.");
+        for (i, &qubit) in qubit_map.iter().enumerate() {
+            self.check_qubit(qubit);
+            assert!(!qubit_map[..i].contains(&qubit), "qubit {} is mapped to more than once", qubit);
+        }
+
+        for op in &other.operations {
+            if *op == Operation::MeasureAll {
+                for &qubit in qubit_map {
+                    self.apply_operation_with_rng(Operation::Measure(qubit), rng);
+                }
+            } else {
+                self.apply_operation_with_rng(op.remapped(|q| qubit_map[q]), rng);
+            }
+        }
+        self
+    }
+
+    /// Returns the number of qubits in the circuit.
+    pub fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    /// Returns the current state vector, indexed by basis state.
+    ///
+    /// This reflects the state as it is, which may be very slightly
+    /// un-normalized due to floating point rounding.
+    pub fn state_vector(&self) -> &[Complex<F>] {
+        &self.state_vector
+    }
+
+    /// Returns the amplitude of the basis state with the given index, where
+    /// bit `q` of the index is the value of qubit `q`.
+    ///
+    /// # Panics
+    /// Panics if `index` is not below `2^num_qubits`.
+    pub fn amplitude(&self, index: usize) -> Complex<F> {
+        assert!(
+            index < self.state_vector.len(),
+            "basis state {} out of range for {}-qubit circuit",
+            index,
+            self.num_qubits
+        );
+        self.state_vector[index]
+    }
+
+    /// Returns the amplitude of the basis state written as a ket label, with
+    /// the highest qubit first: `&[0, 1]` is |01⟩, where qubit 1 is 0 and
+    /// qubit 0 is 1.
+    ///
+    /// # Panics
+    /// Panics if `bits` does not have one entry per qubit or an entry is not
+    /// 0 or 1.
+    pub fn amplitude_of_bits(&self, bits: &[u8]) -> Complex<F> {
+        assert_eq!(bits.len(), self.num_qubits, "Expected one bit per qubit.");
+        let index = bits.iter().fold(0, |index, &bit| {
+            assert!(bit <= 1, "Bits must be 0 or 1, got {}.", bit);
+            (index << 1) | bit as usize
+        });
+        self.state_vector[index]
+    }
+
+    /// Returns the operations applied to the circuit so far, in order.
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    /// Exports the recorded operations as an OpenQASM 2.0 program.
+    pub fn to_qasm(&self) -> String {
+        qasm::to_qasm(self.num_qubits, &self.operations)
+    }
+
+    /// Returns the norm of the state vector, which should be 1.0. Monitoring it
+    /// shows how far floating point drift has pushed the state.
+    pub fn norm(&self) -> F {
+        self.state_vector.iter().map(|amplitude| amplitude.norm_sqr()).sum::<F>().sqrt()
+    }
+
+    /// Rescales the state vector to unit norm to counter floating point drift.
+    /// A zero state is left unchanged, since it has no direction to keep.
+    pub fn normalize(&mut self) {
+        let norm = self.norm();
+        if norm == 0.0 {
+            return;
+        }
+        for amplitude in self.state_vector.iter_mut() {
+            *amplitude /= norm;
         }
     }
 
+    /// Returns the probability of each basis state, i.e. the squared magnitude
+    /// of each amplitude. Like `state_vector`, the values may not sum to
+    /// exactly 1.0.
+    pub fn probabilities(&self) -> Vec<F> {
+        self.state_vector.iter().map(|amplitude| amplitude.norm_sqr()).collect()
+    }
+
+    /// Returns the basis states whose probability exceeds `threshold`, as
+    /// `(index, probability)` pairs from most to least likely. Equally likely
+    /// states are listed by index.
+    pub fn significant_states(&self, threshold: F) -> Vec<(usize, F)> {
+        let mut states: Vec<(usize, F)> =
+            self.probabilities().into_iter().enumerate().filter(|&(_, p)| p > threshold).collect();
+        states.sort_by(|a, b| b.1.total_cmp(&a.1));
+        states
+    }
+
+    /// Returns the index of the most probable basis state without measuring.
+    /// Ties go to the lowest index.
+    pub fn most_likely(&self) -> usize {
+        self.probabilities()
+            .into_iter()
+            .enumerate()
+            .fold((0, F::NEG_INFINITY), |best, (i, p)| if p > best.1 { (i, p) } else { best })
+            .0
+    }
+
      /// Applies a Hadamard gate to the target qubit.
     pub fn h(&mut self, target_qubit: usize) -> &mut Self {
-        self.apply_single_qubit_gate(target_qubit, &gates::HADAMARD);
+        self.apply_operation(Operation::H(target_qubit));
         self
     }
 
     /// Applies a Pauli-X (NOT) gate to the target qubit.
     pub fn x(&mut self, target_qubit: usize) -> &mut Self {
-        self.apply_single_qubit_gate(target_qubit, &gates::PAULI_X);
+        self.apply_operation(Operation::X(target_qubit));
         self
     }
     
     /// Applies a Pauli-Y gate to the target qubit.
     pub fn y(&mut self, target_qubit: usize) -> &mut Self {
-        self.apply_single_qubit_gate(target_qubit, &gates::PAULI_Y);
+        self.apply_operation(Operation::Y(target_qubit));
         self
     }
 
     /// Applies a Pauli-Z gate to the target qubit.
     pub fn z(&mut self, target_qubit: usize) -> &mut Self {
-        self.apply_single_qubit_gate(target_qubit, &gates::PAULI_Z);
+        self.apply_operation(Operation::Z(target_qubit));
+        self
+    }
+
+    /// Applies an Rx(θ) rotation to the target qubit.
+    pub fn rx(&mut self, target_qubit: usize, theta: F) -> &mut Self {
+        self.apply_operation(Operation::Rx(target_qubit, theta));
+        self
+    }
+
+    /// Applies an Ry(θ) rotation to the target qubit.
+    pub fn ry(&mut self, target_qubit: usize, theta: F) -> &mut Self {
+        self.apply_operation(Operation::Ry(target_qubit, theta));
+        self
+    }
+
+    /// Applies an Rz(θ) rotation to the target qubit.
+    pub fn rz(&mut self, target_qubit: usize, theta: F) -> &mut Self {
+        self.apply_operation(Operation::Rz(target_qubit, theta));
+        self
+    }
+
+    /// Applies a phase gate, diag(1, e^{iλ}), to the target qubit.
+    pub fn phase(&mut self, target_qubit: usize, lambda: F) -> &mut Self {
+        self.apply_operation(Operation::Phase(target_qubit, lambda));
+        self
+    }
+
+    /// Applies an arbitrary user-supplied 2x2 gate to the target qubit.
+    ///
+    /// In debug builds this asserts that the matrix is unitary; use
+    /// `apply_unitary_checked` to handle non-unitary input gracefully.
+    pub fn unitary(&mut self, target_qubit: usize, matrix: &[[Complex<F>; 2]; 2]) -> &mut Self {
+        debug_assert!(gates::is_unitary(matrix, UNITARY_TOLERANCE), "Gate matrix is not unitary.");
+        self.apply_operation(Operation::Unitary(target_qubit, *matrix));
+        self
+    }
+
+    /// Applies an arbitrary 2x2 gate to the target qubit after checking that it
+    /// is unitary. The circuit is left untouched if the check fails.
+    pub fn apply_unitary_checked(
+        &mut self,
+        target_qubit: usize,
+        matrix: &[[Complex<F>; 2]; 2],
+    ) -> Result<&mut Self, CircuitError> {
+        if !gates::is_unitary(matrix, UNITARY_TOLERANCE) {
+            return Err(CircuitError::NotUnitary);
+        }
+        self.apply_operation(Operation::Unitary(target_qubit, *matrix));
+        Ok(self)
+    }
+
+    /// Applies an arbitrary 2x2 gate to the target qubit, but only on the part
+    /// of the state where the control qubit is 1.
+    ///
+    /// # Panics
+    /// Panics if the control and target are the same qubit.
+    pub fn controlled_unitary(
+        &mut self,
+        control_qubit: usize,
+        target_qubit: usize,
+        matrix: &[[Complex<F>; 2]; 2],
+    ) -> &mut Self {
+        assert_ne!(control_qubit, target_qubit, "A controlled gate needs distinct control and target qubits.");
+        debug_assert!(gates::is_unitary(matrix, UNITARY_TOLERANCE), "Gate matrix is not unitary.");
+        self.apply_operation(Operation::ControlledUnitary(control_qubit, target_qubit, *matrix));
         self
     }
 
     /// Applies a CNOT gate.
+    ///
+    /// # Panics
+    /// Panics if the control and target are the same qubit.
     pub fn cnot(&mut self, control_qubit: usize, target_qubit: usize) -> &mut Self {
-        self.apply_cnot_gate(control_qubit, target_qubit);
+        assert_ne!(control_qubit, target_qubit, "A CNOT needs distinct control and target qubits.");
+        self.apply_operation(Operation::Cnot(control_qubit, target_qubit));
+        self
+    }
+
+    /// Applies a controlled-Z gate.
+    ///
+    /// # Panics
+    /// Panics if the control and target are the same qubit.
+    pub fn cz(&mut self, control_qubit: usize, target_qubit: usize) -> &mut Self {
+        assert_ne!(control_qubit, target_qubit, "A CZ needs distinct control and target qubits.");
+        self.apply_operation(Operation::Cz(control_qubit, target_qubit));
+        self
+    }
+
+    /// Applies a controlled-phase gate, multiplying every basis state where
+    /// both qubits are 1 by e^{iλ}. The gate is symmetric in its two qubits,
+    /// and `cphase(c, t, π)` is the same as `cz(c, t)`.
+    ///
+    /// This is the rotation used throughout `qft` and in phase estimation.
+    pub fn cphase(&mut self, control_qubit: usize, target_qubit: usize, lambda: F) -> &mut Self {
+        self.apply_operation(Operation::ControlledPhase(control_qubit, target_qubit, lambda));
+        self
+    }
+
+    /// Applies a SWAP gate, exchanging the states of two qubits.
+    pub fn swap(&mut self, qubit1: usize, qubit2: usize) -> &mut Self {
+        self.apply_operation(Operation::Swap(qubit1, qubit2));
+        self
+    }
+
+    /// Applies the Quantum Fourier Transform over the listed qubits.
+    ///
+    /// `qubits[0]` is treated as the least significant bit of the transformed
+    /// register, so applying it over `0..num_qubits` maps the basis state |x⟩
+    /// to `1/√N · Σ_k e^{2πi·xk/N} |k⟩`. The final swaps that undo the QFT's
+    /// bit reversal are included.
+    pub fn qft(&mut self, qubits: &[usize]) -> &mut Self {
+        let n = qubits.len();
+        for j in (0..n).rev() {
+            self.h(qubits[j]);
+            for k in (0..j).rev() {
+                let angle = std::f64::consts::PI / (1 << (j - k)) as F;
+                self.cphase(qubits[k], qubits[j], angle);
+            }
+        }
+        for i in 0..n / 2 {
+            self.swap(qubits[i], qubits[n - 1 - i]);
+        }
+        self
+    }
+
+    /// Applies the inverse Quantum Fourier Transform over the listed qubits,
+    /// undoing `qft` over the same list.
+    pub fn iqft(&mut self, qubits: &[usize]) -> &mut Self {
+        let n = qubits.len();
+        for i in 0..n / 2 {
+            self.swap(qubits[i], qubits[n - 1 - i]);
+        }
+        for j in 0..n {
+            for k in 0..j {
+                let angle = -std::f64::consts::PI / (1 << (j - k)) as F;
+                self.cphase(qubits[k], qubits[j], angle);
+            }
+            self.h(qubits[j]);
+        }
+        self
+    }
+
+    /// Applies a Toffoli (CCNOT) gate, flipping the target only when both
+    /// control qubits are 1.
+    ///
+    /// # Panics
+    /// Panics if any two of the three qubits are the same.
+    pub fn toffoli(&mut self, control1: usize, control2: usize, target_qubit: usize) -> &mut Self {
+        assert!(
+            control1 != control2 && control1 != target_qubit && control2 != target_qubit,
+            "A Toffoli needs three distinct qubits."
+        );
+        self.apply_operation(Operation::Toffoli(control1, control2, target_qubit));
+        self
+    }
+
+    /// Applies a multi-controlled Z gate, flipping the sign of every basis
+    /// state where all the controls and the target are 1. With no controls it
+    /// is a plain Z on the target.
+    ///
+    /// # Panics
+    /// Panics if the target is one of the controls or a control is repeated.
+    pub fn mcz(&mut self, controls: &[usize], target_qubit: usize) -> &mut Self {
+        assert!(!controls.contains(&target_qubit), "The target of an MCZ cannot also be a control.");
+        assert!(
+            controls.iter().enumerate().all(|(i, c)| !controls[..i].contains(c)),
+            "The controls of an MCZ must be distinct."
+        );
+        self.apply_operation(Operation::Mcz(controls.to_vec(), target_qubit));
+        self
+    }
+
+    /// Applies Grover's diffusion operator (inversion about the mean) over the
+    /// listed qubits: H and X on each, a multi-controlled Z across them, then X
+    /// and H on each again.
+    ///
+    /// Together with an oracle that flips the sign of the marked states, this
+    /// makes up one Grover iteration. Up to a global phase of -1, it maps each
+    /// amplitude `a` to `2·mean - a`.
+    ///
+    /// # Panics
+    /// Panics if `qubits` is empty.
+    pub fn grover_diffusion(&mut self, qubits: &[usize]) -> &mut Self {
+        let (&target, controls) = qubits.split_last().expect("Grover diffusion needs at least one qubit.");
+
+        for &q in qubits {
+            self.h(q).x(q);
+        }
+        self.mcz(controls, target);
+        for &q in qubits {
+            self.x(q).h(q);
+        }
         self
     }
 
+/// Panics with a descriptive message if `qubit` is not in the register.
+fn check_qubit(&self, qubit: usize) {
+    assert!(
+        qubit < self.num_qubits,
+        "qubit index {} out of range for {}-qubit circuit",
+        qubit,
+        self.num_qubits
+    );
+}
+
+/// Applies an operation to the state and records it in the op log.
+///
+/// # Panics
+/// Panics if the operation names a qubit outside the register.
+fn apply_operation(&mut self, operation: Operation) {
+    self.apply_operation_with_rng(operation, &mut rand::rng());
+}
+
+/// Applies an operation like `apply_operation`, drawing the random sample
+/// for a measurement or reset from `rng`.
+fn apply_operation_with_rng<R: Rng + ?Sized>(&mut self, operation: Operation, rng: &mut R) {
+    for qubit in operation.qubits() {
+        self.check_qubit(qubit);
+    }
+    match operation {
+        Operation::H(t) => self.apply_single_qubit_gate(t, &gates::HADAMARD),
+        Operation::X(t) => self.apply_single_qubit_gate(t, &gates::PAULI_X),
+        Operation::Y(t) => self.apply_single_qubit_gate(t, &gates::PAULI_Y),
+        Operation::Z(t) => self.apply_single_qubit_gate(t, &gates::PAULI_Z),
+        Operation::Rx(t, theta) => self.apply_single_qubit_gate(t, &gates::rx(theta)),
+        Operation::Ry(t, theta) => self.apply_single_qubit_gate(t, &gates::ry(theta)),
+        Operation::Rz(t, theta) => self.apply_single_qubit_gate(t, &gates::rz(theta)),
+        Operation::Phase(t, lambda) => self.apply_single_qubit_gate(t, &gates::phase(lambda)),
+        Operation::Unitary(t, ref matrix) => self.apply_single_qubit_gate(t, matrix),
+        Operation::ControlledUnitary(c, t, ref matrix) => self.apply_controlled_gate(c, t, matrix),
+        Operation::Cnot(c, t) => self.apply_cnot_gate(c, t),
+        Operation::Cz(c, t) => self.apply_cz_gate(c, t),
+        Operation::Swap(q1, q2) => self.apply_swap_gate(q1, q2),
+        Operation::ControlledPhase(c, t, lambda) => self.apply_controlled_phase_gate(c, t, lambda),
+        Operation::Toffoli(c1, c2, t) => self.apply_toffoli_gate(c1, c2, t),
+        Operation::Mcz(ref controls, t) => self.apply_mcz_gate(controls, t),
+        Operation::TwoQubit(q1, q2, ref matrix) => self.apply_two_qubit_matrix(q1, q2, matrix),
+        Operation::Reset(t) => self.reset_qubit(t, rng),
+        Operation::Measure(t) => {
+            self.collapse_qubit(t, rng);
+        }
+        Operation::MeasureAll => {
+            self.collapse_all(rng);
+        }
+    }
+    self.operations.push(operation);
+}
+
 /// Applies a single-qubit gate to a specific target qubit in the circuit.
 fn apply_single_qubit_gate(&mut self, target_qubit: usize, gate_matrix: &[[Complex<F>; 2]; 2]) {
     // The "stride" is the distance between the two amplitudes we need to modify.
@@ -86,6 +699,62 @@ fn apply_single_qubit_gate(&mut self, target_qubit: usize, gate_matrix: &[[Compl
     }
 }
 
+/// Applies a single-qubit gate to the target qubit, restricted to the basis
+/// states where the control qubit is 1.
+fn apply_controlled_gate(&mut self, control_qubit: usize, target_qubit: usize, gate_matrix: &[[Complex<F>; 2]; 2]) {
+    let control_mask = 1 << control_qubit;
+    let stride = 1 << target_qubit;
+    let g00 = gate_matrix[0][0];
+    let g01 = gate_matrix[0][1];
+    let g10 = gate_matrix[1][0];
+    let g11 = gate_matrix[1][1];
+
+    // The same chunked walk as `apply_single_qubit_gate`, skipping the pairs
+    // whose control bit is 0.
+    for i in (0..self.state_vector.len()).step_by(stride * 2) {
+        for j in i..(i + stride) {
+            if (j & control_mask) == 0 {
+                continue;
+            }
+            let amplitude0 = self.state_vector[j];
+            let amplitude1 = self.state_vector[j + stride];
+
+            self.state_vector[j]          = g00 * amplitude0 + g01 * amplitude1;
+            self.state_vector[j + stride] = g10 * amplitude0 + g11 * amplitude1;
+        }
+    }
+}
+
+/// Applies an arbitrary two-qubit gate, given as a 4x4 matrix, to a pair of qubits.
+///
+/// The matrix acts on the basis |q1 q2⟩ ordered |00⟩, |01⟩, |10⟩, |11⟩, so for
+/// `gates::CNOT` the first qubit is the control and the second the target.
+pub fn apply_two_qubit_gate(&mut self, q1: usize, q2: usize, gate_matrix: &[[Complex<F>; 4]; 4]) -> &mut Self {
+    assert_ne!(q1, q2, "A two-qubit gate needs two distinct qubits.");
+    self.apply_operation(Operation::TwoQubit(q1, q2, *gate_matrix));
+    self
+}
+
+/// Applies a 4x4 matrix to the amplitudes of a pair of qubits.
+fn apply_two_qubit_matrix(&mut self, q1: usize, q2: usize, gate_matrix: &[[Complex<F>; 4]; 4]) {
+    let mask1 = 1 << q1;
+    let mask2 = 1 << q2;
+
+    // Visit each group of four amplitudes once, starting from the index where
+    // both qubits are 0.
+    for i in 0..self.state_vector.len() {
+        if i & (mask1 | mask2) != 0 {
+            continue;
+        }
+        let indices = [i, i | mask2, i | mask1, i | mask1 | mask2];
+        let amplitudes = indices.map(|k| self.state_vector[k]);
+
+        for (row, &k) in indices.iter().enumerate() {
+            self.state_vector[k] = (0..4).map(|col| gate_matrix[row][col] * amplitudes[col]).sum();
+        }
+    }
+}
+
 /// Applies a CNOT gate to the circuit.
 fn apply_cnot_gate(&mut self, control_qubit: usize, target_qubit: usize) {
     let control_mask = 1 << control_qubit;
@@ -109,12 +778,97 @@ fn apply_cnot_gate(&mut self, control_qubit: usize, target_qubit: usize) {
     }
 }
 
+/// Applies a Toffoli (CCNOT) gate to the circuit.
+fn apply_toffoli_gate(&mut self, control1: usize, control2: usize, target_qubit: usize) {
+    let c1_mask = 1 << control1;
+    let c2_mask = 1 << control2;
+    let target_mask = 1 << target_qubit;
+
+    for i in 0..self.state_vector.len() {
+        // Only basis states with both control bits set are affected.
+        if (i & c1_mask) != 0 && (i & c2_mask) != 0 {
+            let j = i ^ target_mask; // XOR flips the target bit.
+
+            // To avoid swapping twice, only swap from the smaller index.
+            if i < j {
+                self.state_vector.swap(i, j);
+            }
+        }
+    }
+}
+
+/// Applies a multi-controlled Z gate, negating every basis state where the
+/// control and target bits are all 1.
+fn apply_mcz_gate(&mut self, controls: &[usize], target_qubit: usize) {
+    let mask = controls.iter().fold(1 << target_qubit, |mask, &c| mask | (1 << c));
+
+    for (i, amplitude) in self.state_vector.iter_mut().enumerate() {
+        if i & mask == mask {
+            *amplitude = -*amplitude;
+        }
+    }
+}
+
+/// Applies a controlled-phase gate to the circuit, multiplying every basis
+/// state where both qubits are 1 by e^{iλ}.
+fn apply_controlled_phase_gate(&mut self, control_qubit: usize, target_qubit: usize, lambda: F) {
+    let both_mask = (1 << control_qubit) | (1 << target_qubit);
+    let phase = Complex::from_polar(1.0, lambda);
+
+    for (i, amplitude) in self.state_vector.iter_mut().enumerate() {
+        if (i & both_mask) == both_mask {
+            *amplitude *= phase;
+        }
+    }
+}
+
+/// Applies a controlled-Z gate to the circuit.
+fn apply_cz_gate(&mut self, control_qubit: usize, target_qubit: usize) {
+    let both_mask = (1 << control_qubit) | (1 << target_qubit);
+
+    // Flip the sign of every basis state |i⟩ where both bits are 1.
+    for (i, amplitude) in self.state_vector.iter_mut().enumerate() {
+        if (i & both_mask) == both_mask {
+            *amplitude = -*amplitude;
+        }
+    }
+}
+
+/// Applies a SWAP gate to the circuit.
+fn apply_swap_gate(&mut self, qubit1: usize, qubit2: usize) {
+    let mask1 = 1 << qubit1;
+    let mask2 = 1 << qubit2;
+
+    for i in 0..self.state_vector.len() {
+        // Each pair |..0..1..⟩ <-> |..1..0..⟩ is swapped exactly once, from the
+        // side where qubit1 is 0 and qubit2 is 1.
+        if (i & mask1) == 0 && (i & mask2) != 0 {
+            let j = i ^ mask1 ^ mask2; // Flip both bits.
+            self.state_vector.swap(i, j);
+        }
+    }
+}
+
 /// Measures the entire quantum circuit.
 /// Returns the classical outcome as an integer.
 pub fn measure(&mut self) -> usize {
-    // 1. Get a random number generator.
-    let mut rng = rand::rng();
-    // Generate a random float between 0.0 and 1.0.
+    self.measure_with_rng(&mut rand::rng())
+}
+
+/// Measures the entire quantum circuit, drawing the random sample from `rng`.
+/// Returns the classical outcome as an integer.
+///
+/// Passing a seeded generator makes the outcome reproducible. The state is
+/// assumed to be normalized, i.e. its probabilities sum to ~1.0.
+pub fn measure_with_rng<R: Rng + ?Sized>(&mut self, rng: &mut R) -> usize {
+    let outcome = self.collapse_all(rng);
+    self.operations.push(Operation::MeasureAll);
+    outcome
+}
+
+/// Samples a basis state and collapses the whole register onto it.
+fn collapse_all<R: Rng + ?Sized>(&mut self, rng: &mut R) -> usize {
+    // 1. Generate a random float between 0.0 and 1.0.
     let random_sample: f64 = rng.random();
 
     // 2. Calculate the cumulative probability distribution.
@@ -140,6 +894,260 @@ pub fn measure(&mut self) -> usize {
     // Fallback in case of floating point errors, should not be reached.
     self.state_vector.len() - 1
 }
+
+/// Resets a qubit to |0⟩ by measuring it and flipping it back if it read 1.
+/// The rest of the register is left in the renormalized post-measurement state.
+pub fn reset(&mut self, target_qubit: usize) -> &mut Self {
+    self.reset_with_rng(target_qubit, &mut rand::rng())
+}
+
+/// Resets a qubit to |0⟩ like `reset`, drawing the measurement's random sample
+/// from `rng` so the post-reset state of the rest of the register is
+/// reproducible.
+pub fn reset_with_rng<R: Rng + ?Sized>(&mut self, target_qubit: usize, rng: &mut R) -> &mut Self {
+    self.check_qubit(target_qubit);
+    self.reset_qubit(target_qubit, rng);
+    self.operations.push(Operation::Reset(target_qubit));
+    self
+}
+
+/// Collapses a qubit and flips it back to |0⟩ if it read 1.
+fn reset_qubit<R: Rng + ?Sized>(&mut self, target_qubit: usize, rng: &mut R) {
+    if self.collapse_qubit(target_qubit, rng) == 1 {
+        self.apply_single_qubit_gate(target_qubit, &gates::PAULI_X);
+    }
+}
+
+/// Returns the reduced density matrix of a single qubit, obtained by tracing
+/// out every other qubit. Entry `[a][b]` is ⟨a|ρ|b⟩ in the qubit's basis.
+pub fn reduced_density_matrix(&self, qubit: usize) -> [[Complex<F>; 2]; 2] {
+    self.check_qubit(qubit);
+    let mask = 1 << qubit;
+    let mut rho = [[Complex::new(0.0, 0.0); 2]; 2];
+
+    for i in (0..self.state_vector.len()).filter(|i| i & mask == 0) {
+        let amplitudes = [self.state_vector[i], self.state_vector[i | mask]];
+        for (a, &amplitude_a) in amplitudes.iter().enumerate() {
+            for (b, &amplitude_b) in amplitudes.iter().enumerate() {
+                rho[a][b] += amplitude_a * amplitude_b.conj();
+            }
+        }
+    }
+    rho
+}
+
+/// Returns the purity Tr(ρ²) of a single qubit's reduced state: 1.0 when the
+/// qubit is unentangled with the rest of the register, down to 0.5 when it
+/// is maximally entangled.
+pub fn purity(&self, qubit: usize) -> f64 {
+    // ρ is Hermitian, so Tr(ρ²) is the sum of |ρ_ab|².
+    self.reduced_density_matrix(qubit).iter().flatten().map(|entry| entry.norm_sqr()).sum()
+}
+
+/// Returns `true` if the state factorizes into a product of single-qubit
+/// states, i.e. no qubit is entangled with any other.
+///
+/// For a pure state this holds exactly when every qubit's reduced state is
+/// pure, so each purity is checked to be within `tolerance` of 1.0.
+pub fn is_product_state(&self, tolerance: f64) -> bool {
+    (0..self.num_qubits).all(|qubit| 1.0 - self.purity(qubit) <= tolerance)
+}
+
+/// Returns the von Neumann entropy, in bits, of the reduced state of the
+/// qubits in `partition`: 0.0 when they are unentangled with the rest of the
+/// register, and 1.0 for one half of a Bell pair.
+///
+/// # Panics
+/// Panics if a qubit is out of range or listed more than once.
+pub fn entanglement_entropy(&self, partition: &[usize]) -> f64 {
+    let mut partition_mask = 0;
+    for &qubit in partition {
+        self.check_qubit(qubit);
+        assert!(partition_mask & (1 << qubit) == 0, "qubit {} is listed twice in the partition", qubit);
+        partition_mask |= 1 << qubit;
+    }
+
+    // The state is pure, so the partition and its complement have the same
+    // nonzero spectrum; trace out onto whichever side is smaller.
+    let complement_mask = (self.state_vector.len() - 1) & !partition_mask;
+    let kept_mask = if partition.len() <= self.num_qubits / 2 { partition_mask } else { complement_mask };
+    let traced_mask = (self.state_vector.len() - 1) & !kept_mask;
+
+    let kept: Vec<usize> = (0..self.num_qubits).filter(|q| kept_mask & (1 << q) != 0).collect();
+    let dimension = 1 << kept.len();
+    // Maps a row of the reduced matrix to the kept bits of a basis state.
+    let spread = |row: usize| -> usize {
+        kept.iter().enumerate().filter(|&(bit, _)| row & (1 << bit) != 0).map(|(_, &q)| 1 << q).sum()
+    };
+    let rows: Vec<usize> = (0..dimension).map(spread).collect();
+
+    let mut rho = vec![Complex::new(0.0, 0.0); dimension * dimension];
+    for traced in (0..self.state_vector.len()).filter(|i| i & !traced_mask == 0) {
+        for (a, &row_a) in rows.iter().enumerate() {
+            let amplitude_a = self.state_vector[traced | row_a];
+            for (b, &row_b) in rows.iter().enumerate() {
+                rho[a * dimension + b] += amplitude_a * self.state_vector[traced | row_b].conj();
+            }
+        }
+    }
+
+    hermitian_eigenvalues(&rho, dimension)
+        .into_iter()
+        .filter(|&lambda| lambda > 1e-12)
+        .map(|lambda| -lambda * lambda.log2())
+        .sum()
+}
+
+/// Returns the exact expectation value ⟨Z⟩ of a single qubit: the probability
+/// of reading 0 minus the probability of reading 1.
+pub fn expectation_z(&self, qubit: usize) -> f64 {
+    self.expectation_pauli(&[(qubit, 'Z')]).expect("A single Z is a valid Pauli string.")
+}
+
+/// Returns the exact expectation value of a tensor product of Pauli operators,
+/// given as `(qubit, 'X' | 'Y' | 'Z' | 'I')` pairs.
+///
+/// The X and Y factors are rotated into the computational basis on a copy of
+/// the state, which is then read analytically, so there is no sampling noise.
+///
+/// # Errors
+/// Returns `CircuitError::DuplicateQubit` if a qubit is listed more than once,
+/// since a product such as `X·Y` on one qubit is not Hermitian and has no real
+/// expectation value, and `CircuitError::UnknownPauli` if an operator is not
+/// one of `X`, `Y`, `Z` or `I`.
+///
+/// # Panics
+/// Panics if a qubit is out of range.
+pub fn expectation_pauli(&self, ops: &[(usize, char)]) -> Result<f64, CircuitError> {
+    let mut rotated = QuantumCircuit {
+        num_qubits: self.num_qubits,
+        state_vector: self.state_vector.clone(),
+        operations: Vec::new(),
+    };
+
+    let mut parity_mask = 0;
+    for (i, &(qubit, op)) in ops.iter().enumerate() {
+        self.check_qubit(qubit);
+        if ops[..i].iter().any(|&(seen, _)| seen == qubit) {
+            return Err(CircuitError::DuplicateQubit(qubit));
+        }
+        match op.to_ascii_uppercase() {
+            'I' => continue,
+            'Z' => {}
+            'X' => rotated.apply_single_qubit_gate(qubit, &gates::HADAMARD),
+            'Y' => {
+                // S† then H maps the Y eigenbasis onto |0⟩ and |1⟩.
+                rotated.apply_single_qubit_gate(qubit, &gates::phase(-std::f64::consts::FRAC_PI_2));
+                rotated.apply_single_qubit_gate(qubit, &gates::HADAMARD);
+            }
+            other => return Err(CircuitError::UnknownPauli(other)),
+        }
+        parity_mask |= 1 << qubit;
+    }
+
+    // Each basis state contributes +1 or -1 depending on the parity of the
+    // measured bits.
+    Ok(rotated
+        .state_vector
+        .iter()
+        .enumerate()
+        .map(|(i, amplitude)| {
+            let sign = if (i & parity_mask).count_ones() % 2 == 0 { 1.0 } else { -1.0 };
+            sign * amplitude.norm_sqr()
+        })
+        .sum())
+}
+
+/// Samples the current state `shots` times without collapsing it.
+/// Returns how many times each basis-state index was observed.
+///
+/// The cumulative distribution is built once, and each shot is resolved with a
+/// binary search, so large shot counts stay cheap.
+pub fn sample(&self, shots: usize, rng: &mut impl Rng) -> HashMap<usize, usize> {
+    let cumulative: Vec<f64> = self
+        .state_vector
+        .iter()
+        .scan(0.0, |total, amplitude| {
+            *total += amplitude.norm_sqr();
+            Some(*total)
+        })
+        .collect();
+    let last_index = cumulative.len() - 1;
+
+    let mut counts = HashMap::new();
+    for _ in 0..shots {
+        let random_sample: f64 = rng.random();
+        // The first index whose cumulative probability exceeds the sample. Clamp
+        // in case floating point errors leave the total just below 1.0.
+        let index = cumulative.partition_point(|&p| p <= random_sample).min(last_index);
+        *counts.entry(index).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Samples the current state `shots` times without collapsing it, like
+/// `sample`, but keys the counts by the outcome's bitstring.
+///
+/// Each key has `num_qubits` digits with the highest qubit first, so qubit 0
+/// is the rightmost digit.
+pub fn measure_bitstrings(&self, shots: usize, rng: &mut impl Rng) -> HashMap<String, usize> {
+    self.sample(shots, rng)
+        .into_iter()
+        .map(|(index, count)| (format!("{:0width$b}", index, width = self.num_qubits), count))
+        .collect()
+}
+
+/// Measures a single qubit, leaving the rest of the register in superposition.
+/// Returns the classical outcome, 0 or 1.
+///
+/// The outcome is sampled from the qubit's marginal probability. Amplitudes
+/// that disagree with the outcome are zeroed and the survivors are renormalized,
+/// so any qubits entangled with the measured one collapse consistently.
+pub fn measure_qubit(&mut self, target: usize) -> u8 {
+    self.measure_qubit_with_rng(target, &mut rand::rng())
+}
+
+/// Measures a single qubit like `measure_qubit`, drawing the random sample
+/// from `rng` so the outcome is reproducible.
+pub fn measure_qubit_with_rng<R: Rng + ?Sized>(&mut self, target: usize, rng: &mut R) -> u8 {
+    self.check_qubit(target);
+    let outcome = self.collapse_qubit(target, rng);
+    self.operations.push(Operation::Measure(target));
+    outcome
+}
+
+/// Samples a single qubit's outcome and collapses the register to match it.
+fn collapse_qubit<R: Rng + ?Sized>(&mut self, target: usize, rng: &mut R) -> u8 {
+    let mask = 1 << target;
+
+    // 1. Calculate the marginal probabilities of the target reading 0 and 1.
+    let (mut prob_zero, mut prob_one) = (0.0, 0.0);
+    for (i, amplitude) in self.state_vector.iter().enumerate() {
+        if i & mask != 0 {
+            prob_one += amplitude.norm_sqr();
+        } else {
+            prob_zero += amplitude.norm_sqr();
+        }
+    }
+
+    // 2. Sample the outcome. Scaling the sample by the total probability means a
+    // marginal of exactly 0 or 1 can never select an empty branch.
+    let random_sample: f64 = rng.random();
+    let outcome = if random_sample * (prob_zero + prob_one) < prob_one { 1 } else { 0 };
+    let retained_prob = if outcome == 1 { prob_one } else { prob_zero };
+
+    // 3. Collapse: drop the inconsistent amplitudes and renormalize the rest.
+    let scale = 1.0 / retained_prob.sqrt();
+    for (i, amplitude) in self.state_vector.iter_mut().enumerate() {
+        if ((i & mask != 0) as u8) == outcome {
+            *amplitude *= scale;
+        } else {
+            *amplitude = Complex::new(0.0, 0.0);
+        }
+    }
+
+    outcome
+}
 }
 
 
@@ -159,4 +1167,973 @@ impl fmt::Display for QuantumCircuit {
         }
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// Returns the eigenvalues of an `n` × `n` Hermitian matrix stored row by row.
+///
+/// H = A + iB has the same spectrum as the real symmetric matrix
+/// [[A, -B], [B, A]] with every eigenvalue doubled, so this diagonalizes the
+/// latter with cyclic Jacobi rotations and keeps every other eigenvalue.
+fn hermitian_eigenvalues(matrix: &[Complex<F>], n: usize) -> Vec<F> {
+    let size = 2 * n;
+    let mut a = vec![0.0; size * size];
+    for row in 0..n {
+        for col in 0..n {
+            let entry = matrix[row * n + col];
+            a[row * size + col] = entry.re;
+            a[(row + n) * size + col + n] = entry.re;
+            a[row * size + col + n] = -entry.im;
+            a[(row + n) * size + col] = entry.im;
+        }
+    }
+
+    for _sweep in 0..100 {
+        let off_diagonal: F = (0..size)
+            .flat_map(|p| (p + 1..size).map(move |q| (p, q)))
+            .map(|(p, q)| a[p * size + q] * a[p * size + q])
+            .sum();
+        if off_diagonal < 1e-24 {
+            break;
+        }
+
+        for p in 0..size {
+            for q in p + 1..size {
+                let apq = a[p * size + q];
+                if apq.abs() < 1e-300 {
+                    continue;
+                }
+                // Choose the rotation angle that zeroes a[p][q].
+                let theta = (a[q * size + q] - a[p * size + p]) / (2.0 * apq);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+
+                for k in 0..size {
+                    let (x, y) = (a[k * size + p], a[k * size + q]);
+                    a[k * size + p] = c * x - s * y;
+                    a[k * size + q] = s * x + c * y;
+                }
+                for k in 0..size {
+                    let (x, y) = (a[p * size + k], a[q * size + k]);
+                    a[p * size + k] = c * x - s * y;
+                    a[q * size + k] = s * x + c * y;
+                }
+            }
+        }
+    }
+
+    let mut eigenvalues: Vec<F> = (0..size).map(|i| a[i * size + i]).collect();
+    eigenvalues.sort_by(|x, y| x.total_cmp(y));
+    eigenvalues.into_iter().step_by(2).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    /// Asserts that two circuits hold the same state vector, up to rounding.
+    fn assert_same_state(a: &QuantumCircuit, b: &QuantumCircuit) {
+        assert_eq!(a.state_vector().len(), b.state_vector().len());
+        for (i, (x, y)) in a.state_vector().iter().zip(b.state_vector()).enumerate() {
+            assert!((x - y).norm() < 1e-12, "amplitude {} differs: {} vs {}", i, x, y);
+        }
+    }
+
+    #[test]
+    fn cnot_matrix_matches_the_cnot_method_on_every_basis_input() {
+        for n in [2, 3] {
+            for control in 0..n {
+                for target in (0..n).filter(|&t| t != control) {
+                    for basis in 0..1 << n {
+                        let mut direct = QuantumCircuit::new(n);
+                        let mut matrix = QuantumCircuit::new(n);
+                        for q in (0..n).filter(|q| basis >> q & 1 == 1) {
+                            direct.x(q);
+                            matrix.x(q);
+                        }
+                        direct.cnot(control, target);
+                        matrix.apply_two_qubit_gate(control, target, &gates::CNOT);
+                        assert_same_state(&direct, &matrix);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn two_sqrt_iswaps_make_an_iswap() {
+        let mut twice = QuantumCircuit::new(2);
+        twice.h(0).apply_two_qubit_gate(0, 1, &gates::SQRT_ISWAP).apply_two_qubit_gate(0, 1, &gates::SQRT_ISWAP);
+        let mut once = QuantumCircuit::new(2);
+        once.h(0).apply_two_qubit_gate(0, 1, &gates::ISWAP);
+        assert_same_state(&twice, &once);
+    }
+
+    #[test]
+    fn swap_matrix_exchanges_the_two_qubits() {
+        let mut circuit = QuantumCircuit::new(2);
+        circuit.x(0).apply_two_qubit_gate(1, 0, &gates::SWAP);
+        assert!((circuit.amplitude(0b10) - 1.0).norm() < 1e-12);
+    }
+
+    #[test]
+    fn measuring_one_qubit_collapses_its_entangled_partner() {
+        for _ in 0..50 {
+            let mut circuit = QuantumCircuit::new(3);
+            circuit.h(0).cnot(0, 2).h(1);
+            let outcome = circuit.measure_qubit(0);
+            // Qubit 1 is still in superposition, but qubit 2 must agree with qubit 0.
+            assert!((circuit.norm() - 1.0).abs() < 1e-12);
+            assert!(circuit.expectation_z(1).abs() < 1e-12);
+            let full = circuit.measure();
+            assert_eq!((full & 1) as u8, outcome);
+            assert_eq!((full >> 2 & 1) as u8, outcome);
+        }
+    }
+
+    #[test]
+    fn measuring_a_qubit_with_a_certain_outcome_leaves_the_state_unchanged() {
+        let mut circuit = QuantumCircuit::new(2);
+        circuit.x(1);
+        for _ in 0..20 {
+            assert_eq!(circuit.measure_qubit(1), 1);
+            assert_eq!(circuit.measure_qubit(0), 0);
+        }
+        assert!((circuit.amplitude(0b10) - 1.0).norm() < 1e-12);
+    }
+
+    #[test]
+    fn seeded_measurements_are_reproducible() {
+        let run = |seed| {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            (0..20)
+                .map(|_| {
+                    let mut circuit = QuantumCircuit::new(3);
+                    circuit.h(0).h(1).h(2);
+                    circuit.measure_with_rng(&mut rng)
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(run(7), run(7));
+        assert_ne!(run(7), run(8));
+    }
+
+    #[test]
+    fn measurement_collapses_onto_the_outcome() {
+        let mut circuit = QuantumCircuit::new(2);
+        circuit.h(0).cnot(0, 1);
+        let outcome = circuit.measure_with_rng(&mut ChaCha8Rng::seed_from_u64(1));
+        assert!(outcome == 0b00 || outcome == 0b11);
+        assert!((circuit.amplitude(outcome) - 1.0).norm() < 1e-12);
+        assert_eq!(circuit.operations().last(), Some(&Operation::MeasureAll));
+    }
+
+    #[test]
+    fn rotations_are_undone_by_their_negated_angles() {
+        let mut circuit = QuantumCircuit::new(2);
+        circuit.h(0).ry(1, 0.7);
+        let before = circuit.state_vector().to_vec();
+        circuit.rz(0, 1.3).rz(0, -1.3).rx(1, 0.4).rx(1, -0.4).phase(0, 0.9).phase(0, -0.9);
+        for (after, before) in circuit.state_vector().iter().zip(&before) {
+            assert!((after - before).norm() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn rx_by_pi_flips_the_qubit_with_a_phase_of_minus_i() {
+        let mut circuit = QuantumCircuit::new(1);
+        circuit.rx(0, std::f64::consts::PI);
+        assert!(circuit.amplitude(0).norm() < 1e-12);
+        assert!((circuit.amplitude(1) - Complex::new(0.0, -1.0)).norm() < 1e-12);
+    }
+
+    #[test]
+    fn unitary_applies_to_the_highest_qubit() {
+        let mut circuit = QuantumCircuit::new(3);
+        circuit.unitary(2, &gates::PAULI_X);
+        assert!((circuit.amplitude(0b100) - 1.0).norm() < 1e-12);
+    }
+
+    #[test]
+    fn apply_unitary_checked_rejects_a_non_unitary_matrix() {
+        let shear = [
+            [Complex::new(1.0, 0.0), Complex::new(1.0, 0.0)],
+            [Complex::new(0.0, 0.0), Complex::new(1.0, 0.0)],
+        ];
+        let mut circuit = QuantumCircuit::new(1);
+        assert_eq!(circuit.apply_unitary_checked(0, &shear).err(), Some(CircuitError::NotUnitary));
+        assert!(circuit.operations().is_empty());
+        assert!((circuit.amplitude(0) - 1.0).norm() < 1e-12);
+
+        assert!(circuit.apply_unitary_checked(0, &gates::HADAMARD).is_ok());
+        assert!((circuit.probabilities()[1] - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn sampling_a_hadamard_splits_the_shots_evenly_without_collapsing() {
+        let mut circuit = QuantumCircuit::new(1);
+        circuit.h(0);
+        let before = circuit.state_vector().to_vec();
+
+        let counts = circuit.sample(10_000, &mut ChaCha8Rng::seed_from_u64(3));
+        assert_eq!(counts[&0] + counts[&1], 10_000);
+        assert!(counts[&0].abs_diff(5_000) < 300, "{:?}", counts);
+        assert_eq!(circuit.state_vector(), &before[..]);
+    }
+
+    #[test]
+    fn sampling_a_basis_state_always_gives_that_state() {
+        let mut circuit = QuantumCircuit::new(2);
+        circuit.x(1);
+        let counts = circuit.sample(10, &mut ChaCha8Rng::seed_from_u64(3));
+        assert_eq!(counts, HashMap::from([(0b10, 10)]));
+    }
+
+    #[test]
+    fn read_apis_reflect_the_current_state() {
+        let mut circuit = QuantumCircuit::new(2);
+        circuit.h(0);
+        assert_eq!(circuit.num_qubits(), 2);
+        assert_eq!(circuit.state_vector().len(), 4);
+
+        let probabilities = circuit.probabilities();
+        assert!((probabilities[0] - 0.5).abs() < 1e-12);
+        assert!((probabilities[1] - 0.5).abs() < 1e-12);
+        assert_eq!(&probabilities[2..], [0.0, 0.0]);
+    }
+
+    #[test]
+    fn swap_moves_a_one_to_the_other_qubit() {
+        let mut circuit = QuantumCircuit::new(2);
+        circuit.x(0).swap(0, 1);
+        assert!((circuit.amplitude(0b10) - 1.0).norm() < 1e-12);
+    }
+
+    #[test]
+    fn cz_negates_only_the_state_with_both_qubits_set() {
+        let mut circuit = QuantumCircuit::new(2);
+        circuit.h(0).h(1).cz(0, 1);
+        for (i, amplitude) in circuit.state_vector().iter().enumerate() {
+            let expected = if i == 0b11 { -0.5 } else { 0.5 };
+            assert!((amplitude - expected).norm() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn cz_and_swap_match_their_registry_matrices() {
+        let prepare = || {
+            let mut circuit = QuantumCircuit::new(3);
+            circuit.h(0).ry(1, 0.3).rx(2, 1.1);
+            circuit
+        };
+        let (mut direct, mut matrix) = (prepare(), prepare());
+        direct.swap(2, 0).cz(2, 1);
+        matrix.apply_two_qubit_gate(0, 2, &gates::SWAP).apply_two_qubit_gate(1, 2, &gates::CZ);
+        assert_same_state(&direct, &matrix);
+    }
+
+    #[test]
+    #[should_panic(expected = "distinct control and target")]
+    fn cz_rejects_identical_control_and_target() {
+        QuantumCircuit::new(2).cz(1, 1);
+    }
+
+    #[test]
+    fn toffoli_computes_and_into_an_ancilla() {
+        for a in 0..2 {
+            for b in 0..2 {
+                let mut circuit = QuantumCircuit::new(3);
+                if a == 1 {
+                    circuit.x(0);
+                }
+                if b == 1 {
+                    circuit.x(1);
+                }
+                circuit.toffoli(0, 1, 2);
+                let expected = a | b << 1 | (a & b) << 2;
+                assert!((circuit.amplitude(expected) - 1.0).norm() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "three distinct qubits")]
+    fn toffoli_rejects_a_repeated_qubit() {
+        QuantumCircuit::new(3).toffoli(0, 2, 2);
+    }
+
+    #[test]
+    fn reset_returns_an_entangled_control_to_zero() {
+        for seed in 0..30 {
+            let mut circuit = QuantumCircuit::new(2);
+            circuit.h(0).cnot(0, 1).reset_with_rng(0, &mut ChaCha8Rng::seed_from_u64(seed));
+            let probabilities = circuit.probabilities();
+            assert!(probabilities[0b01] + probabilities[0b11] < 1e-12);
+            assert!((probabilities.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+            assert_eq!(circuit.operations().last(), Some(&Operation::Reset(0)));
+        }
+    }
+
+    #[test]
+    fn seeded_resets_leave_the_same_state() {
+        let run = || {
+            let mut rng = ChaCha8Rng::seed_from_u64(11);
+            (0..10)
+                .map(|_| {
+                    let mut circuit = QuantumCircuit::new(2);
+                    circuit.h(0).cnot(0, 1).reset_with_rng(0, &mut rng);
+                    circuit.most_likely()
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    fn qft_maps_each_basis_state_to_its_fourier_phases() {
+        let (n, size) = (3, 8);
+        for x in 0..size {
+            let mut circuit = QuantumCircuit::new(n);
+            for q in (0..n).filter(|q| x >> q & 1 == 1) {
+                circuit.x(q);
+            }
+            circuit.qft(&[0, 1, 2]);
+            for k in 0..size {
+                let angle = std::f64::consts::TAU * (x * k) as F / size as F;
+                let expected = Complex::from_polar(1.0 / (size as F).sqrt(), angle);
+                assert!((circuit.amplitude(k) - expected).norm() < 1e-9, "x = {}, k = {}", x, k);
+            }
+        }
+    }
+
+    #[test]
+    fn iqft_undoes_qft() {
+        let mut circuit = QuantumCircuit::new(4);
+        circuit.h(0).ry(1, 0.4).rx(3, 1.2).cnot(0, 2);
+        let before = circuit.state_vector().to_vec();
+
+        for qubits in [&[0, 1, 2, 3][..], &[3, 1]] {
+            circuit.qft(qubits).iqft(qubits);
+            for (after, before) in circuit.state_vector().iter().zip(&before) {
+                assert!((after - before).norm() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn tensor_puts_the_first_register_in_the_low_bits() {
+        let mut one = QuantumCircuit::new(1);
+        one.x(0);
+        let zero = QuantumCircuit::new(1);
+        assert!((one.tensor(&zero).amplitude(0b01) - 1.0).norm() < 1e-12);
+        assert!((zero.tensor(&one).amplitude(0b10) - 1.0).norm() < 1e-12);
+    }
+
+    #[test]
+    fn tensor_matches_preparing_the_joint_register_directly() {
+        let mut low = QuantumCircuit::new(2);
+        low.h(0).ry(1, 0.3);
+        let mut high = QuantumCircuit::new(1);
+        high.rx(0, 0.8);
+        let mut joint = QuantumCircuit::new(3);
+        joint.h(0).ry(1, 0.3).rx(2, 0.8);
+
+        let combined = low.tensor(&high);
+        assert_same_state(&combined, &joint);
+        assert_eq!(combined.operations(), joint.operations());
+    }
+
+    #[test]
+    fn expectation_z_reads_plus_one_minus_one_and_zero() {
+        let zero = QuantumCircuit::new(1);
+        let mut one = QuantumCircuit::new(1);
+        one.x(0);
+        let mut plus = QuantumCircuit::new(1);
+        plus.h(0);
+
+        assert!((zero.expectation_z(0) - 1.0).abs() < 1e-12);
+        assert!((one.expectation_z(0) + 1.0).abs() < 1e-12);
+        assert!(plus.expectation_z(0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn expectation_pauli_measures_bell_pair_correlations() {
+        let bell = QuantumCircuit::bell_pair();
+        assert!((bell.expectation_pauli(&[(0, 'X'), (1, 'X')]).unwrap() - 1.0).abs() < 1e-12);
+        assert!((bell.expectation_pauli(&[(0, 'Y'), (1, 'Y')]).unwrap() + 1.0).abs() < 1e-12);
+        assert!((bell.expectation_pauli(&[(0, 'Z'), (1, 'z')]).unwrap() - 1.0).abs() < 1e-12);
+        assert!(bell.expectation_pauli(&[(0, 'X'), (1, 'I')]).unwrap().abs() < 1e-12);
+    }
+
+    #[test]
+    fn expectation_pauli_rejects_invalid_pauli_strings() {
+        let bell = QuantumCircuit::bell_pair();
+        assert_eq!(bell.expectation_pauli(&[(0, 'X'), (0, 'Y')]), Err(CircuitError::DuplicateQubit(0)));
+        assert_eq!(bell.expectation_pauli(&[(1, 'Q')]), Err(CircuitError::UnknownPauli('Q')));
+    }
+
+    #[test]
+    fn normalize_restores_unit_norm_after_a_non_unitary_gate() {
+        let mut scale = [[Complex::new(0.0, 0.0); 4]; 4];
+        for (i, row) in scale.iter_mut().enumerate() {
+            row[i] = Complex::new(3.0, 0.0);
+        }
+        let mut circuit = QuantumCircuit::new(2);
+        circuit.h(0).apply_two_qubit_gate(0, 1, &scale);
+        assert!((circuit.norm() - 3.0).abs() < 1e-12);
+
+        circuit.normalize();
+        assert!((circuit.norm() - 1.0).abs() < 1e-12);
+        assert!((circuit.probabilities()[0] - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn normalize_leaves_a_zero_state_alone() {
+        let mut circuit = QuantumCircuit::new(2);
+        circuit.apply_two_qubit_gate(0, 1, &[[Complex::new(0.0, 0.0); 4]; 4]);
+        circuit.normalize();
+        assert_eq!(circuit.norm(), 0.0);
+    }
+
+    #[test]
+    fn controlled_hadamard_acts_only_when_the_control_is_set() {
+        let mut controlled = QuantumCircuit::new(2);
+        controlled.ry(1, 0.3).controlled_unitary(0, 1, &gates::HADAMARD);
+        let mut idle = QuantumCircuit::new(2);
+        idle.ry(1, 0.3);
+        assert_same_state(&controlled, &idle);
+
+        let mut controlled = QuantumCircuit::new(2);
+        controlled.x(0).controlled_unitary(0, 1, &gates::HADAMARD);
+        let mut applied = QuantumCircuit::new(2);
+        applied.x(0).h(1);
+        assert_same_state(&controlled, &applied);
+    }
+
+    #[test]
+    fn controlled_pauli_x_is_a_cnot() {
+        let prepare = || {
+            let mut circuit = QuantumCircuit::new(3);
+            circuit.h(0).h(2).ry(1, 0.4);
+            circuit
+        };
+        let (mut controlled, mut cnot) = (prepare(), prepare());
+        controlled.controlled_unitary(2, 0, &gates::PAULI_X);
+        cnot.cnot(2, 0);
+        assert_same_state(&controlled, &cnot);
+    }
+
+    #[test]
+    #[should_panic(expected = "distinct control and target")]
+    fn controlled_unitary_rejects_identical_control_and_target() {
+        QuantumCircuit::new(2).controlled_unitary(0, 0, &gates::HADAMARD);
+    }
+
+    #[test]
+    fn bell_pair_and_ghz_split_their_amplitude_between_all_zeros_and_all_ones() {
+        let half = std::f64::consts::FRAC_1_SQRT_2;
+        for (n, circuit) in [(2, QuantumCircuit::bell_pair()), (3, QuantumCircuit::ghz(3)), (5, QuantumCircuit::ghz(5))] {
+            let state = circuit.state_vector();
+            assert_eq!(state.len(), 1 << n);
+            let last = state.len() - 1;
+            for (i, amplitude) in state.iter().enumerate() {
+                let expected = if i == 0 || i == last { half } else { 0.0 };
+                assert!((amplitude - Complex::new(expected, 0.0)).norm() < 1e-12, "n = {}, index {}", n, i);
+            }
+        }
+    }
+
+    #[test]
+    fn ghz_of_one_qubit_is_the_plus_state() {
+        let mut plus = QuantumCircuit::new(1);
+        plus.h(0);
+        assert_same_state(&QuantumCircuit::ghz(1), &plus);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one qubit")]
+    fn ghz_rejects_zero_qubits() {
+        QuantumCircuit::ghz(0);
+    }
+
+    #[test]
+    fn a_random_circuit_followed_by_its_inverse_returns_to_all_zeros() {
+        let mut rng = ChaCha8Rng::seed_from_u64(21);
+        let n = 4;
+        let mut circuit = QuantumCircuit::new(n);
+        for _ in 0..40 {
+            let a = rng.random_range(0..n);
+            let b = (a + rng.random_range(1..n)) % n;
+            let angle = rng.random_range(-3.0..3.0);
+            match rng.random_range(0..8) {
+                0 => circuit.h(a),
+                1 => circuit.x(a),
+                2 => circuit.phase(a, std::f64::consts::FRAC_PI_2),
+                3 => circuit.rz(a, angle),
+                4 => circuit.ry(a, angle),
+                5 => circuit.cnot(a, b),
+                6 => circuit.cz(a, b),
+                _ => circuit.unitary(a, &gates::rx(angle)),
+            };
+        }
+
+        let inverse = circuit.inverse();
+        circuit.append(&inverse, &[0, 1, 2, 3]);
+        for (i, amplitude) in circuit.state_vector().iter().enumerate() {
+            let expected = if i == 0 { 1.0 } else { 0.0 };
+            assert!((amplitude - Complex::new(expected, 0.0)).norm() < 1e-9, "amplitude {} is {}", i, amplitude);
+        }
+    }
+
+    #[test]
+    fn inverse_records_the_adjoints_in_reverse_order() {
+        let mut circuit = QuantumCircuit::new(2);
+        circuit.h(0).rz(1, 0.3).cnot(0, 1);
+        let inverse = circuit.inverse();
+        assert_eq!(inverse.operations(), &[Operation::Cnot(0, 1), Operation::Rz(1, -0.3), Operation::H(0)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot invert")]
+    fn inverse_rejects_a_circuit_with_a_reset() {
+        let mut circuit = QuantumCircuit::new(1);
+        circuit.h(0).reset(0);
+        circuit.inverse();
+    }
+
+    /// Flips the sign of basis state `marked` across `qubits` with an MCZ,
+    /// conjugated by X on every qubit whose bit in `marked` is 0.
+    fn mark(circuit: &mut QuantumCircuit, qubits: &[usize], marked: usize) {
+        let zeros: Vec<usize> = qubits.iter().copied().filter(|&q| marked & (1 << q) == 0).collect();
+        for &q in &zeros {
+            circuit.x(q);
+        }
+        let (&target, controls) = qubits.split_last().unwrap();
+        circuit.mcz(controls, target);
+        for &q in &zeros {
+            circuit.x(q);
+        }
+    }
+
+    #[test]
+    fn mcz_negates_only_the_all_ones_state() {
+        let mut circuit = QuantumCircuit::new(3);
+        circuit.h(0).h(1).h(2).mcz(&[0, 1], 2);
+        let amplitude = 1.0 / 8f64.sqrt();
+        for (i, a) in circuit.state_vector().iter().enumerate() {
+            let expected = if i == 7 { -amplitude } else { amplitude };
+            assert!((a - Complex::new(expected, 0.0)).norm() < 1e-12, "index {}", i);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot also be a control")]
+    fn mcz_rejects_a_target_among_the_controls() {
+        QuantumCircuit::new(2).mcz(&[0, 1], 1);
+    }
+
+    #[test]
+    fn one_grover_iteration_finds_the_marked_state_among_four() {
+        for marked in 0..4 {
+            let mut circuit = QuantumCircuit::new(2);
+            circuit.h(0).h(1);
+            mark(&mut circuit, &[0, 1], marked);
+            circuit.grover_diffusion(&[0, 1]);
+            let probability = circuit.probabilities()[marked];
+            assert!(probability > 0.9, "marked {} has probability {}", marked, probability);
+        }
+    }
+
+    #[test]
+    fn two_grover_iterations_amplify_one_state_among_eight() {
+        let qubits = [0, 1, 2];
+        let mut circuit = QuantumCircuit::new(3);
+        for &q in &qubits {
+            circuit.h(q);
+        }
+        for _ in 0..2 {
+            mark(&mut circuit, &qubits, 5);
+            circuit.grover_diffusion(&qubits);
+        }
+        assert!(circuit.probabilities()[5] > 0.9);
+    }
+
+    #[test]
+    fn each_half_of_a_bell_pair_is_maximally_mixed() {
+        let bell = QuantumCircuit::bell_pair();
+        for qubit in 0..2 {
+            let rho = bell.reduced_density_matrix(qubit);
+            let expected = [[0.5, 0.0], [0.0, 0.5]];
+            for (a, row) in rho.iter().enumerate() {
+                for (b, entry) in row.iter().enumerate() {
+                    assert!((entry - Complex::new(expected[a][b], 0.0)).norm() < 1e-12);
+                }
+            }
+            assert!((bell.purity(qubit) - 0.5).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn an_unentangled_qubit_reduces_to_its_own_pure_state() {
+        let theta: f64 = 1.2;
+        let mut circuit = QuantumCircuit::new(3);
+        circuit.h(0).cnot(0, 2).ry(1, theta);
+
+        // ry(θ)|0⟩ = cos(θ/2)|0⟩ + sin(θ/2)|1⟩.
+        let (c, s) = ((theta / 2.0).cos(), (theta / 2.0).sin());
+        let rho = circuit.reduced_density_matrix(1);
+        let expected = [[c * c, c * s], [c * s, s * s]];
+        for (a, row) in rho.iter().enumerate() {
+            for (b, entry) in row.iter().enumerate() {
+                assert!((entry - Complex::new(expected[a][b], 0.0)).norm() < 1e-12);
+            }
+        }
+        assert!((circuit.purity(1) - 1.0).abs() < 1e-12);
+        assert!((circuit.purity(0) - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn bell_pair_bitstrings_are_only_all_zeros_and_all_ones() {
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        let counts = QuantumCircuit::bell_pair().measure_bitstrings(4000, &mut rng);
+        let mut keys: Vec<&String> = counts.keys().collect();
+        keys.sort();
+        assert_eq!(keys, ["00", "11"]);
+        assert_eq!(counts["00"] + counts["11"], 4000);
+        assert!(counts["00"].abs_diff(2000) < 200, "got {:?}", counts);
+    }
+
+    #[test]
+    fn bitstrings_put_the_highest_qubit_first_and_keep_leading_zeros() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut circuit = QuantumCircuit::new(4);
+        circuit.x(1);
+        let counts = circuit.measure_bitstrings(10, &mut rng);
+        assert_eq!(counts.len(), 1);
+        assert_eq!(counts["0010"], 10);
+    }
+
+    /// Runs `gate` on a fresh 2-qubit circuit and returns its panic message.
+    fn panic_message(gate: impl FnOnce(&mut QuantumCircuit) -> &mut QuantumCircuit) -> String {
+        let mut circuit = QuantumCircuit::new(2);
+        let payload = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            gate(&mut circuit);
+        })).unwrap_err();
+        payload
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| payload.downcast_ref::<&str>().map(|s| s.to_string()))
+            .unwrap()
+    }
+
+    #[test]
+    #[should_panic(expected = "qubit index 3 out of range for 2-qubit circuit")]
+    fn h_rejects_an_out_of_range_target() {
+        QuantumCircuit::new(2).h(3);
+    }
+
+    #[test]
+    fn every_gate_names_the_out_of_range_qubit() {
+        let expected = "qubit index 2 out of range for 2-qubit circuit";
+        assert_eq!(panic_message(|c| c.x(2)), expected);
+        assert_eq!(panic_message(|c| c.ry(2, 0.5)), expected);
+        assert_eq!(panic_message(|c| c.phase(2, 0.5)), expected);
+        assert_eq!(panic_message(|c| c.cnot(2, 0)), expected);
+        assert_eq!(panic_message(|c| c.cz(0, 2)), expected);
+        assert_eq!(panic_message(|c| c.swap(1, 2)), expected);
+        assert_eq!(panic_message(|c| c.reset(2)), expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "distinct control and target")]
+    fn cnot_rejects_the_same_control_and_target() {
+        QuantumCircuit::new(2).cnot(1, 1);
+    }
+
+    #[test]
+    fn a_rejected_cnot_leaves_the_state_and_log_untouched() {
+        let mut circuit = QuantumCircuit::new(2);
+        circuit.h(0).h(1);
+        let (state, operations) = (circuit.state_vector().to_vec(), circuit.operations().to_vec());
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            circuit.cnot(1, 1);
+        }));
+        assert!(result.is_err());
+        assert_eq!(circuit.state_vector(), &state[..]);
+        assert_eq!(circuit.operations(), &operations[..]);
+    }
+
+    #[test]
+    fn amplitude_of_bits_reads_the_ket_label_highest_qubit_first() {
+        let one = Complex::new(1.0, 0.0);
+        let mut circuit = QuantumCircuit::new(2);
+        circuit.x(0);
+        assert_eq!(circuit.amplitude_of_bits(&[0, 1]), one);
+        assert_eq!(circuit.amplitude(1), one);
+        assert_eq!(circuit.amplitude_of_bits(&[1, 0]), Complex::new(0.0, 0.0));
+
+        let mut three = QuantumCircuit::new(3);
+        three.x(2).x(0);
+        assert_eq!(three.amplitude_of_bits(&[1, 0, 1]), one);
+        assert_eq!(three.amplitude(0b101), one);
+    }
+
+    #[test]
+    #[should_panic(expected = "basis state 4 out of range for 2-qubit circuit")]
+    fn amplitude_rejects_an_index_past_the_register() {
+        QuantumCircuit::new(2).amplitude(4);
+    }
+
+    #[test]
+    #[should_panic(expected = "one bit per qubit")]
+    fn amplitude_of_bits_rejects_the_wrong_number_of_bits() {
+        QuantumCircuit::new(2).amplitude_of_bits(&[1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "must be 0 or 1")]
+    fn amplitude_of_bits_rejects_a_non_binary_digit() {
+        QuantumCircuit::new(2).amplitude_of_bits(&[0, 2]);
+    }
+
+    #[test]
+    fn each_qubit_of_a_bell_pair_carries_one_bit_of_entanglement() {
+        let bell = QuantumCircuit::bell_pair();
+        assert!((bell.entanglement_entropy(&[0]) - 1.0).abs() < 1e-9);
+        assert!((bell.entanglement_entropy(&[1]) - 1.0).abs() < 1e-9);
+        assert!(bell.entanglement_entropy(&[0, 1]).abs() < 1e-9);
+    }
+
+    #[test]
+    fn a_product_state_has_no_entanglement_entropy() {
+        let mut circuit = QuantumCircuit::new(3);
+        circuit.h(0).ry(1, 0.8).x(2);
+        for partition in [&[][..], &[0], &[1, 2], &[0, 2]] {
+            assert!(circuit.entanglement_entropy(partition).abs() < 1e-9, "partition {:?}", partition);
+        }
+    }
+
+    #[test]
+    fn entanglement_entropy_counts_the_pairs_cut_by_the_partition() {
+        // Bell pairs on (0, 1) and (2, 3).
+        let mut pairs = QuantumCircuit::new(4);
+        pairs.h(0).cnot(0, 1).h(2).cnot(2, 3);
+        assert!(pairs.entanglement_entropy(&[0, 1]).abs() < 1e-9);
+        assert!((pairs.entanglement_entropy(&[0, 2]) - 2.0).abs() < 1e-9);
+        assert!((pairs.entanglement_entropy(&[0, 2, 3]) - 1.0).abs() < 1e-9);
+
+        let ghz = QuantumCircuit::ghz(5);
+        assert!((ghz.entanglement_entropy(&[1, 3]) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn partial_entanglement_gives_the_binary_entropy_of_the_split() {
+        let theta: f64 = 1.1;
+        let mut circuit = QuantumCircuit::new(2);
+        circuit.ry(0, theta).cnot(0, 1);
+        let p = (theta / 2.0).cos().powi(2);
+        let expected = -p * p.log2() - (1.0 - p) * (1.0 - p).log2();
+        assert!((circuit.entanglement_entropy(&[1]) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    #[should_panic(expected = "listed twice")]
+    fn entanglement_entropy_rejects_a_repeated_qubit() {
+        QuantumCircuit::bell_pair().entanglement_entropy(&[0, 0]);
+    }
+
+    /// A 3-qubit circuit prepared in basis state `index`.
+    fn basis_state(index: usize) -> QuantumCircuit {
+        let mut circuit = QuantumCircuit::new(3);
+        for qubit in (0..3).filter(|q| index & (1 << q) != 0) {
+            circuit.x(qubit);
+        }
+        circuit
+    }
+
+    #[test]
+    fn cphase_by_pi_is_cz_on_every_basis_state() {
+        for index in 0..8 {
+            let mut with_cphase = basis_state(index);
+            with_cphase.cphase(0, 2, std::f64::consts::PI);
+            let mut with_cz = basis_state(index);
+            with_cz.cz(0, 2);
+            assert_same_state(&with_cphase, &with_cz);
+        }
+    }
+
+    #[test]
+    fn cphase_multiplies_only_states_with_both_qubits_set() {
+        let lambda = 0.7;
+        let mut circuit = QuantumCircuit::new(2);
+        circuit.h(0).h(1).cphase(1, 0, lambda);
+        for (index, amplitude) in circuit.state_vector().iter().enumerate() {
+            let phase = if index == 3 { Complex::from_polar(1.0, lambda) } else { Complex::new(1.0, 0.0) };
+            assert!((amplitude - phase * 0.5).norm() < 1e-12, "index {}", index);
+        }
+
+        let mut swapped = QuantumCircuit::new(2);
+        swapped.h(0).h(1).cphase(0, 1, lambda);
+        assert_same_state(&circuit, &swapped);
+    }
+
+    #[test]
+    fn cphase_kicks_its_phase_back_onto_a_superposed_control() {
+        let lambda = 1.3;
+        let mut circuit = QuantumCircuit::new(2);
+        circuit.h(0).x(1).cphase(0, 1, lambda);
+        // The target stays |1⟩ while the control picks up the relative phase.
+        let half = std::f64::consts::FRAC_1_SQRT_2;
+        assert!((circuit.amplitude(0b10) - Complex::new(half, 0.0)).norm() < 1e-12);
+        assert!((circuit.amplitude(0b11) - Complex::from_polar(half, lambda)).norm() < 1e-12);
+    }
+
+    #[test]
+    fn appended_subcircuit_matches_applying_its_gate_directly() {
+        let mut apply_h = QuantumCircuit::new(1);
+        apply_h.h(0);
+
+        let mut appended = QuantumCircuit::new(3);
+        appended.x(0).append(&apply_h, &[2]);
+        let mut direct = QuantumCircuit::new(3);
+        direct.x(0).h(2);
+
+        assert_same_state(&appended, &direct);
+        assert_eq!(appended.operations(), &[Operation::X(0), Operation::H(2)]);
+    }
+
+    #[test]
+    fn append_remaps_multi_qubit_operations() {
+        let mut entangle = QuantumCircuit::new(2);
+        entangle.h(0).cnot(0, 1);
+
+        let mut appended = QuantumCircuit::new(3);
+        appended.append(&entangle, &[2, 0]);
+        let mut direct = QuantumCircuit::new(3);
+        direct.h(2).cnot(2, 0);
+
+        assert_same_state(&appended, &direct);
+        assert_eq!(appended.operations(), &[Operation::H(2), Operation::Cnot(2, 0)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "one mapped qubit per qubit")]
+    fn append_rejects_a_map_of_the_wrong_length() {
+        let sub = QuantumCircuit::new(2);
+        QuantumCircuit::new(3).append(&sub, &[0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "mapped to more than once")]
+    fn append_rejects_a_repeated_target_qubit() {
+        let sub = QuantumCircuit::new(2);
+        QuantumCircuit::new(3).append(&sub, &[1, 1]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn append_rejects_a_target_qubit_outside_the_circuit() {
+        let sub = QuantumCircuit::new(1);
+        QuantumCircuit::new(2).append(&sub, &[2]);
+    }
+
+    #[test]
+    fn append_with_rng_replays_measurements_and_resets_reproducibly() {
+        let mut sub = QuantumCircuit::new(2);
+        sub.h(0).cnot(0, 1);
+        sub.measure_qubit(0);
+        sub.h(1).reset(1);
+
+        for seed in 0..8 {
+            let mut appended = QuantumCircuit::new(3);
+            appended.append_with_rng(&sub, &[2, 0], &mut ChaCha8Rng::seed_from_u64(seed));
+
+            let mut direct = QuantumCircuit::new(3);
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            direct.h(2).cnot(2, 0);
+            direct.measure_qubit_with_rng(2, &mut rng);
+            direct.h(0).reset_with_rng(0, &mut rng);
+
+            assert_same_state(&appended, &direct);
+            assert_eq!(appended.operations(), direct.operations());
+        }
+    }
+
+    #[test]
+    fn significant_states_of_a_bell_pair_are_the_two_correlated_outcomes() {
+        let bell = QuantumCircuit::bell_pair();
+        let states = bell.significant_states(0.4);
+        assert_eq!(states.iter().map(|&(index, _)| index).collect::<Vec<_>>(), vec![0, 3]);
+        assert!(states.iter().all(|&(_, p)| (p - 0.5).abs() < 1e-12));
+        assert!(bell.significant_states(0.5).is_empty());
+    }
+
+    #[test]
+    fn significant_states_are_listed_from_most_to_least_likely() {
+        // Ry(θ) leaves |1⟩ with probability sin²(θ/2) = 0.75 here.
+        let mut circuit = QuantumCircuit::new(2);
+        circuit.ry(0, 2.0 * std::f64::consts::FRAC_PI_3);
+        let states = circuit.significant_states(1e-10);
+        assert_eq!(states.len(), 2);
+        assert_eq!((states[0].0, states[1].0), (1, 0));
+        assert!((states[0].1 - 0.75).abs() < 1e-12);
+        assert!((states[1].1 - 0.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn most_likely_finds_the_largest_probability_without_measuring() {
+        let mut circuit = QuantumCircuit::new(3);
+        circuit.x(1).ry(2, 0.5);
+        let before = circuit.state_vector().to_vec();
+        assert_eq!(circuit.most_likely(), 0b010);
+        assert_eq!(circuit.state_vector(), &before[..]);
+
+        circuit.ry(2, 2.0);
+        assert_eq!(circuit.most_likely(), 0b110);
+    }
+
+    #[test]
+    fn most_likely_breaks_ties_towards_the_lowest_index() {
+        assert_eq!(QuantumCircuit::bell_pair().most_likely(), 0);
+        let mut circuit = QuantumCircuit::new(2);
+        circuit.x(0).h(1);
+        assert_eq!(circuit.most_likely(), 0b01);
+    }
+
+    #[test]
+    fn is_product_state_accepts_unentangled_registers() {
+        let mut circuit = QuantumCircuit::new(2);
+        circuit.h(1);
+        assert!(circuit.is_product_state(1e-9));
+
+        circuit.ry(0, 0.4).rz(1, 1.1).x(0);
+        assert!(circuit.is_product_state(1e-9));
+        assert!(QuantumCircuit::new(3).is_product_state(1e-9));
+    }
+
+    #[test]
+    fn is_product_state_rejects_entangled_registers() {
+        assert!(!QuantumCircuit::bell_pair().is_product_state(1e-9));
+        assert!(!QuantumCircuit::ghz(3).is_product_state(1e-9));
+
+        // A Bell pair alongside an untouched qubit still does not factorize.
+        let mut circuit = QuantumCircuit::new(3);
+        circuit.h(0).cnot(0, 2);
+        assert!(!circuit.is_product_state(1e-9));
+
+        // Undoing the entangling gate restores a product state.
+        circuit.cnot(0, 2);
+        assert!(circuit.is_product_state(1e-9));
+    }
+
+    #[test]
+    fn is_product_state_tolerates_entanglement_within_the_tolerance() {
+        // cos(θ/2)|00⟩ + sin(θ/2)|11⟩ has single-qubit purity cos⁴ + sin⁴ ≈ 0.995.
+        let mut circuit = QuantumCircuit::new(2);
+        circuit.ry(0, 0.1).cnot(0, 1);
+        assert!(circuit.is_product_state(0.01));
+        assert!(!circuit.is_product_state(1e-3));
+    }
+}
@@ -0,0 +1,102 @@
+//! # Control Module
+//
+// Provides a small feedback controller for steering a measured quantity, such
+// as the Gowers norm of a path, toward a target by adjusting a parameter.
+
+/// A proportional-integral-derivative controller.
+///
+/// The error is `target - measured`, so with positive gains the adjustment is
+/// positive when the measurement is below the target. For a process where
+/// increasing the parameter lowers the measurement (e.g. a turn penalty that
+/// lowers a path's U2 norm), use negative gains.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PidController {
+    /// The proportional gain.
+    pub kp: f64,
+    /// The integral gain.
+    pub ki: f64,
+    /// The derivative gain.
+    pub kd: f64,
+    /// The sum of all errors seen so far.
+    integral: f64,
+    /// The error from the previous update, if there was one.
+    previous_error: Option<f64>,
+}
+
+impl PidController {
+    /// Creates a controller with the given gains and no accumulated state.
+    pub fn new(kp: f64, ki: f64, kd: f64) -> Self {
+        Self { kp, ki, kd, integral: 0.0, previous_error: None }
+    }
+
+    /// Creates a pure proportional controller.
+    pub fn proportional(kp: f64) -> Self {
+        Self::new(kp, 0.0, 0.0)
+    }
+
+    /// Feeds in the latest measurement and returns the adjustment to apply to
+    /// the controlled parameter.
+    ///
+    /// Each call counts as one time step. The derivative term is zero on the
+    /// first update, since there is no previous error to compare against.
+    pub fn update(&mut self, measured: f64, target: f64) -> f64 {
+        let error = target - measured;
+        self.integral += error;
+        let derivative = self.previous_error.map_or(0.0, |previous| error - previous);
+        self.previous_error = Some(error);
+
+        self.kp * error + self.ki * self.integral + self.kd * derivative
+    }
+
+    /// Clears the accumulated integral and previous error, keeping the gains.
+    pub fn reset(&mut self) {
+        self.integral = 0.0;
+        self.previous_error = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_proportional_controller_drives_a_plant_monotonically_to_its_setpoint() {
+        // The plant's output moves by whatever adjustment it is given.
+        let mut controller = PidController::proportional(0.5);
+        let (target, mut output) = (10.0, 0.0);
+        let mut previous_gap = f64::INFINITY;
+        for _ in 0..40 {
+            output += controller.update(output, target);
+            let gap = target - output;
+            assert!(gap >= 0.0 && gap < previous_gap);
+            previous_gap = gap;
+        }
+        assert!(previous_gap < 1e-9);
+    }
+
+    #[test]
+    fn the_integral_term_removes_a_steady_offset() {
+        // A constant leak pulls the output down every step, which a pure
+        // proportional controller can only offset by holding a steady error.
+        let run = |mut controller: PidController| {
+            let mut output = 0.0;
+            for _ in 0..200 {
+                output += controller.update(output, 1.0) - 0.2;
+            }
+            output
+        };
+        assert!((run(PidController::proportional(0.5)) - 0.6).abs() < 1e-9);
+        assert!((run(PidController::new(0.5, 0.2, 0.0)) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn the_derivative_term_starts_at_zero_and_reset_clears_the_state() {
+        let mut controller = PidController::new(0.0, 1.0, 1.0);
+        assert_eq!(controller.update(0.0, 2.0), 2.0);
+        // Integral 2 + 3, derivative 3 - 2.
+        assert_eq!(controller.update(-1.0, 2.0), 6.0);
+
+        controller.reset();
+        assert_eq!(controller.update(0.0, 2.0), 2.0);
+    }
+}
@@ -0,0 +1,369 @@
+//! # Density Module
+//!
+//! Provides a density-matrix simulator for mixed states, so decoherence and
+//! noise channels can be modelled alongside the pure-state `QuantumCircuit`.
+
+use crate::circuit::QuantumCircuit;
+use crate::gates;
+use num_complex::Complex;
+
+type F = f64;
+
+/// A register of qubits described by a 2^n × 2^n density matrix ρ.
+///
+/// Gates act as ρ → UρU† and noise channels as ρ → Σ KρK† over their Kraus
+/// operators. This costs far more memory and time than a state vector, so use
+/// it only when mixed states are needed.
+///
+/// Like `QuantumCircuit`, every gate and channel panics with a descriptive
+/// message if it names a qubit outside the register.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DensityMatrixCircuit {
+    num_qubits: usize,
+    /// The density matrix, stored row by row (`row * dimension + col`).
+    rho: Vec<Complex<F>>,
+}
+
+impl DensityMatrixCircuit {
+    /// Creates a register of `num_qubits` qubits in the pure state |00...0⟩.
+    pub fn new(num_qubits: usize) -> Self {
+        let dimension = 1 << num_qubits;
+        let mut rho = vec![Complex::new(0.0, 0.0); dimension * dimension];
+        rho[0] = Complex::new(1.0, 0.0);
+        Self { num_qubits, rho }
+    }
+
+    /// Creates the pure density matrix |ψ⟩⟨ψ| of a circuit's current state.
+    pub fn from_circuit(circuit: &QuantumCircuit) -> Self {
+        let psi = circuit.state_vector();
+        let rho = psi.iter().flat_map(|&a| psi.iter().map(move |&b| a * b.conj())).collect();
+        Self { num_qubits: circuit.num_qubits(), rho }
+    }
+
+    /// Returns the number of qubits in the register.
+    pub fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    /// Returns the side length of the density matrix, 2^n.
+    fn dimension(&self) -> usize {
+        1 << self.num_qubits
+    }
+
+    /// Returns the density matrix, stored row by row.
+    pub fn density_matrix(&self) -> &[Complex<F>] {
+        &self.rho
+    }
+
+    /// Returns the entry `ρ[row][col]`.
+    pub fn element(&self, row: usize, col: usize) -> Complex<F> {
+        self.rho[row * self.dimension() + col]
+    }
+
+    /// Returns the probability of each basis state, i.e. the diagonal of ρ.
+    pub fn probabilities(&self) -> Vec<F> {
+        (0..self.dimension()).map(|i| self.element(i, i).re).collect()
+    }
+
+    /// Returns the purity tr(ρ²): 1.0 for a pure state, down to 1/2^n for
+    /// the maximally mixed state.
+    pub fn purity(&self) -> F {
+        // ρ is Hermitian, so tr(ρ²) is the sum of |ρ_ij|².
+        self.rho.iter().map(|entry| entry.norm_sqr()).sum()
+    }
+
+    /// Applies a Hadamard gate to the target qubit.
+    pub fn h(&mut self, target_qubit: usize) -> &mut Self {
+        self.unitary(target_qubit, &gates::HADAMARD)
+    }
+
+    /// Applies a Pauli-X (NOT) gate to the target qubit.
+    pub fn x(&mut self, target_qubit: usize) -> &mut Self {
+        self.unitary(target_qubit, &gates::PAULI_X)
+    }
+
+    /// Applies a Pauli-Y gate to the target qubit.
+    pub fn y(&mut self, target_qubit: usize) -> &mut Self {
+        self.unitary(target_qubit, &gates::PAULI_Y)
+    }
+
+    /// Applies a Pauli-Z gate to the target qubit.
+    pub fn z(&mut self, target_qubit: usize) -> &mut Self {
+        self.unitary(target_qubit, &gates::PAULI_Z)
+    }
+
+    /// Applies an Rx(θ) rotation to the target qubit.
+    pub fn rx(&mut self, target_qubit: usize, theta: F) -> &mut Self {
+        self.unitary(target_qubit, &gates::rx(theta))
+    }
+
+    /// Applies an Ry(θ) rotation to the target qubit.
+    pub fn ry(&mut self, target_qubit: usize, theta: F) -> &mut Self {
+        self.unitary(target_qubit, &gates::ry(theta))
+    }
+
+    /// Applies an Rz(θ) rotation to the target qubit.
+    pub fn rz(&mut self, target_qubit: usize, theta: F) -> &mut Self {
+        self.unitary(target_qubit, &gates::rz(theta))
+    }
+
+    /// Applies a phase gate, diag(1, e^{iλ}), to the target qubit.
+    pub fn phase(&mut self, target_qubit: usize, lambda: F) -> &mut Self {
+        self.unitary(target_qubit, &gates::phase(lambda))
+    }
+
+    /// Applies an arbitrary single-qubit gate to the target qubit. The matrix
+    /// is assumed to be unitary.
+    pub fn unitary(&mut self, target_qubit: usize, matrix: &[[Complex<F>; 2]; 2]) -> &mut Self {
+        self.check_qubit(target_qubit);
+        self.conjugate_single(target_qubit, matrix);
+        self
+    }
+
+    /// Applies a CNOT gate.
+    pub fn cnot(&mut self, control_qubit: usize, target_qubit: usize) -> &mut Self {
+        self.apply_two_qubit_gate(control_qubit, target_qubit, &gates::CNOT)
+    }
+
+    /// Applies a controlled-Z gate.
+    pub fn cz(&mut self, control_qubit: usize, target_qubit: usize) -> &mut Self {
+        self.apply_two_qubit_gate(control_qubit, target_qubit, &gates::CZ)
+    }
+
+    /// Swaps the states of two qubits.
+    pub fn swap(&mut self, qubit1: usize, qubit2: usize) -> &mut Self {
+        self.apply_two_qubit_gate(qubit1, qubit2, &gates::SWAP)
+    }
+
+    /// Applies an arbitrary two-qubit gate, with the same basis ordering as
+    /// `QuantumCircuit::apply_two_qubit_gate`.
+    pub fn apply_two_qubit_gate(&mut self, q1: usize, q2: usize, gate_matrix: &[[Complex<F>; 4]; 4]) -> &mut Self {
+        self.check_qubit(q1);
+        self.check_qubit(q2);
+        assert_ne!(q1, q2, "A two-qubit gate needs two distinct qubits.");
+        self.left_multiply_two(q1, q2, gate_matrix);
+        self.conjugate_transpose();
+        self.left_multiply_two(q1, q2, gate_matrix);
+        self
+    }
+
+    /// Applies the depolarizing channel to a qubit: with probability `p` its
+    /// state is replaced by the maximally mixed state I/2.
+    ///
+    /// # Panics
+    /// Panics if `p` is not in `[0, 1]`.
+    pub fn depolarizing(&mut self, qubit: usize, p: F) -> &mut Self {
+        assert!((0.0..=1.0).contains(&p), "Depolarizing probability must be in [0, 1].");
+
+        // (1 - p)ρ + p·I/2 ⊗ tr_q(ρ), written as the Kraus operators
+        // √(1 - 3p/4)·I and √(p/4)·X, Y, Z.
+        let scaled = |matrix: &[[Complex<F>; 2]; 2], factor: F| matrix.map(|row| row.map(|entry| entry * factor));
+        let (one, zero) = (Complex::new(1.0, 0.0), Complex::new(0.0, 0.0));
+        let identity = [[one, zero], [zero, one]];
+        let pauli_weight = (p / 4.0).sqrt();
+        self.apply_channel(
+            qubit,
+            &[
+                scaled(&identity, (1.0 - 3.0 * p / 4.0).sqrt()),
+                scaled(&gates::PAULI_X, pauli_weight),
+                scaled(&gates::PAULI_Y, pauli_weight),
+                scaled(&gates::PAULI_Z, pauli_weight),
+            ],
+        );
+        self
+    }
+
+    /// Applies the amplitude damping channel to a qubit, which models energy
+    /// loss: |1⟩ decays to |0⟩ with probability `gamma`.
+    ///
+    /// # Panics
+    /// Panics if `gamma` is not in `[0, 1]`.
+    pub fn amplitude_damping(&mut self, qubit: usize, gamma: F) -> &mut Self {
+        assert!((0.0..=1.0).contains(&gamma), "Damping probability must be in [0, 1].");
+
+        let zero = Complex::new(0.0, 0.0);
+        self.apply_channel(
+            qubit,
+            &[
+                [[Complex::new(1.0, 0.0), zero], [zero, Complex::new((1.0 - gamma).sqrt(), 0.0)]],
+                [[zero, Complex::new(gamma.sqrt(), 0.0)], [zero, zero]],
+            ],
+        );
+        self
+    }
+
+    /// Replaces ρ with Σ KρK† over the given single-qubit Kraus operators.
+    fn apply_channel(&mut self, qubit: usize, kraus: &[[[Complex<F>; 2]; 2]]) {
+        self.check_qubit(qubit);
+        let mut result = vec![Complex::new(0.0, 0.0); self.rho.len()];
+        for operator in kraus {
+            let mut term = self.clone();
+            term.conjugate_single(qubit, operator);
+            for (total, entry) in result.iter_mut().zip(term.rho) {
+                *total += entry;
+            }
+        }
+        self.rho = result;
+    }
+
+    /// Panics with a descriptive message if `qubit` is not in the register.
+    fn check_qubit(&self, qubit: usize) {
+        assert!(
+            qubit < self.num_qubits,
+            "qubit index {} out of range for {}-qubit register",
+            qubit,
+            self.num_qubits
+        );
+    }
+
+    /// Replaces ρ with MρM† for a single-qubit matrix M.
+    ///
+    /// Since ρ is Hermitian, MρM† = M(Mρ)†, so this only needs left
+    /// multiplication and a conjugate transpose.
+    fn conjugate_single(&mut self, target_qubit: usize, matrix: &[[Complex<F>; 2]; 2]) {
+        self.left_multiply_single(target_qubit, matrix);
+        self.conjugate_transpose();
+        self.left_multiply_single(target_qubit, matrix);
+    }
+
+    /// Replaces ρ with Mρ, acting on the row index of every column.
+    fn left_multiply_single(&mut self, target_qubit: usize, matrix: &[[Complex<F>; 2]; 2]) {
+        let dimension = self.dimension();
+        let mask = 1 << target_qubit;
+
+        for row in (0..dimension).filter(|row| row & mask == 0) {
+            for col in 0..dimension {
+                let a0 = self.rho[row * dimension + col];
+                let a1 = self.rho[(row | mask) * dimension + col];
+                self.rho[row * dimension + col] = matrix[0][0] * a0 + matrix[0][1] * a1;
+                self.rho[(row | mask) * dimension + col] = matrix[1][0] * a0 + matrix[1][1] * a1;
+            }
+        }
+    }
+
+    /// Replaces ρ with Mρ for a two-qubit matrix M, acting on the row index of
+    /// every column.
+    fn left_multiply_two(&mut self, q1: usize, q2: usize, matrix: &[[Complex<F>; 4]; 4]) {
+        let dimension = self.dimension();
+        let mask1 = 1 << q1;
+        let mask2 = 1 << q2;
+
+        for row in (0..dimension).filter(|row| row & (mask1 | mask2) == 0) {
+            let rows = [row, row | mask2, row | mask1, row | mask1 | mask2];
+            for col in 0..dimension {
+                let amplitudes = rows.map(|r| self.rho[r * dimension + col]);
+                for (i, &r) in rows.iter().enumerate() {
+                    self.rho[r * dimension + col] = (0..4).map(|k| matrix[i][k] * amplitudes[k]).sum();
+                }
+            }
+        }
+    }
+
+    /// Replaces ρ with its conjugate transpose.
+    fn conjugate_transpose(&mut self) {
+        let dimension = self.dimension();
+        for row in 0..dimension {
+            self.rho[row * dimension + row] = self.rho[row * dimension + row].conj();
+            for col in row + 1..dimension {
+                let upper = self.rho[row * dimension + col];
+                let lower = self.rho[col * dimension + row];
+                self.rho[row * dimension + col] = lower.conj();
+                self.rho[col * dimension + row] = upper.conj();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Asserts that two density matrices agree entry by entry, up to rounding.
+    fn assert_same_rho(a: &DensityMatrixCircuit, b: &DensityMatrixCircuit) {
+        for (i, (x, y)) in a.density_matrix().iter().zip(b.density_matrix()).enumerate() {
+            assert!((x - y).norm() < 1e-12, "entry {} differs: {} vs {}", i, x, y);
+        }
+    }
+
+    #[test]
+    fn full_depolarizing_drives_any_qubit_to_the_maximally_mixed_state() {
+        let (half, zero) = (Complex::new(0.5, 0.0), Complex::new(0.0, 0.0));
+        let mut plus = DensityMatrixCircuit::new(1);
+        plus.h(0);
+        let mut rotated = DensityMatrixCircuit::new(1);
+        rotated.ry(0, 0.7).rz(0, 1.9);
+
+        for mut register in [DensityMatrixCircuit::new(1), plus, rotated] {
+            register.depolarizing(0, 1.0);
+            for (entry, expected) in register.density_matrix().iter().zip([half, zero, zero, half]) {
+                assert!((entry - expected).norm() < 1e-12, "got {}", entry);
+            }
+            assert!((register.purity() - 0.5).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn gates_on_a_density_matrix_match_the_pure_state_circuit() {
+        let mut circuit = QuantumCircuit::new(3);
+        circuit.h(0).cnot(0, 1).ry(2, 0.4).cz(1, 2).rz(0, -1.1).swap(0, 2);
+
+        let mut register = DensityMatrixCircuit::new(3);
+        register.h(0).cnot(0, 1).ry(2, 0.4).cz(1, 2).rz(0, -1.1).swap(0, 2);
+
+        assert_same_rho(&register, &DensityMatrixCircuit::from_circuit(&circuit));
+        assert!((register.purity() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn amplitude_damping_moves_population_from_one_to_zero() {
+        let mut register = DensityMatrixCircuit::new(1);
+        register.x(0).amplitude_damping(0, 0.25);
+        let probabilities = register.probabilities();
+        assert!((probabilities[0] - 0.25).abs() < 1e-12);
+        assert!((probabilities[1] - 0.75).abs() < 1e-12);
+
+        register.amplitude_damping(0, 1.0);
+        assert_same_rho(&register, &DensityMatrixCircuit::new(1));
+    }
+
+    #[test]
+    fn noise_channels_preserve_the_trace() {
+        let mut register = DensityMatrixCircuit::new(2);
+        register.h(0).cnot(0, 1).depolarizing(1, 0.3).amplitude_damping(0, 0.6);
+        let trace: F = register.probabilities().iter().sum();
+        assert!((trace - 1.0).abs() < 1e-12);
+        assert!(register.purity() < 1.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "must be in [0, 1]")]
+    fn depolarizing_rejects_a_probability_above_one() {
+        DensityMatrixCircuit::new(1).depolarizing(0, 1.5);
+    }
+
+    /// Runs `gate` on a fresh 2-qubit register and returns its panic message.
+    fn panic_message(gate: impl FnOnce(&mut DensityMatrixCircuit) -> &mut DensityMatrixCircuit) -> String {
+        let mut register = DensityMatrixCircuit::new(2);
+        let payload = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            gate(&mut register);
+        }))
+        .unwrap_err();
+        payload
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| payload.downcast_ref::<&str>().map(|s| s.to_string()))
+            .unwrap()
+    }
+
+    #[test]
+    fn every_gate_and_channel_names_the_out_of_range_qubit() {
+        let expected = "qubit index 2 out of range for 2-qubit register";
+        assert_eq!(panic_message(|r| r.h(2)), expected);
+        assert_eq!(panic_message(|r| r.x(2)), expected);
+        assert_eq!(panic_message(|r| r.unitary(2, &gates::PAULI_Y)), expected);
+        assert_eq!(panic_message(|r| r.cnot(0, 2)), expected);
+        assert_eq!(panic_message(|r| r.apply_two_qubit_gate(2, 1, &gates::SWAP)), expected);
+        assert_eq!(panic_message(|r| r.depolarizing(2, 0.5)), expected);
+        assert_eq!(panic_message(|r| r.amplitude_damping(2, 0.5)), expected);
+    }
+}
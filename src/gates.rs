@@ -26,4 +26,110 @@ pub static PAULI_Z: [[Complex<F>; 2]; 2] = [
 pub static HADAMARD: [[Complex<F>; 2]; 2] = [
     [Complex::new(1.0 / std::f64::consts::SQRT_2, 0.0), Complex::new(1.0 / std::f64::consts::SQRT_2, 0.0)],
     [Complex::new(1.0 / std::f64::consts::SQRT_2, 0.0), Complex::new(-1.0 / std::f64::consts::SQRT_2, 0.0)],
-];
\ No newline at end of file
+];
+
+// --- Parametric single-qubit gates ---
+
+/// Builds the Rx(θ) rotation matrix, a rotation of θ about the X axis.
+pub fn rx(theta: F) -> [[Complex<F>; 2]; 2] {
+    let (sin, cos) = (theta / 2.0).sin_cos();
+    [
+        [Complex::new(cos, 0.0), Complex::new(0.0, -sin)],
+        [Complex::new(0.0, -sin), Complex::new(cos, 0.0)],
+    ]
+}
+
+/// Builds the Ry(θ) rotation matrix, a rotation of θ about the Y axis.
+pub fn ry(theta: F) -> [[Complex<F>; 2]; 2] {
+    let (sin, cos) = (theta / 2.0).sin_cos();
+    [
+        [Complex::new(cos, 0.0), Complex::new(-sin, 0.0)],
+        [Complex::new(sin, 0.0), Complex::new(cos, 0.0)],
+    ]
+}
+
+/// Builds the Rz(θ) rotation matrix, diag(e^{-iθ/2}, e^{iθ/2}).
+pub fn rz(theta: F) -> [[Complex<F>; 2]; 2] {
+    [
+        [Complex::from_polar(1.0, -theta / 2.0), Complex::new(0.0, 0.0)],
+        [Complex::new(0.0, 0.0), Complex::from_polar(1.0, theta / 2.0)],
+    ]
+}
+
+/// Builds the phase gate matrix, diag(1, e^{iλ}).
+pub fn phase(lambda: F) -> [[Complex<F>; 2]; 2] {
+    [
+        [Complex::new(1.0, 0.0), Complex::new(0.0, 0.0)],
+        [Complex::new(0.0, 0.0), Complex::from_polar(1.0, lambda)],
+    ]
+}
+
+/// Returns `true` if `M·M†` equals the identity within `tolerance`, i.e. the
+/// matrix is a valid single-qubit gate.
+pub fn is_unitary(matrix: &[[Complex<F>; 2]; 2], tolerance: F) -> bool {
+    (0..2).all(|row| {
+        (0..2).all(|col| {
+            let entry: Complex<F> = (0..2).map(|k| matrix[row][k] * matrix[col][k].conj()).sum();
+            let expected = if row == col { 1.0 } else { 0.0 };
+            (entry - expected).norm() <= tolerance
+        })
+    })
+}
+
+/// Returns the conjugate transpose (adjoint) of a gate matrix, which undoes
+/// the gate.
+pub fn dagger<const N: usize>(matrix: &[[Complex<F>; N]; N]) -> [[Complex<F>; N]; N] {
+    let mut adjoint = [[Complex::new(0.0, 0.0); N]; N];
+    for (row, entries) in matrix.iter().enumerate() {
+        for (col, entry) in entries.iter().enumerate() {
+            adjoint[col][row] = entry.conj();
+        }
+    }
+    adjoint
+}
+
+// --- Two-qubit gates ---
+//
+// These 4x4 matrices act on the basis |q1 q2⟩ ordered |00⟩, |01⟩, |10⟩, |11⟩,
+// where q1 and q2 are the first and second qubits passed to
+// `QuantumCircuit::apply_two_qubit_gate`.
+
+// The CNOT gate matrix, with q1 as the control and q2 as the target.
+pub static CNOT: [[Complex<F>; 4]; 4] = [
+    [Complex::new(1.0, 0.0), Complex::new(0.0, 0.0), Complex::new(0.0, 0.0), Complex::new(0.0, 0.0)],
+    [Complex::new(0.0, 0.0), Complex::new(1.0, 0.0), Complex::new(0.0, 0.0), Complex::new(0.0, 0.0)],
+    [Complex::new(0.0, 0.0), Complex::new(0.0, 0.0), Complex::new(0.0, 0.0), Complex::new(1.0, 0.0)],
+    [Complex::new(0.0, 0.0), Complex::new(0.0, 0.0), Complex::new(1.0, 0.0), Complex::new(0.0, 0.0)],
+];
+
+// The controlled-Z gate matrix. It is symmetric in its two qubits.
+pub static CZ: [[Complex<F>; 4]; 4] = [
+    [Complex::new(1.0, 0.0), Complex::new(0.0, 0.0), Complex::new(0.0, 0.0), Complex::new(0.0, 0.0)],
+    [Complex::new(0.0, 0.0), Complex::new(1.0, 0.0), Complex::new(0.0, 0.0), Complex::new(0.0, 0.0)],
+    [Complex::new(0.0, 0.0), Complex::new(0.0, 0.0), Complex::new(1.0, 0.0), Complex::new(0.0, 0.0)],
+    [Complex::new(0.0, 0.0), Complex::new(0.0, 0.0), Complex::new(0.0, 0.0), Complex::new(-1.0, 0.0)],
+];
+
+// The SWAP gate matrix.
+pub static SWAP: [[Complex<F>; 4]; 4] = [
+    [Complex::new(1.0, 0.0), Complex::new(0.0, 0.0), Complex::new(0.0, 0.0), Complex::new(0.0, 0.0)],
+    [Complex::new(0.0, 0.0), Complex::new(0.0, 0.0), Complex::new(1.0, 0.0), Complex::new(0.0, 0.0)],
+    [Complex::new(0.0, 0.0), Complex::new(1.0, 0.0), Complex::new(0.0, 0.0), Complex::new(0.0, 0.0)],
+    [Complex::new(0.0, 0.0), Complex::new(0.0, 0.0), Complex::new(0.0, 0.0), Complex::new(1.0, 0.0)],
+];
+
+// The iSWAP gate matrix. Swaps |01⟩ and |10⟩ with a phase of i.
+pub static ISWAP: [[Complex<F>; 4]; 4] = [
+    [Complex::new(1.0, 0.0), Complex::new(0.0, 0.0), Complex::new(0.0, 0.0), Complex::new(0.0, 0.0)],
+    [Complex::new(0.0, 0.0), Complex::new(0.0, 0.0), Complex::new(0.0, 1.0), Complex::new(0.0, 0.0)],
+    [Complex::new(0.0, 0.0), Complex::new(0.0, 1.0), Complex::new(0.0, 0.0), Complex::new(0.0, 0.0)],
+    [Complex::new(0.0, 0.0), Complex::new(0.0, 0.0), Complex::new(0.0, 0.0), Complex::new(1.0, 0.0)],
+];
+
+// The square root of iSWAP. Applying it twice gives ISWAP.
+pub static SQRT_ISWAP: [[Complex<F>; 4]; 4] = [
+    [Complex::new(1.0, 0.0), Complex::new(0.0, 0.0), Complex::new(0.0, 0.0), Complex::new(0.0, 0.0)],
+    [Complex::new(0.0, 0.0), Complex::new(1.0 / std::f64::consts::SQRT_2, 0.0), Complex::new(0.0, 1.0 / std::f64::consts::SQRT_2), Complex::new(0.0, 0.0)],
+    [Complex::new(0.0, 0.0), Complex::new(0.0, 1.0 / std::f64::consts::SQRT_2), Complex::new(1.0 / std::f64::consts::SQRT_2, 0.0), Complex::new(0.0, 0.0)],
+    [Complex::new(0.0, 0.0), Complex::new(0.0, 0.0), Complex::new(0.0, 0.0), Complex::new(1.0, 0.0)],
+];
@@ -0,0 +1,146 @@
+//! # Gowers Module
+//
+// Provides the Gowers uniformity norms used by the crate's MOMA-Gowers
+// feedback loops to measure how structured a path is.
+
+use crate::grid::Point;
+use num_complex::Complex;
+use rustfft::{Fft, FftPlanner};
+
+/// Converts a path into a sequence of unit complex numbers, one per step,
+/// whose angle is the heading of that step.
+///
+/// Paths with fewer than two points have no steps and give an empty sequence.
+pub fn path_to_turning_sequence(points: &[Point]) -> Vec<Complex<f64>> {
+    points
+        .windows(2)
+        .map(|pair| {
+            let dx = pair[1].x as f64 - pair[0].x as f64;
+            let dy = pair[1].y as f64 - pair[0].y as f64;
+            Complex::from_polar(1.0, dy.atan2(dx))
+        })
+        .collect()
+}
+
+/// Calculates the Gowers U2 norm of a sequence from its Fourier transform: the
+/// fourth root of the normalized sum of |F(k)|^4.
+///
+/// For a turning sequence, a straight line (every step in the same direction)
+/// scores the maximum of 1.0, while an irregular zigzag spreads its energy
+/// over many frequencies and scores much lower. An empty sequence scores 0.0.
+pub fn u2_norm(sequence: &[Complex<f64>]) -> f64 {
+    let n = sequence.len();
+    if n == 0 {
+        return 0.0;
+    }
+
+    let fft = FftPlanner::new().plan_fft_forward(n);
+    u2_norm_pow4(sequence, fft.as_ref()).powf(1.0 / 4.0)
+}
+
+/// Calculates the Gowers U3 norm of a sequence, treated as a function on the
+/// cyclic group of its length.
+///
+/// The U3 norm is the eighth root of the average, over `x` and shifts
+/// `h1, h2, h3`, of the products of `f` (conjugated for an odd number of
+/// shifts) over the cube `x + ω·h`. It is computed through the identity
+/// `‖f‖_U3^8 = E_h ‖Δ_h f‖_U2^4`, where `Δ_h f(x) = f(x + h) · conj(f(x))`.
+///
+/// A quadratic phase such as `e^{2πi x²/n}` scores 1.0 on U3 while scoring
+/// close to 0 on U2, so U3 detects quadratic structure that U2 misses. An
+/// empty sequence scores 0.0.
+pub fn u3_norm(sequence: &[Complex<f64>]) -> f64 {
+    let n = sequence.len();
+    if n == 0 {
+        return 0.0;
+    }
+
+    let fft = FftPlanner::new().plan_fft_forward(n);
+    let sum: f64 = (0..n)
+        .map(|h| {
+            let derivative: Vec<Complex<f64>> = (0..n).map(|x| sequence[(x + h) % n] * sequence[x].conj()).collect();
+            u2_norm_pow4(&derivative, fft.as_ref())
+        })
+        .sum();
+    (sum / n as f64).powf(1.0 / 8.0)
+}
+
+/// Calculates the fourth power of the U2 norm with a pre-planned forward FFT
+/// of the sequence's length.
+fn u2_norm_pow4(sequence: &[Complex<f64>], fft: &dyn Fft<f64>) -> f64 {
+    let n = sequence.len();
+    let mut spectrum = sequence.to_vec();
+    fft.process(&mut spectrum);
+
+    let sum_of_magnitudes_pow4: f64 = spectrum.iter().map(|c| c.norm_sqr().powi(2)).sum();
+    sum_of_magnitudes_pow4 / (n as f64).powi(4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn turning_sequence_has_one_unit_heading_per_step() {
+        let path = [Point::new(0, 0), Point::new(1, 0), Point::new(1, 1), Point::new(0, 1)];
+        let sequence = path_to_turning_sequence(&path);
+        let expected = [Complex::new(1.0, 0.0), Complex::new(0.0, 1.0), Complex::new(-1.0, 0.0)];
+        assert_eq!(sequence.len(), 3);
+        for (actual, expected) in sequence.iter().zip(expected) {
+            assert!((actual - expected).norm() < 1e-12);
+        }
+        assert!(path_to_turning_sequence(&path[..1]).is_empty());
+    }
+
+    #[test]
+    fn a_straight_line_scores_higher_than_a_random_zigzag() {
+        let line: Vec<Point> = (0..64).map(|x| Point::new(x, 3)).collect();
+        let line_norm = u2_norm(&path_to_turning_sequence(&line));
+        assert!((line_norm - 1.0).abs() < 1e-12);
+
+        // A walk that heads off in a random direction at every step.
+        let mut rng = ChaCha8Rng::seed_from_u64(11);
+        let mut zigzag = vec![Point::new(64, 64)];
+        for _ in 0..63 {
+            let last = zigzag[zigzag.len() - 1];
+            zigzag.push(match rng.random_range(0..4) {
+                0 => Point::new(last.x + 1, last.y),
+                1 => Point::new(last.x - 1, last.y),
+                2 => Point::new(last.x, last.y + 1),
+                _ => Point::new(last.x, last.y - 1),
+            });
+        }
+        let zigzag_norm = u2_norm(&path_to_turning_sequence(&zigzag));
+        assert!(zigzag_norm < 0.6, "zigzag scored {zigzag_norm}");
+        assert!(zigzag_norm < line_norm);
+    }
+
+    #[test]
+    fn an_empty_sequence_scores_zero() {
+        assert_eq!(u2_norm(&[]), 0.0);
+    }
+
+    #[test]
+    fn a_quadratic_phase_is_large_on_u3_but_small_on_u2() {
+        let n = 61;
+        let quadratic: Vec<Complex<f64>> = (0..n)
+            .map(|x| Complex::from_polar(1.0, std::f64::consts::TAU * (x * x) as f64 / n as f64))
+            .collect();
+        assert!((u3_norm(&quadratic) - 1.0).abs() < 1e-9);
+        // For a prime length every Fourier coefficient has magnitude √n, so U2 is n^(-1/4).
+        assert!((u2_norm(&quadratic) - (n as f64).powf(-0.25)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn u3_is_never_below_u2() {
+        let mut rng = ChaCha8Rng::seed_from_u64(5);
+        let sequence: Vec<Complex<f64>> = (0..40).map(|_| Complex::from_polar(1.0, rng.random_range(0.0..std::f64::consts::TAU))).collect();
+        assert!(u3_norm(&sequence) >= u2_norm(&sequence) - 1e-12);
+
+        let constant = vec![Complex::new(1.0, 0.0); 16];
+        assert!((u3_norm(&constant) - 1.0).abs() < 1e-12);
+        assert_eq!(u3_norm(&[]), 0.0);
+    }
+}
@@ -3,10 +3,43 @@
 // Provides the fundamental data structures for working with a 2D grid,
 // including `Point`, `Cell` state, and the `Grid` itself.
 
+use crate::automaton::{CellState, Moma2dAutomaton};
+use moma::core::OriginStrategy;
+use rand::Rng;
+use rand::seq::IndexedRandom;
+use std::fmt;
 use std::ops::{Index, IndexMut};
 
+/// Errors reported when parsing a grid from text.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GridError {
+    /// The text contains no rows.
+    Empty,
+    /// A row is a different length from the first row.
+    RaggedRow { row: usize, expected: usize, actual: usize },
+    /// A character does not stand for any cell.
+    UnknownCharacter { row: usize, column: usize, character: char },
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GridError::Empty => write!(f, "grid text has no rows"),
+            GridError::RaggedRow { row, expected, actual } => {
+                write!(f, "row {} has {} cells but the first row has {}", row, actual, expected)
+            }
+            GridError::UnknownCharacter { row, column, character } => {
+                write!(f, "unknown cell character {:?} at row {}, column {}", character, row, column)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GridError {}
+
 /// Represents a 2D coordinate on the grid.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point {
     pub x: usize,
     pub y: usize,
@@ -20,6 +53,7 @@ impl Point {
 
 /// Represents the state of a single cell within the grid.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Cell {
     /// An impassable wall.
     Blocked,
@@ -29,12 +63,47 @@ pub enum Cell {
     Path,
 }
 
+/// Controls how `Grid::neighbors_with_mode` treats neighbors that fall outside the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NeighborMode {
+    /// Out-of-bounds neighbors are dropped.
+    #[default]
+    Bounded,
+    /// Out-of-bounds neighbors are mirrored back across the edge, so the
+    /// neighbor at `x = -1` resolves to `x = 1` and the one at `x = width`
+    /// resolves to `x = width - 2`.
+    Reflecting,
+    /// Out-of-bounds neighbors wrap around to the opposite edge, matching the
+    /// toroidal topology of the MOMA automata.
+    Wrapping,
+}
+
+/// Controls which moves `Grid::neighbors_with_connectivity` allows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Connectivity {
+    /// Only the 4 orthogonal neighbors.
+    #[default]
+    Orthogonal,
+    /// The 4 orthogonal and 4 diagonal neighbors.
+    Diagonal,
+    /// Like `Diagonal`, but a diagonal move is forbidden when both orthogonal
+    /// cells it passes between are blocked, so paths cannot squeeze through
+    /// the corner of two walls.
+    DiagonalNoCornerCutting,
+}
+
+/// The cost of entering a cell whose terrain cost has not been set.
+pub const DEFAULT_TERRAIN_COST: u32 = 1;
+
 /// Represents a 2D grid of cells.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Grid {
     width: usize,
     height: usize,
     cells: Vec<Cell>,
+    /// The terrain cost of entering each cell, stored alongside `cells`.
+    costs: Vec<u32>,
 }
 
 impl Grid {
@@ -44,9 +113,86 @@ impl Grid {
             width,
             height,
             cells: vec![initial_cell; width * height],
+            costs: vec![DEFAULT_TERRAIN_COST; width * height],
         }
     }
 
+    /// Builds an obstacle grid the same size as a 2D automaton, marking a cell
+    /// `Blocked` when its state is at least `blocked_if_ge` and `Free`
+    /// otherwise.
+    pub fn from_automaton_threshold<S: OriginStrategy + Clone, C: CellState>(
+        automaton: &Moma2dAutomaton<S, C>,
+        blocked_if_ge: u64,
+    ) -> Self {
+        let cells: Vec<Cell> = automaton
+            .state
+            .iter()
+            .map(|&value| if value.to_residue() >= blocked_if_ge { Cell::Blocked } else { Cell::Free })
+            .collect();
+        let len = cells.len();
+
+        Self {
+            width: automaton.width,
+            height: automaton.height,
+            cells,
+            costs: vec![DEFAULT_TERRAIN_COST; len],
+        }
+    }
+
+    /// Parses a grid from text, one line per row: `#` is `Blocked`, `.` or a
+    /// space is `Free`, and `*` is `Path`. Every cell gets the default
+    /// terrain cost.
+    ///
+    /// # Errors
+    /// Returns a `GridError` if the text has no rows, the rows differ in
+    /// length, or a character is not one of the above.
+    pub fn from_ascii(s: &str) -> Result<Self, GridError> {
+        let rows: Vec<&str> = s.lines().collect();
+        let Some(first) = rows.first() else {
+            return Err(GridError::Empty);
+        };
+        let width = first.chars().count();
+
+        let mut cells = Vec::with_capacity(width * rows.len());
+        for (row, line) in rows.iter().enumerate() {
+            let actual = line.chars().count();
+            if actual != width {
+                return Err(GridError::RaggedRow { row, expected: width, actual });
+            }
+            for (column, character) in line.chars().enumerate() {
+                cells.push(match character {
+                    '#' => Cell::Blocked,
+                    '.' | ' ' => Cell::Free,
+                    '*' => Cell::Path,
+                    _ => return Err(GridError::UnknownCharacter { row, column, character }),
+                });
+            }
+        }
+
+        let height = rows.len();
+        Ok(Self {
+            width,
+            height,
+            cells,
+            costs: vec![DEFAULT_TERRAIN_COST; width * height],
+        })
+    }
+
+    /// Renders the grid as text in the format read by `from_ascii`, one line
+    /// per row, with `.` for free cells.
+    pub fn to_ascii(&self) -> String {
+        let mut output = String::with_capacity((self.width + 1) * self.height);
+        for row in self.cells.chunks(self.width.max(1)) {
+            output.extend(row.iter().map(|cell| match cell {
+                Cell::Blocked => '#',
+                Cell::Free => '.',
+                Cell::Path => '*',
+            }));
+            output.push('\n');
+        }
+        output
+    }
+
     pub fn width(&self) -> usize {
         self.width
     }
@@ -55,26 +201,276 @@ impl Grid {
         self.height
     }
 
+    /// Serializes the grid, including its terrain costs, to a JSON string.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Deserializes a grid previously saved with `to_json`.
+    ///
+    /// Fails if the JSON is malformed or if its cell or cost layers do not
+    /// match its dimensions.
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        use serde::de::Error;
+
+        let grid: Self = serde_json::from_str(json)?;
+        let expected = grid.width * grid.height;
+        if grid.cells.len() != expected || grid.costs.len() != expected {
+            return Err(serde_json::Error::custom(format!(
+                "grid layers do not match its {}x{} dimensions",
+                grid.width, grid.height
+            )));
+        }
+        Ok(grid)
+    }
+
+    /// Returns an iterator over every point of the grid in row-major order.
+    pub fn iter_points(&self) -> impl Iterator<Item = Point> + '_ {
+        (0..self.height).flat_map(move |y| (0..self.width).map(move |x| Point::new(x, y)))
+    }
+
+    /// Returns an iterator over every cell of the grid and its point, in
+    /// row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, Cell)> + '_ {
+        self.iter_points().zip(self.cells.iter().copied())
+    }
+
+    /// Returns an iterator over every cell of the grid and its point, in
+    /// row-major order, allowing the cells to be changed in place.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Point, &mut Cell)> + '_ {
+        let width = self.width;
+        self.cells
+            .iter_mut()
+            .enumerate()
+            .map(move |(i, cell)| (Point::new(i % width, i / width), cell))
+    }
+
+    /// Picks a passable (non-`Blocked`) cell uniformly at random, or returns
+    /// `None` if every cell is blocked.
+    pub fn random_free_cell(&self, rng: &mut impl Rng) -> Option<Point> {
+        self.passable_points().choose(rng).copied()
+    }
+
+    /// Picks up to `n` distinct passable cells uniformly at random. Fewer are
+    /// returned if the grid does not have `n` passable cells.
+    pub fn random_free_cells(&self, n: usize, rng: &mut impl Rng) -> Vec<Point> {
+        self.passable_points().choose_multiple(rng, n).copied().collect()
+    }
+
+    /// Resizes the grid in place, anchored at the top-left corner.
+    ///
+    /// Cells inside both the old and new bounds keep their state and terrain
+    /// cost; cells added by growing are set to `fill` with the default cost.
+    pub fn resize(&mut self, new_width: usize, new_height: usize, fill: Cell) {
+        let mut resized = Grid::new(new_width, new_height, fill);
+        for y in 0..self.height.min(new_height) {
+            for x in 0..self.width.min(new_width) {
+                let (from, to) = (y * self.width + x, y * new_width + x);
+                resized.cells[to] = self.cells[from];
+                resized.costs[to] = self.costs[from];
+            }
+        }
+        *self = resized;
+    }
+
+    /// Returns a copy of the `width` × `height` region whose top-left corner
+    /// is `top_left`, including its terrain costs.
+    ///
+    /// # Panics
+    /// Panics if the region does not lie entirely within the grid.
+    pub fn crop(&self, top_left: Point, width: usize, height: usize) -> Grid {
+        assert!(
+            top_left.x + width <= self.width && top_left.y + height <= self.height,
+            "crop region {}x{} at ({}, {}) exceeds the {}x{} grid",
+            width,
+            height,
+            top_left.x,
+            top_left.y,
+            self.width,
+            self.height
+        );
+
+        let mut cropped = Grid::new(width, height, Cell::Free);
+        for y in 0..height {
+            let from = (top_left.y + y) * self.width + top_left.x;
+            cropped.cells[y * width..(y + 1) * width].copy_from_slice(&self.cells[from..from + width]);
+            cropped.costs[y * width..(y + 1) * width].copy_from_slice(&self.costs[from..from + width]);
+        }
+        cropped
+    }
+
+    /// Returns a copy where every `Free` cell within `radius` (Chebyshev
+    /// distance) of a `Blocked` cell is also `Blocked`, so paths planned on
+    /// the copy keep that much clearance from walls. `Path` cells and terrain
+    /// costs are left unchanged.
+    pub fn inflate_obstacles(&self, radius: usize) -> Grid {
+        let mut inflated = self.clone();
+        for (point, cell) in self.iter() {
+            if cell != Cell::Blocked {
+                continue;
+            }
+            for y in point.y.saturating_sub(radius)..=(point.y + radius).min(self.height - 1) {
+                for x in point.x.saturating_sub(radius)..=(point.x + radius).min(self.width - 1) {
+                    let index = y * self.width + x;
+                    if inflated.cells[index] == Cell::Free {
+                        inflated.cells[index] = Cell::Blocked;
+                    }
+                }
+            }
+        }
+        inflated
+    }
+
+    /// Collects every point whose cell is not `Blocked`, in row-major order.
+    fn passable_points(&self) -> Vec<Point> {
+        self.iter().filter(|&(_, cell)| cell != Cell::Blocked).map(|(point, _)| point).collect()
+    }
+
+    /// Returns the cost of moving into `point`. Every cell starts at
+    /// `DEFAULT_TERRAIN_COST`.
+    pub fn cost_at(&self, point: Point) -> u32 {
+        self.costs[point.y * self.width + point.x]
+    }
+
+    /// Sets the cost of moving into `point`, e.g. a high cost for a swamp or a
+    /// low one for a road.
+    ///
+    /// A* uses the Manhattan distance as its heuristic, which assumes every
+    /// step costs at least 1; costs below that may lead to suboptimal paths.
+    pub fn set_cost(&mut self, point: Point, cost: u32) {
+        self.costs[point.y * self.width + point.x] = cost;
+    }
+
     /// Returns an iterator over the valid neighbors of a given point.
     /// A neighbor is valid if it is within the grid bounds and is not blocked.
     pub fn neighbors(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbors_with_mode(point, NeighborMode::Bounded)
+    }
+
+    /// Returns an iterator over the unblocked neighbors of a given point,
+    /// wrapping around the edges as on a torus, so the neighbors of a corner
+    /// cell include cells on the two opposite edges.
+    pub fn neighbors_wrapping(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbors_with_mode(point, NeighborMode::Wrapping)
+    }
+
+    /// Returns an iterator over the valid neighbors of a given point, including
+    /// the 4 diagonal cells. Diagonal moves may cut corners between blocked
+    /// cells; use `neighbors_with_connectivity` to forbid that.
+    pub fn neighbors_diagonal(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbors_with_connectivity(point, Connectivity::Diagonal)
+    }
+
+    /// Returns an iterator over the valid neighbors of a given point, reachable
+    /// with the moves allowed by `connectivity`.
+    ///
+    /// A neighbor is valid if it is within the grid bounds and is not blocked.
+    pub fn neighbors_with_connectivity(
+        &self,
+        point: Point,
+        connectivity: Connectivity,
+    ) -> impl Iterator<Item = Point> + '_ {
+        // Left, Right, Up, Down, then the four diagonals.
+        const OFFSETS: [(isize, isize); 8] = [(-1, 0), (1, 0), (0, -1), (0, 1), (-1, -1), (1, -1), (-1, 1), (1, 1)];
+        let offsets = match connectivity {
+            Connectivity::Orthogonal => &OFFSETS[..4],
+            Connectivity::Diagonal | Connectivity::DiagonalNoCornerCutting => &OFFSETS[..],
+        };
+
+        offsets.iter().filter_map(move |&(dx, dy)| {
+            let nx = point.x as isize + dx;
+            let ny = point.y as isize + dy;
+            if nx < 0 || nx >= self.width as isize || ny < 0 || ny >= self.height as isize {
+                return None;
+            }
+
+            let neighbor_point = Point::new(nx as usize, ny as usize);
+            if self[neighbor_point] == Cell::Blocked {
+                return None;
+            }
+
+            if connectivity == Connectivity::DiagonalNoCornerCutting && dx != 0 && dy != 0 {
+                // Both orthogonal cells are in bounds since the diagonal one is.
+                let horizontal = Point::new(nx as usize, point.y);
+                let vertical = Point::new(point.x, ny as usize);
+                if self[horizontal] == Cell::Blocked && self[vertical] == Cell::Blocked {
+                    return None;
+                }
+            }
+
+            Some(neighbor_point)
+        })
+    }
+
+    /// Returns an iterator over the neighbors of a given point, resolving
+    /// out-of-bounds neighbors according to `mode`.
+    ///
+    /// Blocked cells are always excluded. In `Reflecting` mode an edge cell can
+    /// yield the same mirrored neighbor more than once.
+    pub fn neighbors_with_mode(
+        &self,
+        point: Point,
+        mode: NeighborMode,
+    ) -> impl Iterator<Item = Point> + '_ {
         [(-1, 0), (1, 0), (0, -1), (0, 1)] // Left, Right, Up, Down
             .iter()
             .filter_map(move |&(dx, dy)| {
                 let nx = point.x as isize + dx;
                 let ny = point.y as isize + dy;
 
-                if nx >= 0 && nx < self.width as isize && ny >= 0 && ny < self.height as isize {
-                    let neighbor_point = Point::new(nx as usize, ny as usize);
-                    if self[neighbor_point] != Cell::Blocked {
-                        return Some(neighbor_point);
+                let neighbor_point = match mode {
+                    NeighborMode::Bounded => {
+                        if nx < 0 || nx >= self.width as isize || ny < 0 || ny >= self.height as isize {
+                            return None;
+                        }
+                        Point::new(nx as usize, ny as usize)
                     }
+                    NeighborMode::Reflecting => {
+                        Point::new(reflect(nx, self.width), reflect(ny, self.height))
+                    }
+                    NeighborMode::Wrapping => Point::new(
+                        nx.rem_euclid(self.width as isize) as usize,
+                        ny.rem_euclid(self.height as isize) as usize,
+                    ),
+                };
+
+                if self[neighbor_point] != Cell::Blocked {
+                    Some(neighbor_point)
+                } else {
+                    None
                 }
-                None
             })
     }
 }
 
+/// Maps a cell value to an opaque RGBA color on a smooth ramp from cool blue
+/// at 0 to warm red at `modulus - 1`, for visualizing automaton state or
+/// terrain with any modulus. Values past `modulus - 1` get the warm end.
+pub fn value_to_rgba(value: u64, modulus: u64) -> [u8; 4] {
+    let top = modulus.saturating_sub(1).max(1);
+    let ratio = value.min(top) as f64 / top as f64;
+    let r = (200.0 * ratio).round() as u8 + 55;
+    let g = 55;
+    let b = (200.0 * (1.0 - ratio)).round() as u8 + 55;
+    [r, g, b, 255]
+}
+
+/// Mirrors a coordinate that is at most one step outside `0..len` back inside it.
+pub(crate) fn reflect(coord: isize, len: usize) -> usize {
+    let last = len as isize - 1;
+    let mirrored = if coord < 0 {
+        -coord
+    } else if coord > last {
+        2 * last - coord
+    } else {
+        coord
+    };
+    // A grid one cell wide has nothing to mirror onto but itself.
+    mirrored.clamp(0, last) as usize
+}
+
 // Allow accessing grid cells using `grid[point]` syntax.
 impl Index<Point> for Grid {
     type Output = Cell;
@@ -89,3 +485,339 @@ impl IndexMut<Point> for Grid {
         &mut self.cells[point.y * self.width + point.x]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn reflecting_neighbors_of_a_corner_mirror_back_inside() {
+        let grid = Grid::new(3, 3, Cell::Free);
+        let neighbors: Vec<Point> = grid.neighbors_with_mode(Point::new(0, 0), NeighborMode::Reflecting).collect();
+        // Left mirrors onto the right neighbor, and up onto the one below.
+        assert_eq!(neighbors, vec![Point::new(1, 0), Point::new(1, 0), Point::new(0, 1), Point::new(0, 1)]);
+
+        let neighbors: Vec<Point> = grid.neighbors_with_mode(Point::new(2, 2), NeighborMode::Reflecting).collect();
+        assert_eq!(neighbors, vec![Point::new(1, 2), Point::new(1, 2), Point::new(2, 1), Point::new(2, 1)]);
+    }
+
+    #[test]
+    fn reflecting_neighbors_exclude_blocked_mirrored_cells() {
+        let mut grid = Grid::new(3, 3, Cell::Free);
+        grid[Point::new(1, 0)] = Cell::Blocked;
+
+        let neighbors: Vec<Point> = grid.neighbors_with_mode(Point::new(0, 0), NeighborMode::Reflecting).collect();
+        assert_eq!(neighbors, vec![Point::new(0, 1), Point::new(0, 1)]);
+        assert_eq!(grid.neighbors(Point::new(0, 0)).collect::<Vec<_>>(), vec![Point::new(0, 1)]);
+    }
+
+    #[test]
+    fn reflecting_on_a_single_cell_grid_stays_put() {
+        let grid = Grid::new(1, 1, Cell::Free);
+        assert!(grid.neighbors_with_mode(Point::new(0, 0), NeighborMode::Reflecting).all(|p| p == Point::new(0, 0)));
+    }
+
+    #[test]
+    fn terrain_costs_default_to_one_and_can_be_set_per_cell() {
+        let mut grid = Grid::new(3, 2, Cell::Free);
+        assert!(grid.iter_points().all(|p| grid.cost_at(p) == DEFAULT_TERRAIN_COST));
+        grid.set_cost(Point::new(2, 1), 7);
+        assert_eq!(grid.cost_at(Point::new(2, 1)), 7);
+        assert_eq!(grid.cost_at(Point::new(1, 1)), DEFAULT_TERRAIN_COST);
+        // Costly cells are still neighbors; only blocked ones are skipped.
+        assert!(grid.neighbors(Point::new(1, 1)).any(|p| p == Point::new(2, 1)));
+    }
+
+    #[test]
+    fn diagonal_moves_cannot_cut_between_two_blocked_cells() {
+        let mut grid = Grid::new(3, 3, Cell::Free);
+        grid[Point::new(1, 0)] = Cell::Blocked;
+        grid[Point::new(0, 1)] = Cell::Blocked;
+
+        let corner = Point::new(0, 0);
+        assert_eq!(grid.neighbors_with_connectivity(corner, Connectivity::DiagonalNoCornerCutting).count(), 0);
+        assert_eq!(grid.neighbors_diagonal(corner).collect::<Vec<_>>(), vec![Point::new(1, 1)]);
+
+        // With only one side blocked the diagonal stays open.
+        grid[Point::new(0, 1)] = Cell::Free;
+        let neighbors: Vec<Point> = grid.neighbors_with_connectivity(corner, Connectivity::DiagonalNoCornerCutting).collect();
+        assert_eq!(neighbors, vec![Point::new(0, 1), Point::new(1, 1)]);
+    }
+
+    #[test]
+    fn an_interior_cell_has_eight_diagonal_neighbors() {
+        let grid = Grid::new(3, 3, Cell::Free);
+        assert_eq!(grid.neighbors_diagonal(Point::new(1, 1)).count(), 8);
+        assert_eq!(grid.neighbors_with_connectivity(Point::new(1, 1), Connectivity::Orthogonal).count(), 4);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn a_maze_survives_a_json_round_trip_cell_for_cell() {
+        let mut maze = crate::maze::generate_maze_seeded(15, 11, 4);
+        maze.set_cost(Point::new(1, 1), 7);
+        let decoded = Grid::from_json(&maze.to_json().unwrap()).unwrap();
+        assert!(maze.iter().zip(decoded.iter()).all(|(a, b)| a == b));
+        assert_eq!(decoded.cost_at(Point::new(1, 1)), 7);
+        assert_eq!(decoded, maze);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_whose_layers_do_not_match_its_dimensions_is_rejected() {
+        let json = Grid::new(2, 2, Cell::Free).to_json().unwrap().replacen("\"width\":2", "\"width\":3", 1);
+        assert!(Grid::from_json(&json).is_err());
+        assert!(Grid::from_json("not json").is_err());
+    }
+
+    #[test]
+    fn ascii_round_trips_a_small_grid() {
+        let text = "#.#*\n..*.\n####\n";
+        let grid = Grid::from_ascii(text).unwrap();
+        assert_eq!((grid.width(), grid.height()), (4, 3));
+        assert_eq!(grid[Point::new(0, 0)], Cell::Blocked);
+        assert_eq!(grid[Point::new(3, 0)], Cell::Path);
+        assert_eq!(grid[Point::new(1, 1)], Cell::Free);
+        assert_eq!(grid.to_ascii(), text);
+    }
+
+    #[test]
+    fn from_ascii_reads_spaces_as_free_cells() {
+        let grid = Grid::from_ascii("# #\n   ").unwrap();
+        assert_eq!(grid.to_ascii(), "#.#\n...\n");
+    }
+
+    #[test]
+    fn from_ascii_rejects_empty_ragged_and_unknown_input() {
+        assert_eq!(Grid::from_ascii(""), Err(GridError::Empty));
+        assert_eq!(
+            Grid::from_ascii("###\n#.\n"),
+            Err(GridError::RaggedRow { row: 1, expected: 3, actual: 2 })
+        );
+        assert_eq!(
+            Grid::from_ascii("#.\n.x"),
+            Err(GridError::UnknownCharacter { row: 1, column: 1, character: 'x' })
+        );
+    }
+
+    #[test]
+    fn iter_counts_the_same_free_cells_as_a_manual_scan() {
+        let grid = crate::maze::generate_maze_seeded(21, 15, 4);
+        let from_iter = grid.iter().filter(|&(_, cell)| cell == Cell::Free).count();
+
+        let mut manual = 0;
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                if grid[Point::new(x, y)] == Cell::Free {
+                    manual += 1;
+                }
+            }
+        }
+        assert!(manual > 0);
+        assert_eq!(from_iter, manual);
+    }
+
+    #[test]
+    fn iterators_walk_the_grid_in_row_major_order() {
+        let grid = Grid::from_ascii("#.\n.*\n.#").unwrap();
+        let points: Vec<Point> = grid.iter_points().collect();
+        assert_eq!(points.len(), 6);
+        assert_eq!(points[..3], [Point::new(0, 0), Point::new(1, 0), Point::new(0, 1)]);
+        for (point, cell) in grid.iter() {
+            assert_eq!(cell, grid[point]);
+        }
+    }
+
+    #[test]
+    fn iter_mut_changes_cells_in_place() {
+        let mut grid = Grid::from_ascii("#..\n.#.").unwrap();
+        for (point, cell) in grid.iter_mut() {
+            if point.y == 1 && *cell == Cell::Free {
+                *cell = Cell::Path;
+            }
+        }
+        assert_eq!(grid.to_ascii(), "#..\n*#*\n");
+    }
+
+    #[test]
+    fn random_free_cells_are_passable_and_distinct() {
+        let mut rng = ChaCha8Rng::seed_from_u64(13);
+        let grid = crate::maze::generate_maze_seeded(15, 11, 2);
+
+        for _ in 0..50 {
+            let point = grid.random_free_cell(&mut rng).unwrap();
+            assert_ne!(grid[point], Cell::Blocked);
+        }
+
+        let points = grid.random_free_cells(20, &mut rng);
+        assert_eq!(points.len(), 20);
+        assert!(points.iter().all(|&p| grid[p] != Cell::Blocked));
+        let distinct: std::collections::HashSet<Point> = points.iter().copied().collect();
+        assert_eq!(distinct.len(), points.len());
+    }
+
+    #[test]
+    fn random_free_cells_stop_at_the_passable_cells_available() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let grid = Grid::from_ascii("#.#\n*##").unwrap();
+        let mut points = grid.random_free_cells(10, &mut rng);
+        points.sort_by_key(|p| (p.y, p.x));
+        assert_eq!(points, [Point::new(1, 0), Point::new(0, 1)]);
+    }
+
+    #[test]
+    fn a_fully_blocked_grid_has_no_free_cell() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let grid = Grid::new(4, 4, Cell::Blocked);
+        assert_eq!(grid.random_free_cell(&mut rng), None);
+        assert!(grid.random_free_cells(3, &mut rng).is_empty());
+    }
+
+    #[test]
+    fn automaton_threshold_blocks_states_at_or_above_it() {
+        let state: Vec<u64> = vec![0, 3, 5, 2, 4, 1];
+        let automaton = Moma2dAutomaton::from_state(3, 2, 6, moma::strategy::PrimeGap, state.clone()).unwrap();
+        let grid = Grid::from_automaton_threshold(&automaton, 3);
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.to_ascii(), ".##\n.#.\n");
+        let blocked = grid.iter().filter(|&(_, cell)| cell == Cell::Blocked).count();
+        assert_eq!(blocked, state.iter().filter(|&&v| v >= 3).count());
+        assert!(grid.iter_points().all(|p| grid.cost_at(p) == DEFAULT_TERRAIN_COST));
+    }
+
+    #[test]
+    fn automaton_threshold_extremes_block_everything_or_nothing() {
+        let automaton = Moma2dAutomaton::new_seeded(5, 4, 7, moma::strategy::CompositeMass, 2);
+        let all_blocked = Grid::from_automaton_threshold(&automaton, 0);
+        assert!(all_blocked.iter().all(|(_, cell)| cell == Cell::Blocked));
+        let all_free = Grid::from_automaton_threshold(&automaton, 7);
+        assert!(all_free.iter().all(|(_, cell)| cell == Cell::Free));
+    }
+
+    #[test]
+    fn value_to_rgba_spans_the_ramp_for_any_modulus() {
+        for modulus in [2, 7, 16, 1000] {
+            assert_eq!(value_to_rgba(0, modulus), [55, 55, 255, 255]);
+            assert_eq!(value_to_rgba(modulus - 1, modulus), [255, 55, 55, 255]);
+
+            let colors: Vec<[u8; 4]> = (0..modulus).map(|v| value_to_rgba(v, modulus)).collect();
+            for pair in colors.windows(2) {
+                assert!(pair[1][0] >= pair[0][0], "red falls for modulus {}", modulus);
+                assert!(pair[1][2] <= pair[0][2], "blue rises for modulus {}", modulus);
+            }
+        }
+    }
+
+    #[test]
+    fn value_to_rgba_clamps_out_of_range_values_and_tolerates_a_single_state() {
+        assert_eq!(value_to_rgba(40, 16), value_to_rgba(15, 16));
+        assert_eq!(value_to_rgba(0, 1), [55, 55, 255, 255]);
+    }
+
+    #[test]
+    fn crop_copies_the_cells_and_costs_of_the_region() {
+        let mut grid = crate::maze::generate_maze_seeded(15, 11, 6);
+        grid.set_cost(Point::new(5, 4), 9);
+        let (top_left, width, height) = (Point::new(3, 2), 7, 5);
+        let cropped = grid.crop(top_left, width, height);
+
+        assert_eq!((cropped.width(), cropped.height()), (width, height));
+        for (point, cell) in cropped.iter() {
+            let original = Point::new(top_left.x + point.x, top_left.y + point.y);
+            assert_eq!(cell, grid[original]);
+            assert_eq!(cropped.cost_at(point), grid.cost_at(original));
+        }
+        assert_eq!(cropped.cost_at(Point::new(2, 2)), 9);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the 5x5 grid")]
+    fn crop_rejects_a_region_past_the_edge() {
+        Grid::new(5, 5, Cell::Free).crop(Point::new(3, 0), 3, 2);
+    }
+
+    #[test]
+    fn growing_keeps_the_old_cells_and_fills_the_new_area() {
+        let mut grid = Grid::from_ascii("#.\n*#").unwrap();
+        grid.set_cost(Point::new(1, 0), 4);
+        grid.resize(4, 3, Cell::Blocked);
+
+        assert_eq!(grid.to_ascii(), "#.##\n*###\n####\n");
+        assert_eq!(grid.cost_at(Point::new(1, 0)), 4);
+        assert_eq!(grid.cost_at(Point::new(3, 2)), DEFAULT_TERRAIN_COST);
+    }
+
+    #[test]
+    fn shrinking_keeps_the_top_left_corner() {
+        let mut grid = Grid::from_ascii("#.*\n..#\n#*.").unwrap();
+        let corner = grid.crop(Point::new(0, 0), 2, 2);
+        grid.resize(2, 2, Cell::Free);
+        assert_eq!(grid, corner);
+    }
+
+    #[test]
+    fn wrapping_neighbors_of_a_corner_reach_the_opposite_edges() {
+        let grid = Grid::new(5, 4, Cell::Free);
+        let neighbors: Vec<Point> = grid.neighbors_wrapping(Point::new(0, 0)).collect();
+        assert_eq!(neighbors, vec![Point::new(4, 0), Point::new(1, 0), Point::new(0, 3), Point::new(0, 1)]);
+
+        let neighbors: Vec<Point> = grid.neighbors_wrapping(Point::new(4, 3)).collect();
+        assert_eq!(neighbors, vec![Point::new(3, 3), Point::new(0, 3), Point::new(4, 2), Point::new(4, 0)]);
+    }
+
+    #[test]
+    fn wrapping_neighbors_skip_blocked_cells_across_the_edge() {
+        let mut grid = Grid::new(4, 4, Cell::Free);
+        grid[Point::new(3, 1)] = Cell::Blocked;
+        grid[Point::new(0, 0)] = Cell::Blocked;
+        let neighbors: Vec<Point> = grid.neighbors_wrapping(Point::new(0, 1)).collect();
+        assert_eq!(neighbors, vec![Point::new(1, 1), Point::new(0, 2)]);
+    }
+
+    #[test]
+    fn wrapping_matches_bounded_neighbors_away_from_the_edges() {
+        let grid = crate::maze::generate_maze_seeded(11, 9, 3);
+        for point in grid.iter_points().filter(|p| (1..10).contains(&p.x) && (1..8).contains(&p.y)) {
+            let bounded: Vec<Point> = grid.neighbors(point).collect();
+            let wrapping: Vec<Point> = grid.neighbors_wrapping(point).collect();
+            assert_eq!(bounded, wrapping);
+        }
+    }
+
+    #[test]
+    fn inflate_obstacles_blocks_the_cells_around_a_wall() {
+        let grid = Grid::from_ascii(".....\n.....\n..#..\n.....\n.....").unwrap();
+        let inflated = grid.inflate_obstacles(1);
+        assert_eq!(inflated.to_ascii(), ".....\n.###.\n.###.\n.###.\n.....\n");
+        // The original is left untouched.
+        assert_eq!(grid[Point::new(1, 1)], Cell::Free);
+    }
+
+    #[test]
+    fn inflate_obstacles_grows_by_the_chebyshev_radius_and_clips_at_the_edges() {
+        let grid = Grid::from_ascii("#....\n.....\n.....\n.....").unwrap();
+        assert_eq!(grid.inflate_obstacles(2).to_ascii(), "###..\n###..\n###..\n.....\n");
+        assert_eq!(grid.inflate_obstacles(0).to_ascii(), grid.to_ascii());
+    }
+
+    #[test]
+    fn inflate_obstacles_keeps_path_cells_and_terrain_costs() {
+        let mut grid = Grid::from_ascii("*#.\n...").unwrap();
+        grid.set_cost(Point::new(2, 1), 5);
+        let inflated = grid.inflate_obstacles(1);
+        assert_eq!(inflated.to_ascii(), "*##\n###\n");
+        assert_eq!(inflated.cost_at(Point::new(2, 1)), 5);
+    }
+
+    #[test]
+    fn paths_on_an_inflated_grid_keep_clear_of_walls() {
+        let grid = Grid::from_ascii(".......\n.......\n...#...\n.......\n.......").unwrap();
+        let inflated = grid.inflate_obstacles(1);
+        let path = crate::pathfinding::a_star(&inflated, Point::new(0, 2), Point::new(6, 2)).unwrap();
+        let wall = Point::new(3, 2);
+        assert!(path.iter().all(|p| p.x.abs_diff(wall.x).max(p.y.abs_diff(wall.y)) > 1));
+    }
+}
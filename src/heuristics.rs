@@ -0,0 +1,84 @@
+//! # Heuristics Module
+//
+// Provides float-valued distance heuristics for searches whose costs are not
+// whole numbers, such as `pathfinding::a_star_float`.
+
+use crate::grid::Point;
+
+/// The straight-line distance between two points.
+///
+/// Admissible whenever every step costs at least its geometric length (1 for
+/// an orthogonal step, √2 for a diagonal one), whichever moves are allowed.
+pub fn euclidean(a: Point, b: Point) -> f64 {
+    let dx = a.x as f64 - b.x as f64;
+    let dy = a.y as f64 - b.y as f64;
+    dx.hypot(dy)
+}
+
+/// The squared straight-line distance, which is cheaper to compute and
+/// orders points the same way as `euclidean`.
+///
+/// It is not admissible as an A* heuristic, since it grows faster than any
+/// path length; use it to compare or rank distances.
+pub fn squared_euclidean(a: Point, b: Point) -> f64 {
+    let dx = a.x as f64 - b.x as f64;
+    let dy = a.y as f64 - b.y as f64;
+    dx * dx + dy * dy
+}
+
+/// The octile distance: the exact length of the shortest unobstructed path
+/// when orthogonal steps cost 1 and diagonal steps cost √2.
+///
+/// Admissible for 8-connected movement with those costs, and for 4-connected
+/// movement. It overestimates if diagonal steps cost less than √2, e.g. 1,
+/// where `pathfinding::chebyshev_distance` is the exact heuristic.
+pub fn octile(a: Point, b: Point) -> f64 {
+    let dx = a.x.abs_diff(b.x) as f64;
+    let dy = a.y.abs_diff(b.y) as f64;
+    dx.max(dy) + (std::f64::consts::SQRT_2 - 1.0) * dx.min(dy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{Cell, Connectivity, Grid};
+    use crate::pathfinding::{a_star_float, manhattan_distance};
+    use std::f64::consts::SQRT_2;
+
+    #[test]
+    fn octile_distance_of_a_pure_diagonal_is_its_length() {
+        assert!((octile(Point::new(0, 0), Point::new(3, 3)) - 3.0 * SQRT_2).abs() < 1e-12);
+        assert!((octile(Point::new(5, 1), Point::new(0, 3)) - (3.0 + 2.0 * SQRT_2)).abs() < 1e-12);
+        assert_eq!(octile(Point::new(2, 7), Point::new(2, 7)), 0.0);
+    }
+
+    #[test]
+    fn euclidean_distances_match_a_right_triangle() {
+        let (a, b) = (Point::new(1, 2), Point::new(4, 6));
+        assert!((euclidean(a, b) - 5.0).abs() < 1e-12);
+        assert_eq!(squared_euclidean(a, b), 25.0);
+        assert_eq!(euclidean(a, b), euclidean(b, a));
+    }
+
+    #[test]
+    fn octile_lies_between_the_euclidean_and_manhattan_distances() {
+        for (x, y) in [(0, 0), (7, 0), (3, 9), (12, 5), (6, 6)] {
+            let (a, b) = (Point::new(2, 3), Point::new(x, y));
+            let d = octile(a, b);
+            assert!(euclidean(a, b) <= d + 1e-12);
+            assert!(d <= manhattan_distance(a, b) as f64 + 1e-12);
+        }
+    }
+
+    #[test]
+    fn float_a_star_with_euclidean_and_octile_finds_the_same_cost() {
+        let mut grid = Grid::new(9, 9, Cell::Free);
+        for y in 1..8 {
+            grid[Point::new(4, y)] = Cell::Blocked;
+        }
+        let (start, goal) = (Point::new(1, 4), Point::new(7, 4));
+        let (_, by_euclidean) = a_star_float(&grid, start, goal, Connectivity::Diagonal, euclidean).unwrap();
+        let (_, by_octile) = a_star_float(&grid, start, goal, Connectivity::Diagonal, octile).unwrap();
+        assert!((by_euclidean - by_octile).abs() < 1e-12);
+    }
+}
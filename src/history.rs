@@ -0,0 +1,195 @@
+//! # History Module
+//!
+//! Provides storage for recording the evolution of an automaton over time.
+
+use crate::grid::value_to_rgba;
+
+/// A fixed-capacity record of automaton generations for long runs.
+///
+/// The buffer starts by keeping every generation. Whenever it fills up, it
+/// doubles its sampling stride and discards the generations that no longer
+/// fall on it. Rather than forgetting the start of the run, the buffer always
+/// holds an evenly spaced overview of everything pushed so far.
+#[derive(Debug, Clone)]
+pub struct HistoryBuffer {
+    capacity: usize,
+    /// Only generations whose index is a multiple of the stride are kept.
+    stride: usize,
+    /// The index that the next pushed generation will receive.
+    next_generation: usize,
+    entries: Vec<(usize, Vec<u64>)>,
+}
+
+impl HistoryBuffer {
+    /// Creates an empty buffer that holds at most `capacity` generations.
+    ///
+    /// # Panics
+    /// Panics if `capacity` is less than 2.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity >= 2, "History capacity must be at least 2.");
+
+        Self {
+            capacity,
+            stride: 1,
+            next_generation: 0,
+            entries: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Records the next generation, downsampling the stored history if the
+    /// buffer is full.
+    pub fn push(&mut self, state: &[u64]) {
+        let generation = self.next_generation;
+        self.next_generation += 1;
+
+        if !generation.is_multiple_of(self.stride) {
+            return;
+        }
+        self.entries.push((generation, state.to_vec()));
+
+        if self.entries.len() > self.capacity {
+            self.stride *= 2;
+            let stride = self.stride;
+            self.entries.retain(|(g, _)| g.is_multiple_of(stride));
+        }
+    }
+
+    /// The number of generations currently stored.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no generations have been stored.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The maximum number of generations the buffer will hold.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The current spacing, in generations, between stored entries.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// The total number of generations pushed, including discarded ones.
+    pub fn total_generations(&self) -> usize {
+        self.next_generation
+    }
+
+    /// Returns an iterator over the stored `(generation index, state)` pairs,
+    /// oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &[u64])> + '_ {
+        self.entries.iter().map(|(g, state)| (*g, state.as_slice()))
+    }
+}
+
+/// A complete, generation-by-generation record of an automaton run.
+///
+/// Unlike `HistoryBuffer`, every generation is kept, which makes it suited to
+/// spacetime diagrams of the 1D automaton and frame-by-frame animations of the
+/// 2D one.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct History {
+    generations: Vec<Vec<u64>>,
+}
+
+impl History {
+    /// Starts a history whose first generation is `initial`.
+    pub fn new(initial: &[u64]) -> Self {
+        Self { generations: vec![initial.to_vec()] }
+    }
+
+    /// Appends the next generation.
+    pub fn record(&mut self, state: &[u64]) {
+        self.generations.push(state.to_vec());
+    }
+
+    /// The number of generations recorded, including the initial state.
+    pub fn len(&self) -> usize {
+        self.generations.len()
+    }
+
+    /// Returns `true` if no generations have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.generations.is_empty()
+    }
+
+    /// Returns the recorded generations, oldest first.
+    pub fn generations(&self) -> &[Vec<u64>] {
+        &self.generations
+    }
+
+    /// Maps every generation to a row of RGBA pixels, oldest first, shading
+    /// each cell from cool blue (0) to warm red (`modulus - 1`).
+    ///
+    /// For the 1D automaton the rows form a spacetime diagram; for the 2D
+    /// automaton each row is one flattened frame.
+    pub fn to_image_rows(&self, modulus: u64) -> Vec<Vec<[u8; 4]>> {
+        self.generations
+            .iter()
+            .map(|generation| generation.iter().map(|&state| to_color_ramp(state, modulus)).collect())
+            .collect()
+    }
+}
+
+/// Maps a cell state to an opaque RGBA color for visualization, from cool
+/// blue at 0 to warm red at `modulus - 1`. This is `grid::value_to_rgba`
+/// under the name the history API uses.
+pub fn to_color_ramp(state: u64, modulus: u64) -> [u8; 4] {
+    value_to_rgba(state, modulus)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_buffer_keeps_an_evenly_spaced_overview_within_capacity() {
+        let mut buffer = HistoryBuffer::new(8);
+        for generation in 0..1000u64 {
+            buffer.push(&[generation]);
+            assert!(buffer.len() <= buffer.capacity());
+        }
+        assert_eq!(buffer.total_generations(), 1000);
+
+        let indices: Vec<usize> = buffer.iter().map(|(g, _)| g).collect();
+        assert_eq!(indices[0], 0, "the start of the run is never forgotten");
+        assert!(indices.windows(2).all(|pair| pair[1] - pair[0] == buffer.stride()));
+        assert!(buffer.iter().all(|(g, state)| state == [g as u64]));
+    }
+
+    #[test]
+    fn history_buffer_is_full_after_a_power_of_two_multiple_of_capacity() {
+        let mut buffer = HistoryBuffer::new(8);
+        for generation in 0..64u64 {
+            buffer.push(&[generation]);
+        }
+        assert_eq!(buffer.len(), buffer.capacity());
+        assert_eq!(buffer.iter().map(|(g, _)| g).collect::<Vec<_>>(), (0..64).step_by(8).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn history_maps_each_generation_to_a_row_of_pixels() {
+        let mut history = History::new(&[0, 1, 2]);
+        history.record(&[2, 1, 0]);
+        let rows = history.to_image_rows(3);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0], [to_color_ramp(0, 3), to_color_ramp(1, 3), to_color_ramp(2, 3)]);
+        assert_eq!(rows[1][0], rows[0][2]);
+        assert_ne!(rows[0][0], rows[0][2]);
+    }
+
+    #[test]
+    fn color_ramp_runs_from_blue_to_red_across_the_modulus() {
+        assert_eq!(to_color_ramp(0, 4), [55, 55, 255, 255]);
+        assert_eq!(to_color_ramp(3, 4), [255, 55, 55, 255]);
+        let middle = to_color_ramp(1, 4);
+        assert!(middle[0] > 55 && middle[2] < 255);
+        // Out-of-range states are clamped to the warm end.
+        assert_eq!(to_color_ramp(9, 4), to_color_ramp(3, 4));
+    }
+}
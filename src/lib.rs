@@ -2,22 +2,37 @@
 //!
 //! A library for creating and running dynamic systems, such as cellular automata,
 //! using the MOMA framework as the core update rule.
+pub mod analysis;
 pub mod automaton;
 pub mod circuit;
+pub mod control;
+pub mod density;
 pub mod gates;
+pub mod gowers;
 pub mod qubit;
 pub mod grid;
+pub mod heuristics;
+pub mod history;
+pub mod life;
 pub mod maze;
 pub mod pathfinding;
+pub mod patterns;
+pub mod qasm;
 pub mod network_graph;
 
 // Re-export the most important structs for easy access by users of the crate.
 
-pub use circuit::QuantumCircuit;
-pub use gates::{HADAMARD, PAULI_X, PAULI_Y, PAULI_Z};
+pub use circuit::{CircuitError, Operation, QuantumCircuit};
+pub use control::PidController;
+pub use density::DensityMatrixCircuit;
+pub use gates::{HADAMARD, PAULI_X, PAULI_Y, PAULI_Z, CNOT, CZ, SWAP, ISWAP, SQRT_ISWAP};
 pub use qubit::Qubit;
-pub use grid::{Cell, Grid, Point};
-pub use pathfinding::{Node, manhattan_distance, a_star};
-pub use automaton::{Moma2dAutomaton, CellularAutomaton};
-pub use network_graph::{Graph, Edge};
-pub use maze::generate_maze;
+pub use gowers::{path_to_turning_sequence, u2_norm, u3_norm};
+pub use grid::{Cell, Connectivity, Grid, GridError, NeighborMode, Point, DEFAULT_TERRAIN_COST, value_to_rgba};
+pub use pathfinding::{Node, manhattan_distance, euclidean_distance, chebyshev_distance, toroidal_manhattan, a_star, a_star_with, a_star_connected, a_star_float, a_star_move_costs, MoveCosts, a_star_cost, a_star_stats, SearchStats, a_star_generic, a_star_bidirectional, a_star_deadline, dijkstra, dijkstra_path, flow_field, follow_flow_field, line_of_sight, smooth_path, turn_count, total_curvature, theta_star, a_star_moma, a_star_moma_toroidal};
+pub use automaton::{AutomatonError, BoundaryCondition, CellState, Moma2dAutomaton, CellularAutomaton, SimulationSnapshot, DEFAULT_RAMP};
+pub use network_graph::{Graph, GraphError, Edge, RouteResult};
+pub use maze::{MazeAlgorithm, braid_maze, generate_maze, generate_maze_seeded, generate_maze_between, generate_maze_between_seeded, generate_maze_kruskal, generate_maze_prim, generate_maze_recursive_division, generate_maze_with};
+pub use life::LifeAutomaton;
+pub use patterns::{Pattern, stamp, stamp_pattern};
+pub use history::{History, HistoryBuffer, to_color_ramp};
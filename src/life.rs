@@ -0,0 +1,242 @@
+//! # Life Module
+//!
+//! Provides a Conway-style "Game of Life" automaton with configurable birth
+//! and survival rules. Initial patterns come from the `patterns` module.
+
+// The pattern library lives in `patterns`; it is re-exported here so
+// `life::Pattern` and friends keep working.
+pub use crate::patterns::{Pattern, stamp, stamp_pattern};
+
+/// A two-state, Conway-style Life automaton on a toroidal grid.
+///
+/// Each generation, a dead cell comes alive if its number of live neighbors
+/// (of the 8 around it) is in the birth set, and a live cell survives if its
+/// count is in the survival set. The default rule is Conway's B3/S23.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LifeAutomaton {
+    width: usize,
+    height: usize,
+    /// The cells, stored row by row (`y * width + x`); 1 is alive, 0 is dead.
+    cells: Vec<u8>,
+    /// `born[n]` is `true` if a dead cell with `n` live neighbors comes alive.
+    born: [bool; 9],
+    /// `survive[n]` is `true` if a live cell with `n` live neighbors stays alive.
+    survive: [bool; 9],
+}
+
+impl LifeAutomaton {
+    /// Creates an all-dead automaton that follows Conway's rules (B3/S23).
+    pub fn new(width: usize, height: usize) -> Self {
+        Self::with_rule(width, height, &[3], &[2, 3])
+    }
+
+    /// Creates an all-dead automaton with custom birth and survival counts,
+    /// e.g. `&[3, 6]` and `&[2, 3]` for HighLife (B36/S23).
+    ///
+    /// # Panics
+    /// Panics if a count is greater than 8.
+    pub fn with_rule(width: usize, height: usize, born: &[u8], survive: &[u8]) -> Self {
+        let to_table = |counts: &[u8]| {
+            let mut table = [false; 9];
+            for &n in counts {
+                assert!(n <= 8, "A cell has at most 8 neighbors, got a rule count of {}.", n);
+                table[n as usize] = true;
+            }
+            table
+        };
+
+        Self {
+            width,
+            height,
+            cells: vec![0; width * height],
+            born: to_table(born),
+            survive: to_table(survive),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the cells row by row, for use with `stamp_pattern` and `stamp`
+    /// through `cells_mut`.
+    pub fn cells(&self) -> &[u8] {
+        &self.cells
+    }
+
+    /// Returns the cells row by row for in-place editing.
+    pub fn cells_mut(&mut self) -> &mut [u8] {
+        &mut self.cells
+    }
+
+    /// Returns `true` if the cell at `(x, y)` is alive.
+    ///
+    /// # Panics
+    /// Panics if `(x, y)` is out of bounds.
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        assert!(x < self.width && y < self.height, "Cell ({}, {}) is outside a {}x{} automaton.", x, y, self.width, self.height);
+        self.cells[y * self.width + x] != 0
+    }
+
+    /// Sets whether the cell at `(x, y)` is alive.
+    ///
+    /// # Panics
+    /// Panics if `(x, y)` is out of bounds.
+    pub fn set(&mut self, x: usize, y: usize, alive: bool) {
+        assert!(x < self.width && y < self.height, "Cell ({}, {}) is outside a {}x{} automaton.", x, y, self.width, self.height);
+        self.cells[y * self.width + x] = alive as u8;
+    }
+
+    /// Returns the number of live cells.
+    pub fn population(&self) -> usize {
+        self.cells.iter().filter(|&&cell| cell != 0).count()
+    }
+
+    /// Advances the automaton by one generation.
+    pub fn step(&mut self) {
+        let (width, height) = (self.width as isize, self.height as isize);
+        let mut next = vec![0; self.cells.len()];
+
+        for y in 0..height {
+            for x in 0..width {
+                let mut live_neighbors = 0;
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        if dx == 0 && dy == 0 {
+                            continue;
+                        }
+                        let nx = (x + dx).rem_euclid(width);
+                        let ny = (y + dy).rem_euclid(height);
+                        live_neighbors += (self.cells[(ny * width + nx) as usize] != 0) as usize;
+                    }
+                }
+
+                let index = (y * width + x) as usize;
+                let rule = if self.cells[index] != 0 { &self.survive } else { &self.born };
+                next[index] = rule[live_neighbors] as u8;
+            }
+        }
+
+        self.cells = next;
+    }
+
+    /// Renders the automaton as a string, one line per row, with `#` for live
+    /// cells and `.` for dead ones.
+    pub fn render(&self) -> String {
+        let mut output = String::with_capacity((self.width + 1) * self.height);
+        for row in self.cells.chunks(self.width.max(1)) {
+            output.extend(row.iter().map(|&cell| if cell != 0 { '#' } else { '.' }));
+            output.push('\n');
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A `width` x `height` Conway automaton with `pattern` stamped at `origin`.
+    fn life_with(width: usize, height: usize, pattern: &Pattern, origin: (isize, isize)) -> LifeAutomaton {
+        let mut life = LifeAutomaton::new(width, height);
+        stamp_pattern(life.cells_mut(), width, height, pattern, origin);
+        life
+    }
+
+    #[test]
+    fn stamped_blinker_oscillates_with_period_two() {
+        let mut life = life_with(5, 5, &Pattern::blinker(), (1, 2));
+        let initial = life.clone();
+
+        life.step();
+        assert_ne!(life, initial);
+        assert!(life.get(2, 1) && life.get(2, 2) && life.get(2, 3));
+        assert_eq!(life.population(), 3);
+
+        life.step();
+        assert_eq!(life, initial);
+    }
+
+    #[test]
+    fn glider_translates_by_one_cell_diagonally_every_four_generations() {
+        let mut life = life_with(8, 8, &Pattern::glider(), (1, 1));
+        for generation in 1..=3 {
+            life.step();
+            assert_eq!(life.population(), 5, "generation {}", generation);
+        }
+        life.step();
+        assert_eq!(life, life_with(8, 8, &Pattern::glider(), (2, 2)));
+    }
+
+    #[test]
+    fn gosper_glider_gun_emits_a_glider_every_thirty_generations() {
+        let gun = Pattern::gosper_glider_gun();
+        assert!(gun.cells().iter().all(|&(dx, dy)| (0..36).contains(&dx) && (0..9).contains(&dy)));
+
+        let mut life = life_with(64, 64, &gun, (1, 1));
+        assert_eq!(life.population(), 36);
+        for _ in 0..30 {
+            life.step();
+        }
+        // The gun is back in its starting phase, plus one freshly fired glider.
+        assert_eq!(life.population(), 36 + 5);
+    }
+
+    #[test]
+    fn block_is_a_still_life() {
+        let mut life = life_with(6, 6, &Pattern::block(), (2, 2));
+        let initial = life.clone();
+        for _ in 0..5 {
+            life.step();
+            assert_eq!(life, initial);
+        }
+    }
+
+    #[test]
+    fn a_blinker_across_the_edge_wraps_and_still_oscillates() {
+        let mut life = life_with(5, 5, &Pattern::blinker(), (4, 0));
+        assert!(life.get(4, 0) && life.get(0, 0) && life.get(1, 0));
+        let initial = life.clone();
+        life.step();
+        assert!(life.get(0, 4) && life.get(0, 0) && life.get(0, 1));
+        life.step();
+        assert_eq!(life, initial);
+    }
+
+    #[test]
+    fn set_get_and_render_agree() {
+        let mut life = LifeAutomaton::new(3, 2);
+        life.set(0, 0, true);
+        life.set(2, 1, true);
+        life.set(0, 0, false);
+        life.set(1, 0, true);
+        assert!(life.get(1, 0) && life.get(2, 1) && !life.get(0, 0));
+        assert_eq!(life.population(), 2);
+        assert_eq!(life.render(), ".#.\n..#\n");
+    }
+
+    #[test]
+    fn highlife_births_on_six_neighbors_where_conway_does_not() {
+        let ring = [(1, 1), (2, 1), (3, 1), (1, 2), (3, 2), (1, 3)];
+        let mut conway = LifeAutomaton::new(6, 6);
+        let mut highlife = LifeAutomaton::with_rule(6, 6, &[3, 6], &[2, 3]);
+        for &(x, y) in &ring {
+            conway.set(x, y, true);
+            highlife.set(x, y, true);
+        }
+        conway.step();
+        highlife.step();
+        assert!(!conway.get(2, 2));
+        assert!(highlife.get(2, 2));
+    }
+
+    #[test]
+    #[should_panic(expected = "at most 8 neighbors")]
+    fn with_rule_rejects_a_count_above_eight() {
+        LifeAutomaton::with_rule(4, 4, &[3, 9], &[2, 3]);
+    }
+}
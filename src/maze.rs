@@ -1,10 +1,14 @@
 //! # Maze Generation Module
 
-// Provides functions for generating random mazes using a randomized
-// depth-first search algorithm.
+// Provides functions for generating random mazes using randomized
+// depth-first search, Prim's algorithm, Kruskal's algorithm, or recursive
+// division.
 
 use crate::grid::{Cell, Grid, Point};
 use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::cmp::Ordering;
 
 /// Generates a random maze of a given size.
 ///
@@ -15,11 +19,221 @@ use rand::seq::SliceRandom;
 /// * `width` - The width of the maze. Must be an odd number.
 /// * `height` - The height of the maze. Must be an odd number.
 pub fn generate_maze(width: usize, height: usize) -> Grid {
+    generate_maze_seeded(width, height, rand::rng().random())
+}
+
+/// The algorithm used to carve a maze.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MazeAlgorithm {
+    /// Randomized depth-first search: long, winding corridors.
+    #[default]
+    DepthFirst,
+    /// Randomized Prim's algorithm: many short dead ends.
+    Prim,
+    /// Recursive division: straight walls with a room-like look.
+    RecursiveDivision,
+    /// Randomized Kruskal's algorithm: an even texture of short passages.
+    Kruskal,
+}
+
+/// Generates a maze like `generate_maze`, drawing its random choices from a
+/// `ChaCha8Rng` seeded with `seed`, so the same seed always carves the same maze.
+///
+/// # Arguments
+/// * `width` - The width of the maze. Must be an odd number.
+/// * `height` - The height of the maze. Must be an odd number.
+/// * `seed` - The seed for the random number generator.
+pub fn generate_maze_seeded(width: usize, height: usize, seed: u64) -> Grid {
+    build_maze(width, height, MazeAlgorithm::DepthFirst, None, &mut ChaCha8Rng::seed_from_u64(seed)).0
+}
+
+/// Generates a random maze using randomized Prim's algorithm, which grows the
+/// maze outward from a random frontier and leaves many short dead ends.
+///
+/// Like `generate_maze`, the maze has a path from `(0, 1)` to
+/// `(width - 1, height - 2)`, and `width` and `height` must be odd.
+pub fn generate_maze_prim(width: usize, height: usize) -> Grid {
+    generate_maze_with(width, height, MazeAlgorithm::Prim)
+}
+
+/// Generates a random maze by recursive division, which splits an open area
+/// with walls that each have a single gap, giving a room-like look.
+///
+/// Like `generate_maze`, the maze has a path from `(0, 1)` to
+/// `(width - 1, height - 2)`, and `width` and `height` must be odd.
+pub fn generate_maze_recursive_division(width: usize, height: usize) -> Grid {
+    generate_maze_with(width, height, MazeAlgorithm::RecursiveDivision)
+}
+
+/// Generates a maze with randomized Kruskal's algorithm, which knocks down
+/// walls in random order, each only if it joins two separate regions. The
+/// result has a uniform texture without the long corridors of `generate_maze`.
+///
+/// Like `generate_maze_seeded`, the same seed always carves the same maze, and
+/// the maze has a path from `(0, 1)` to `(width - 1, height - 2)`.
+///
+/// # Arguments
+/// * `width` - The width of the maze. Must be an odd number.
+/// * `height` - The height of the maze. Must be an odd number.
+/// * `seed` - The seed for the random number generator.
+pub fn generate_maze_kruskal(width: usize, height: usize, seed: u64) -> Grid {
+    build_maze(width, height, MazeAlgorithm::Kruskal, None, &mut ChaCha8Rng::seed_from_u64(seed)).0
+}
+
+/// Generates a random maze with the chosen algorithm.
+///
+/// Every algorithm carves a perfect maze with a path from `(0, 1)` to
+/// `(width - 1, height - 2)`.
+///
+/// # Arguments
+/// * `width` - The width of the maze. Must be an odd number.
+/// * `height` - The height of the maze. Must be an odd number.
+/// * `algorithm` - The carving algorithm to use.
+pub fn generate_maze_with(width: usize, height: usize, algorithm: MazeAlgorithm) -> Grid {
+    build_maze(width, height, algorithm, None, &mut rand::rng()).0
+}
+
+/// Generates a random maze whose openings are at `start` and `goal` instead of
+/// the usual `(0, 1)` and `(width - 1, height - 2)`.
+///
+/// Maze cells sit on odd coordinates, so each endpoint is snapped before it is
+/// carved:
+/// * A point on the outer wall becomes an opening in that wall. Its coordinate
+///   along the wall is moved to the nearest odd value, so the opening leads
+///   into a maze cell. A corner is treated as lying on its left or right wall.
+/// * A point inside the wall is moved to the nearest maze cell.
+///
+/// # Returns
+/// The maze together with the snapped start and goal, which are always
+/// connected.
+///
+/// # Panics
+/// Panics if `width` or `height` is even or less than 3, or if `start` or
+/// `goal` lies outside the grid.
+pub fn generate_maze_between(width: usize, height: usize, start: Point, goal: Point) -> (Grid, Point, Point) {
+    generate_maze_between_seeded(width, height, start, goal, rand::rng().random())
+}
+
+/// Generates a maze like `generate_maze_between`, drawing its random choices
+/// from a `ChaCha8Rng` seeded with `seed`, so the same seed and endpoints
+/// always carve the same maze.
+pub fn generate_maze_between_seeded(width: usize, height: usize, start: Point, goal: Point, seed: u64) -> (Grid, Point, Point) {
+    build_maze(width, height, MazeAlgorithm::DepthFirst, Some((start, goal)), &mut ChaCha8Rng::seed_from_u64(seed))
+}
+
+/// Snaps a maze endpoint onto a maze cell or an opening into one.
+fn snap_endpoint(point: Point, width: usize, height: usize) -> Point {
+    // Moves a coordinate to the nearest odd value in 1..=len-2.
+    let snap = |coord: usize, len: usize| (coord | 1).min(len - 2);
+
+    if point.x == 0 || point.x == width - 1 {
+        Point::new(point.x, snap(point.y, height))
+    } else if point.y == 0 || point.y == height - 1 {
+        Point::new(snap(point.x, width), point.y)
+    } else {
+        Point::new(snap(point.x, width), snap(point.y, height))
+    }
+}
+
+/// Adds loops to a maze by knocking out dead ends, so it has more than one
+/// solution.
+///
+/// A `dead_end_removal_fraction` of the maze's dead ends (from 0.0 to 1.0) is
+/// picked at random, and each is joined to an adjacent cell by carving one
+/// extra wall, preferring a neighbor that is itself a dead end. Only walls
+/// between interior cells are carved, so the outer boundary stays closed
+/// except at the existing entrance and exit.
+///
+/// # Arguments
+/// * `grid` - A maze produced by one of the generators in this module.
+/// * `dead_end_removal_fraction` - The fraction of dead ends to remove.
+/// * `rng` - The random number generator used to pick dead ends and walls.
+pub fn braid_maze<R: Rng + ?Sized>(grid: &mut Grid, dead_end_removal_fraction: f64, rng: &mut R) {
+    let (width, height) = (grid.width(), grid.height());
+    let mut dead_ends: Vec<Point> = maze_cells(width, height).filter(|&p| is_dead_end(grid, p)).collect();
+    dead_ends.shuffle(rng);
+
+    let to_remove = (dead_ends.len() as f64 * dead_end_removal_fraction.clamp(0.0, 1.0)).round() as usize;
+    for &cell in dead_ends.iter().take(to_remove) {
+        // An earlier carve may already have opened this dead end.
+        if !is_dead_end(grid, cell) {
+            continue;
+        }
+
+        let mut candidates: Vec<Point> = cells_two_away(cell, width, height)
+            .filter(|&next| grid[wall_between(cell, next)] == Cell::Blocked)
+            .collect();
+        candidates.shuffle(rng);
+        // Joining two dead ends removes both with a single wall.
+        candidates.sort_by_key(|&next| !is_dead_end(grid, next));
+
+        if let Some(&next) = candidates.first() {
+            grid[wall_between(cell, next)] = Cell::Free;
+        }
+    }
+}
+
+/// Returns the odd-aligned maze cells inside the outer wall.
+fn maze_cells(width: usize, height: usize) -> impl Iterator<Item = Point> {
+    (1..height.saturating_sub(1))
+        .step_by(2)
+        .flat_map(move |y| (1..width.saturating_sub(1)).step_by(2).map(move |x| Point::new(x, y)))
+}
+
+/// Returns `true` if the free cell at `point` has exactly one open neighbor.
+fn is_dead_end(grid: &Grid, point: Point) -> bool {
+    grid[point] != Cell::Blocked && grid.neighbors(point).count() == 1
+}
+
+/// Returns the wall cell between two maze cells two steps apart.
+fn wall_between(a: Point, b: Point) -> Point {
+    Point::new((a.x + b.x) / 2, (a.y + b.y) / 2)
+}
+
+/// Carves a maze with `algorithm` and opens its entrance and exit.
+///
+/// The openings are at `(0, 1)` and `(width - 1, height - 2)`, unless
+/// `openings` gives a start and goal, which are snapped as described on
+/// `generate_maze_between`. Returns the maze with the openings used.
+fn build_maze<R: Rng + ?Sized>(
+    width: usize,
+    height: usize,
+    algorithm: MazeAlgorithm,
+    openings: Option<(Point, Point)>,
+    rng: &mut R,
+) -> (Grid, Point, Point) {
     assert!(width % 2 != 0 && height % 2 != 0, "Width and height must be odd.");
+    assert!(width >= 3 && height >= 3, "Width and height must be at least 3.");
+
+    let (start, goal) = match openings {
+        Some((start, goal)) => {
+            assert!(
+                start.x < width && start.y < height && goal.x < width && goal.y < height,
+                "Start and goal must lie inside the maze."
+            );
+            (snap_endpoint(start, width, height), snap_endpoint(goal, width, height))
+        }
+        None => (Point::new(0, 1), Point::new(width - 1, height - 2)),
+    };
+
+    let mut grid = match algorithm {
+        MazeAlgorithm::DepthFirst => carve_depth_first(width, height, rng),
+        MazeAlgorithm::Prim => carve_prim(width, height, rng),
+        MazeAlgorithm::RecursiveDivision => carve_recursive_division(width, height, rng),
+        MazeAlgorithm::Kruskal => carve_kruskal(width, height, rng),
+    };
+
+    // Create an entrance and an exit.
+    grid[start] = Cell::Free;
+    grid[goal] = Cell::Free;
+
+    (grid, start, goal)
+}
 
+/// Carves a maze with a randomized depth-first search.
+fn carve_depth_first<R: Rng + ?Sized>(width: usize, height: usize, rng: &mut R) -> Grid {
     let mut grid = Grid::new(width, height, Cell::Blocked);
     let mut stack: Vec<Point> = Vec::new();
-    let mut rng = rand::rng();
 
     // Start carving from the center of the grid.
     let start_point = Point::new(1, 1);
@@ -28,7 +242,7 @@ pub fn generate_maze(width: usize, height: usize) -> Grid {
 
     while let Some(current) = stack.last().copied() {
         let mut directions = [(-2, 0), (2, 0), (0, -2), (0, 2)];
-        directions.shuffle(&mut rng);
+        directions.shuffle(rng);
 
         let mut moved = false;
         for (dx, dy) in directions {
@@ -56,9 +270,322 @@ pub fn generate_maze(width: usize, height: usize) -> Grid {
         }
     }
 
-    // Create an entrance and an exit.
-    grid[Point::new(0, 1)] = Cell::Free;
-    grid[Point::new(width - 1, height - 2)] = Cell::Free;
+    grid
+}
+
+/// Returns the maze cells two steps away from `point` in each direction that
+/// lie inside the outer wall.
+fn cells_two_away(point: Point, width: usize, height: usize) -> impl Iterator<Item = Point> {
+    [(-2, 0), (2, 0), (0, -2), (0, 2)].into_iter().filter_map(move |(dx, dy)| {
+        let nx = point.x as isize + dx;
+        let ny = point.y as isize + dy;
+        if nx > 0 && nx < width as isize - 1 && ny > 0 && ny < height as isize - 1 {
+            Some(Point::new(nx as usize, ny as usize))
+        } else {
+            None
+        }
+    })
+}
+
+/// Carves a maze with randomized Prim's algorithm: repeatedly picks a random
+/// cell bordering the carved area and joins it to a random carved neighbor.
+fn carve_prim<R: Rng + ?Sized>(width: usize, height: usize, rng: &mut R) -> Grid {
+    let mut grid = Grid::new(width, height, Cell::Blocked);
+    let mut frontier: Vec<Point> = Vec::new();
+    let mut in_frontier = vec![false; width * height];
+
+    let start_point = Point::new(1, 1);
+    grid[start_point] = Cell::Free;
+    for next in cells_two_away(start_point, width, height) {
+        in_frontier[next.y * width + next.x] = true;
+        frontier.push(next);
+    }
+
+    while !frontier.is_empty() {
+        let current = frontier.swap_remove(rng.random_range(0..frontier.len()));
+
+        // Join the cell to one of its already-carved neighbors.
+        let carved: Vec<Point> = cells_two_away(current, width, height).filter(|&p| grid[p] == Cell::Free).collect();
+        let joined = carved[rng.random_range(0..carved.len())];
+        grid[current] = Cell::Free;
+        grid[wall_between(current, joined)] = Cell::Free;
 
+        for next in cells_two_away(current, width, height) {
+            let index = next.y * width + next.x;
+            if grid[next] == Cell::Blocked && !in_frontier[index] {
+                in_frontier[index] = true;
+                frontier.push(next);
+            }
+        }
+    }
+
+    grid
+}
+
+/// Carves a maze with randomized Kruskal's algorithm: visits every wall
+/// between two maze cells in random order and carves it if the cells are not
+/// yet connected.
+fn carve_kruskal<R: Rng + ?Sized>(width: usize, height: usize, rng: &mut R) -> Grid {
+    let mut grid = Grid::new(width, height, Cell::Blocked);
+    let mut regions = DisjointSet::new(width * height);
+
+    let mut walls: Vec<(Point, Point)> = Vec::new();
+    for cell in maze_cells(width, height) {
+        grid[cell] = Cell::Free;
+        // Only look right and down so each wall is listed once.
+        walls.extend(
+            cells_two_away(cell, width, height)
+                .filter(|&next| next.x > cell.x || next.y > cell.y)
+                .map(|next| (cell, next)),
+        );
+    }
+    walls.shuffle(rng);
+
+    for (a, b) in walls {
+        if regions.union(a.y * width + a.x, b.y * width + b.x) {
+            grid[wall_between(a, b)] = Cell::Free;
+        }
+    }
+
+    grid
+}
+
+/// A union-find over the indices `0..len`, with path halving and union by size.
+struct DisjointSet {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+        }
+    }
+
+    /// Returns the representative of the set containing `index`.
+    fn find(&mut self, mut index: usize) -> usize {
+        while self.parent[index] != index {
+            self.parent[index] = self.parent[self.parent[index]];
+            index = self.parent[index];
+        }
+        index
+    }
+
+    /// Merges the sets containing `a` and `b`. Returns `false` if they were
+    /// already the same set.
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        true
+    }
+}
+
+/// Carves a maze by recursive division: starts from an open area and splits it
+/// with walls, each with a single gap, until every chamber is one cell wide.
+fn carve_recursive_division<R: Rng + ?Sized>(width: usize, height: usize, rng: &mut R) -> Grid {
+    let mut grid = Grid::new(width, height, Cell::Blocked);
+    for y in 1..height - 1 {
+        for x in 1..width - 1 {
+            grid[Point::new(x, y)] = Cell::Free;
+        }
+    }
+
+    if width > 2 && height > 2 {
+        divide(&mut grid, (1, width - 2), (1, height - 2), rng);
+    }
     grid
 }
+
+/// Splits the open chamber spanning the inclusive odd-aligned ranges `xs` and
+/// `ys` with a wall on an even row or column, then divides both halves.
+fn divide<R: Rng + ?Sized>(grid: &mut Grid, xs: (usize, usize), ys: (usize, usize), rng: &mut R) {
+    let (x_min, x_max) = xs;
+    let (y_min, y_max) = ys;
+    let chamber_width = x_max - x_min;
+    let chamber_height = y_max - y_min;
+    if chamber_width < 2 || chamber_height < 2 {
+        return; // The chamber is a single corridor.
+    }
+
+    // Cut across the longer side so chambers stay roughly square.
+    let horizontal = match chamber_height.cmp(&chamber_width) {
+        Ordering::Greater => true,
+        Ordering::Less => false,
+        Ordering::Equal => rng.random_bool(0.5),
+    };
+
+    if horizontal {
+        let wall_y = y_min + 1 + 2 * rng.random_range(0..chamber_height / 2);
+        let gap_x = x_min + 2 * rng.random_range(0..=chamber_width / 2);
+        for x in (x_min..=x_max).filter(|&x| x != gap_x) {
+            grid[Point::new(x, wall_y)] = Cell::Blocked;
+        }
+        divide(grid, xs, (y_min, wall_y - 1), rng);
+        divide(grid, xs, (wall_y + 1, y_max), rng);
+    } else {
+        let wall_x = x_min + 1 + 2 * rng.random_range(0..chamber_width / 2);
+        let gap_y = y_min + 2 * rng.random_range(0..=chamber_height / 2);
+        for y in (y_min..=y_max).filter(|&y| y != gap_y) {
+            grid[Point::new(wall_x, y)] = Cell::Blocked;
+        }
+        divide(grid, (x_min, wall_x - 1), ys, rng);
+        divide(grid, (wall_x + 1, x_max), ys, rng);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_same_seed_carves_an_identical_maze() {
+        let first = generate_maze_seeded(31, 21, 42);
+        let second = generate_maze_seeded(31, 21, 42);
+        assert_eq!((first.width(), first.height()), (31, 21));
+        assert!(first.iter().zip(second.iter()).all(|(a, b)| a == b));
+
+        let other = generate_maze_seeded(31, 21, 43);
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn every_algorithm_carves_a_solvable_connected_maze() {
+        use crate::pathfinding::{a_star, dijkstra};
+        let algorithms = [
+            MazeAlgorithm::DepthFirst,
+            MazeAlgorithm::Prim,
+            MazeAlgorithm::RecursiveDivision,
+            MazeAlgorithm::Kruskal,
+        ];
+        for algorithm in algorithms {
+            for (width, height) in [(3, 3), (5, 7), (21, 15), (31, 31)] {
+                let grid = generate_maze_with(width, height, algorithm);
+                let (start, goal) = (Point::new(0, 1), Point::new(width - 1, height - 2));
+                assert!(a_star(&grid, start, goal).is_some(), "{algorithm:?} {width}x{height}");
+
+                // Every maze cell is reachable from the entrance.
+                let reachable = dijkstra(&grid, start);
+                assert!(maze_cells(width, height).all(|cell| reachable.contains_key(&cell)), "{algorithm:?}");
+            }
+        }
+    }
+
+    /// Counts the maze cells with exactly one open neighbor.
+    fn dead_end_count(grid: &Grid) -> usize {
+        maze_cells(grid.width(), grid.height()).filter(|&p| is_dead_end(grid, p)).count()
+    }
+
+    /// Returns the cells of the outer wall, in a fixed order.
+    fn boundary(grid: &Grid) -> Vec<Cell> {
+        let (width, height) = (grid.width(), grid.height());
+        grid.iter()
+            .filter(|(p, _)| p.x == 0 || p.y == 0 || p.x == width - 1 || p.y == height - 1)
+            .map(|(_, cell)| cell)
+            .collect()
+    }
+
+    #[test]
+    fn braiding_removes_dead_ends_without_opening_the_boundary() {
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        let mut grid = generate_maze_seeded(41, 41, 3);
+        let before = dead_end_count(&grid);
+        let outer_wall = boundary(&grid);
+        assert!(before > 20);
+
+        braid_maze(&mut grid, 1.0, &mut rng);
+        assert!(dead_end_count(&grid) < before / 4, "{} of {before} dead ends left", dead_end_count(&grid));
+        assert_eq!(boundary(&grid), outer_wall);
+        assert!(crate::pathfinding::a_star(&grid, Point::new(0, 1), Point::new(40, 39)).is_some());
+    }
+
+    #[test]
+    fn braiding_nothing_leaves_the_maze_unchanged() {
+        let mut grid = generate_maze_seeded(21, 21, 9);
+        let original = grid.clone();
+        braid_maze(&mut grid, 0.0, &mut ChaCha8Rng::seed_from_u64(9));
+        assert_eq!(grid, original);
+    }
+
+    #[test]
+    fn a_maze_between_custom_endpoints_is_solvable_between_them() {
+        for seed in 0..5 {
+            let (grid, start, goal) = generate_maze_between_seeded(21, 15, Point::new(8, 0), Point::new(20, 6), seed);
+            assert_eq!((start, goal), (Point::new(9, 0), Point::new(20, 7)));
+            assert!(crate::pathfinding::a_star(&grid, start, goal).is_some());
+            // The default openings stay closed.
+            assert_eq!(grid[Point::new(0, 1)], Cell::Blocked);
+            assert_eq!(grid[Point::new(20, 13)], Cell::Blocked);
+        }
+
+        let (grid, start, goal) = generate_maze_between(15, 15, Point::new(4, 6), Point::new(14, 14));
+        assert_eq!((start, goal), (Point::new(5, 7), Point::new(14, 13)));
+        assert!(crate::pathfinding::a_star(&grid, start, goal).is_some());
+    }
+
+    #[test]
+    fn the_same_seed_and_endpoints_carve_an_identical_maze() {
+        let (start, goal) = (Point::new(0, 5), Point::new(9, 10));
+        assert_eq!(
+            generate_maze_between_seeded(11, 11, start, goal, 5),
+            generate_maze_between_seeded(11, 11, start, goal, 5)
+        );
+    }
+
+    #[test]
+    fn the_smallest_maze_between_endpoints_has_one_cell() {
+        let (grid, start, goal) = generate_maze_between_seeded(3, 3, Point::new(1, 0), Point::new(2, 2), 0);
+        assert_eq!((start, goal), (Point::new(1, 0), Point::new(2, 1)));
+        assert_eq!(grid.to_ascii(), "#.#\n#..\n###\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "at least 3")]
+    fn a_maze_narrower_than_three_cells_is_rejected() {
+        generate_maze_between_seeded(1, 5, Point::new(0, 0), Point::new(0, 4), 0);
+    }
+
+    #[test]
+    fn disjoint_set_merges_only_separate_sets() {
+        let mut sets = DisjointSet::new(6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert_eq!(sets.find(0), sets.find(3));
+        assert_ne!(sets.find(0), sets.find(4));
+        let root = sets.find(2);
+        assert_eq!(sets.size[root], 4);
+    }
+
+    #[test]
+    fn kruskal_carves_a_spanning_tree_of_the_maze_cells() {
+        let (width, height) = (25, 17);
+        let mut rng = ChaCha8Rng::seed_from_u64(8);
+        let grid = carve_kruskal(width, height, &mut rng);
+
+        // A tree over n cells has n - 1 edges, and each edge is one carved wall.
+        let cells = maze_cells(width, height).count();
+        let open = grid.iter().filter(|&(_, cell)| cell == Cell::Free).count();
+        assert_eq!(open, 2 * cells - 1);
+
+        let reachable = crate::pathfinding::dijkstra(&grid, Point::new(1, 1));
+        assert_eq!(reachable.len(), open);
+    }
+
+    #[test]
+    fn kruskal_mazes_are_reproducible_and_solvable() {
+        let maze = generate_maze_kruskal(21, 15, 5);
+        assert_eq!(maze, generate_maze_kruskal(21, 15, 5));
+        assert_ne!(maze, generate_maze_kruskal(21, 15, 6));
+        assert!(crate::pathfinding::a_star(&maze, Point::new(0, 1), Point::new(20, 13)).is_some());
+    }
+}
@@ -7,9 +7,40 @@
 // We reuse the Point struct from our existing pathfinding work.
 // Make sure it's accessible from this module.
 use crate::grid::Point; // Assuming Point is in a `grid` module. Adjust if needed.
-use std::collections::HashMap;
+use crate::grid::{Cell, Grid};
+use std::collections::{HashMap, HashSet, VecDeque};
 use ordered_float::OrderedFloat;
 use std::collections::BinaryHeap;
+use std::fmt::{self, Write};
+
+/// Cost improvements smaller than this are ignored by Bellman-Ford, so that
+/// rounding errors cannot masquerade as negative cycles.
+const COST_EPSILON: f64 = 1e-9;
+
+/// Parent pointers from a path search: for each reached node, the previous
+/// node and the residual arc taken from it.
+type ParentMap = HashMap<Point, (Point, ResidualArc)>;
+
+/// For each node, the edges entering it as `(from, index in adj[from])`.
+type IncomingEdges = HashMap<Point, Vec<(Point, usize)>>;
+
+/// Errors reported by the graph's path searches.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GraphError {
+    /// Edges with spare residual capacity form a cycle of negative total
+    /// cost reachable from the source, so no path is cheapest.
+    NegativeCycle,
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphError::NegativeCycle => write!(f, "the graph has a negative-cost cycle reachable from the source"),
+        }
+    }
+}
+
+impl std::error::Error for GraphError {}
 
 /// Represents a directed connection between two nodes in the graph.
 #[derive(Debug, Clone)]
@@ -20,7 +51,40 @@ pub struct Edge {
     pub flow: u64,
 }
 
+/// A move through the residual network: along the `index`-th edge out of
+/// `from`, or, when `backward`, from that edge's `to` node back to `from`,
+/// which cancels flow on the edge.
+#[derive(Debug, Clone, Copy)]
+struct ResidualArc {
+    from: Point,
+    index: usize,
+    backward: bool,
+}
+
+/// The outcome of routing flow down a single cheapest path.
+#[derive(Debug, Clone)]
+pub struct RouteResult {
+    /// The nodes visited, from source to sink.
+    pub path: Vec<Point>,
+    /// The bottleneck flow pushed along the path.
+    pub flow: u64,
+    /// The sum of the edge costs along the path.
+    pub total_cost: f64,
+    /// The flow added to each edge, as `(from, to, flow)`, in path order.
+    pub edge_flows: Vec<(Point, Point, u64)>,
+}
+
 /// Represents the entire flow network, including all nodes and edges.
+///
+/// `adj` holds only the edges that were added. The flow algorithms may also
+/// cancel flow by moving backwards along an edge that carries some; those
+/// residual moves are derived from each edge's `flow` when a search runs, so
+/// flows and capacities changed directly through `adj` are taken into account.
+///
+/// Edges are indexed by their endpoints as `add_edge`, `remove_edge` and
+/// `remove_node` change them. Edges pushed onto `adj` directly can carry flow,
+/// but are missing from the index, so their flow is never cancelled and
+/// `remove_edge`/`remove_node` do not see them.
 #[derive(Debug)]
 pub struct Graph {
     // We use a HashMap to store the adjacency list.
@@ -28,6 +92,12 @@ pub struct Graph {
     pub adj: HashMap<Point, Vec<Edge>>,
     pub source: Point,
     pub sink: Point,
+    /// Maps `(from, to)` to the index of the first edge between them in
+    /// `adj[from]`, so edges can be found without scanning the list.
+    edge_index: HashMap<(Point, Point), usize>,
+    /// For each node, the edges entering it, kept sorted so that searches
+    /// visit them in the same order on every run.
+    incoming: IncomingEdges,
 }
 
 impl Graph {
@@ -37,7 +107,41 @@ impl Graph {
             adj: HashMap::new(),
             source,
             sink,
+            edge_index: HashMap::new(),
+            incoming: HashMap::new(),
+        }
+    }
+
+    /// Builds a flow network from a grid, such as a generated maze.
+    ///
+    /// Every `Free` or `Path` cell becomes a node, and each pair of orthogonally
+    /// adjacent passable cells is joined by an edge in both directions with the
+    /// given `capacity` and `cost`.
+    ///
+    /// The source and sink default to the first and last passable cells in
+    /// row-major order, which for a maze from `generate_maze` are its entrance
+    /// and exit. Assign `source` and `sink` to route between other cells.
+    pub fn from_grid(grid: &Grid, capacity: u64, cost: f64) -> Self {
+        let (width, height) = (grid.width(), grid.height());
+        let passable: Vec<Point> = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Point::new(x, y)))
+            .filter(|&p| grid[p] != Cell::Blocked)
+            .collect();
+
+        let source = passable.first().copied().unwrap_or(Point::new(0, 0));
+        let sink = passable.last().copied().unwrap_or(source);
+        let mut graph = Graph::new(source, sink);
+
+        for &p in &passable {
+            graph.add_node(p);
+            for q in [Point::new(p.x + 1, p.y), Point::new(p.x, p.y + 1)] {
+                if q.x < width && q.y < height && grid[q] != Cell::Blocked {
+                    graph.add_edge(p, q, capacity, cost);
+                    graph.add_edge(q, p, capacity, cost);
+                }
+            }
         }
+        graph
     }
 
     /// Adds a new node to the graph.
@@ -52,12 +156,120 @@ impl Graph {
         self.add_node(from);
         self.add_node(to);
 
+        let index = self.adj[&from].len();
         self.adj.get_mut(&from).unwrap().push(Edge {
             to,
             capacity,
             cost,
             flow: 0,
         });
+        self.index_edge(from, index);
+    }
+
+    /// Removes the edge from `from` to `to`. If several were added, the first
+    /// is removed.
+    ///
+    /// Returns `true` if an edge was removed.
+    pub fn remove_edge(&mut self, from: Point, to: Point) -> bool {
+        let Some(&index) = self.edge_index.get(&(from, to)) else {
+            return false;
+        };
+        self.unindex_edges(from);
+        self.adj.get_mut(&from).unwrap().remove(index);
+        self.index_edges(from);
+        true
+    }
+
+    /// Removes a node together with every edge leaving or entering it.
+    ///
+    /// The source and sink may be removed like any other node. The graph keeps
+    /// pointing at them, so routing finds no path until they are added back or
+    /// `source`/`sink` are reassigned.
+    pub fn remove_node(&mut self, node: Point) {
+        let mut neighbors: Vec<Point> = self.incoming.get(&node).into_iter().flatten().map(|&(from, _)| from).collect();
+        neighbors.dedup();
+        neighbors.retain(|&from| from != node);
+
+        self.unindex_edges(node);
+        self.adj.remove(&node);
+        for from in neighbors {
+            self.unindex_edges(from);
+            self.adj.get_mut(&from).unwrap().retain(|e| e.to != node);
+            self.index_edges(from);
+        }
+        self.incoming.remove(&node);
+    }
+
+    /// Records the `index`-th edge out of `from` in the endpoint index and in
+    /// the incoming list of the node it enters.
+    fn index_edge(&mut self, from: Point, index: usize) {
+        let to = self.adj[&from][index].to;
+        self.edge_index.entry((from, to)).or_insert(index);
+        let entering = self.incoming.entry(to).or_default();
+        let position = entering.partition_point(|&entry| entry < (from, index));
+        entering.insert(position, (from, index));
+    }
+
+    /// Re-indexes every edge out of `from`, after `unindex_edges` and a change
+    /// to the list.
+    fn index_edges(&mut self, from: Point) {
+        for index in 0..self.get_edges(&from).len() {
+            self.index_edge(from, index);
+        }
+    }
+
+    /// Forgets every edge out of `from`, before its list is changed and the
+    /// positions of its edges shift.
+    fn unindex_edges(&mut self, from: Point) {
+        for edge in self.adj.get(&from).into_iter().flatten() {
+            self.edge_index.remove(&(from, edge.to));
+            if let Some(entering) = self.incoming.get_mut(&edge.to) {
+                entering.retain(|&(f, _)| f != from);
+            }
+        }
+    }
+
+    /// The moves out of `node` in the residual network: along each of its own
+    /// edges, then backwards against each edge entering it.
+    fn residual_arcs(&self, node: Point) -> impl Iterator<Item = ResidualArc> + '_ {
+        let forward = (0..self.get_edges(&node).len()).map(move |index| ResidualArc { from: node, index, backward: false });
+        let backward = self
+            .incoming
+            .get(&node)
+            .into_iter()
+            .flatten()
+            .map(|&(from, index)| ResidualArc { from, index, backward: true });
+        forward.chain(backward)
+    }
+
+    /// The node a residual arc leads to.
+    fn arc_target(&self, arc: ResidualArc) -> Point {
+        if arc.backward { arc.from } else { self.adj[&arc.from][arc.index].to }
+    }
+
+    /// The flow that can still be pushed along a residual arc. Moving backwards
+    /// can cancel at most the flow the edge carries.
+    fn residual_capacity(&self, arc: ResidualArc) -> u64 {
+        let edge = &self.adj[&arc.from][arc.index];
+        if arc.backward { edge.flow } else { edge.capacity.saturating_sub(edge.flow) }
+    }
+
+    /// The cost of pushing flow along a residual arc. Moving backwards refunds
+    /// the edge's current cost.
+    fn edge_cost(&self, arc: ResidualArc) -> f64 {
+        let edge = &self.adj[&arc.from][arc.index];
+        if arc.backward { -edge.cost } else { edge.cost }
+    }
+
+    /// Pushes `amount` of flow along a residual arc. Moving backwards cancels
+    /// flow on the edge.
+    fn push_flow(&mut self, arc: ResidualArc, amount: u64) {
+        let edge = &mut self.adj.get_mut(&arc.from).unwrap()[arc.index];
+        if arc.backward {
+            edge.flow -= amount;
+        } else {
+            edge.flow += amount;
+        }
     }
 
     /// A helper to get all outgoing edges from a given node.
@@ -71,50 +283,76 @@ impl Graph {
         })
     }
 
-    /// Finds the cheapest path from source to sink using Dijkstra's algorithm.
+    /// Finds the cheapest path from `from` to `to` using Dijkstra's algorithm.
     /// This version is cost-aware and replaces the simple BFS.
-    /// It returns a map of parent pointers to reconstruct the path.
+    /// It returns a map of parent pointers to reconstruct the path, each being
+    /// the previous node and the residual arc taken from it.
+    ///
+    /// Moving backwards along an edge has a negative cost, so each node is
+    /// settled only once to guarantee termination.
 
-    fn find_cheapest_path_dijkstra(&self) -> (HashMap<Point, Point>, bool) {
+    fn find_cheapest_path_dijkstra(&self, from: Point, to: Point) -> (ParentMap, bool) {
         let mut distances: HashMap<Point, f64> = HashMap::new();
         let mut parent_map = HashMap::new();
+        let mut settled = HashSet::new();
         let mut pq = BinaryHeap::new();
 
-        distances.insert(self.source, 0.0);
+        distances.insert(from, 0.0);
         // We use OrderedFloat to allow f64 in the max-heap.
         // We still negate to make it a min-heap.
-        pq.push((OrderedFloat(-0.0), self.source));
+        pq.push((OrderedFloat(-0.0), from));
 
         while let Some((cost, u)) = pq.pop() {
             let cost = -cost.into_inner(); // unwrap the OrderedFloat
 
-            if cost > *distances.get(&u).unwrap_or(&f64::MAX) {
+            if cost > *distances.get(&u).unwrap_or(&f64::MAX) || !settled.insert(u) {
                 continue;
             }
-            if u == self.sink {
+            if u == to {
                 return (parent_map, true);
             }
 
-            for edge in self.get_edges(&u) {
-                if edge.capacity > edge.flow {
-                    let new_dist = cost + edge.cost;
-                    if new_dist < *distances.get(&edge.to).unwrap_or(&f64::MAX) {
-                        distances.insert(edge.to, new_dist);
-                        pq.push((OrderedFloat(-new_dist), edge.to));
-                        parent_map.insert(edge.to, u);
+            for arc in self.residual_arcs(u) {
+                let v = self.arc_target(arc);
+                if !settled.contains(&v) && self.residual_capacity(arc) > 0 {
+                    let new_dist = cost + self.edge_cost(arc);
+                    if new_dist < *distances.get(&v).unwrap_or(&f64::MAX) {
+                        distances.insert(v, new_dist);
+                        pq.push((OrderedFloat(-new_dist), v));
+                        parent_map.insert(v, (u, arc));
                     }
                 }
             }
         }
-        (parent_map, distances.contains_key(&self.sink))
+        (parent_map, distances.contains_key(&to))
     }
-    
+
+    /// Finds the cheapest path from `from` to `to` through edges with spare
+    /// residual capacity, the same way `route_detailed` does, but without
+    /// pushing any flow.
+    ///
+    /// # Returns
+    /// The nodes visited, from `from` to `to`, and the sum of the edge costs
+    /// along the way, or `None` if `to` cannot be reached.
+    pub fn shortest_path_cost(&self, from: Point, to: Point) -> Option<(Vec<Point>, f64)> {
+        let (parent_map, found) = self.find_cheapest_path_dijkstra(from, to);
+        if !found {
+            return None;
+        }
+
+        let steps = trace_steps(&parent_map, from, to);
+        let mut path = vec![from];
+        path.extend(steps.iter().map(|&(_, _, v)| v));
+        let cost = steps.iter().map(|&(_, arc, _)| self.edge_cost(arc)).sum();
+        Some((path, cost))
+    }
+
     /// Calculates the maximum flow, now using a cost-aware pathfinding method.
     pub fn edmonds_karp(&mut self) -> u64 {
         let mut max_flow = 0;
         loop {
             // Use the new Dijkstra-based pathfinder
-            let (parent_map, sink_found) = self.find_cheapest_path_dijkstra();
+            let (parent_map, sink_found) = self.find_cheapest_path_dijkstra(self.source, self.sink);
 
             if !sink_found {
                 break; // No more paths to the sink
@@ -124,10 +362,8 @@ impl Graph {
             let mut path_flow = u64::MAX;
             let mut current = self.sink;
             while current != self.source {
-                let prev = parent_map[&current];
-                let edge = self.adj.get(&prev).unwrap().iter()
-                    .find(|e| e.to == current).unwrap();
-                path_flow = path_flow.min(edge.capacity - edge.flow);
+                let (prev, arc) = parent_map[&current];
+                path_flow = path_flow.min(self.residual_capacity(arc));
                 current = prev;
             }
 
@@ -135,11 +371,8 @@ impl Graph {
             max_flow += path_flow;
             let mut v = self.sink;
             while v != self.source {
-                let u = parent_map[&v];
-                if let Some(edge) = self.adj.get_mut(&u).unwrap().iter_mut()
-                    .find(|e| e.to == v) {
-                    edge.flow += path_flow;
-                }
+                let (u, arc) = parent_map[&v];
+                self.push_flow(arc, path_flow);
                 v = u;
             }
         }
@@ -147,44 +380,742 @@ impl Graph {
     }
 
 
+    /// Computes a maximum flow of minimum total cost by successive shortest
+    /// paths: the cheapest augmenting path is found with Bellman-Ford, which
+    /// copes with the negative cost of cancelling flow, and saturated until the
+    /// sink is unreachable.
+    ///
+    /// Flow already in the graph is kept and built upon, so for a true minimum
+    /// the edges should carry no flow beforehand. Edge costs must not form a
+    /// negative cycle; if one is found, routing stops there.
+    ///
+    /// # Returns
+    /// The flow pushed by this call and its total cost (each edge's cost times
+    /// the flow through it).
+    pub fn min_cost_max_flow(&mut self) -> (u64, f64) {
+        let mut total_flow = 0;
+        let mut total_cost = 0.0;
+        if self.source == self.sink {
+            return (total_flow, total_cost);
+        }
+
+        while let Ok((parent_map, true)) = self.bellman_ford() {
+            let steps = trace_steps(&parent_map, self.source, self.sink);
+
+            let path_flow = steps.iter().map(|&(_, arc, _)| self.residual_capacity(arc)).min().unwrap_or(0);
+            let path_cost: f64 = steps.iter().map(|&(_, arc, _)| self.edge_cost(arc)).sum();
+            for &(_, arc, _) in &steps {
+                self.push_flow(arc, path_flow);
+            }
+
+            total_flow += path_flow;
+            total_cost += path_flow as f64 * path_cost;
+        }
+        (total_flow, total_cost)
+    }
+
+    /// Finds the cheapest path from source to sink through edges with spare
+    /// residual capacity using Bellman-Ford.
+    ///
+    /// Unlike the Dijkstra search used by `route_detailed`, this stays correct
+    /// when costs are negative, e.g. when cancelling part of a flow that is
+    /// already routed.
+    ///
+    /// # Returns
+    /// A map from each reached node to the node before it on its cheapest
+    /// path, and whether the sink was reached.
+    ///
+    /// # Errors
+    /// Returns `GraphError::NegativeCycle` if a negative-cost cycle can be
+    /// reached from the source.
+    pub fn find_cheapest_path_bellman_ford(&self) -> Result<(HashMap<Point, Point>, bool), GraphError> {
+        let (parent_map, sink_found) = self.bellman_ford()?;
+        Ok((parent_map.into_iter().map(|(node, (prev, _))| (node, prev)).collect(), sink_found))
+    }
+
+    /// Runs Bellman-Ford from the source, returning parent pointers (previous
+    /// node and residual arc) and whether the sink was reached.
+    ///
+    /// Shortest paths use at most one step fewer than there are nodes, so any
+    /// improvement on the pass after that means a negative cycle.
+    fn bellman_ford(&self) -> Result<(ParentMap, bool), GraphError> {
+        let mut distances: HashMap<Point, f64> = HashMap::from([(self.source, 0.0)]);
+        let mut parent_map = HashMap::new();
+
+        for pass in 0..=self.adj.len() {
+            let mut changed = false;
+            for &u in self.adj.keys() {
+                let Some(&distance) = distances.get(&u) else {
+                    continue;
+                };
+                for arc in self.residual_arcs(u) {
+                    if self.residual_capacity(arc) == 0 {
+                        continue;
+                    }
+                    let v = self.arc_target(arc);
+                    let new_dist = distance + self.edge_cost(arc);
+                    if new_dist < distances.get(&v).map_or(f64::INFINITY, |&d| d - COST_EPSILON) {
+                        distances.insert(v, new_dist);
+                        parent_map.insert(v, (u, arc));
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                break;
+            }
+            if pass == self.adj.len() {
+                return Err(GraphError::NegativeCycle);
+            }
+        }
+
+        Ok((parent_map, distances.contains_key(&self.sink)))
+    }
+
     /// Finds the single cheapest path and routes flow down it.
     /// This replaces edmonds_karp to act as a policy-driven Tactician.
 
     /// Finds the single cheapest path and routes flow, returning the flow and the path itself.
     pub fn route_cheapest_path(&mut self) -> (u64, Option<Vec<Point>>) {
-        let (parent_map, sink_found) = self.find_cheapest_path_dijkstra();
+        match self.route_detailed() {
+            Some(route) => (route.flow, Some(route.path)),
+            None => (0, None),
+        }
+    }
+
+    /// Finds the single cheapest path and routes flow down it, reporting the
+    /// path, its bottleneck flow, its total cost and the flow added to each edge.
+    ///
+    /// A step backwards along an edge cancels flow on that edge; it is reported
+    /// like any other step, and its negative cost counts towards the total.
+    ///
+    /// Returns `None` if the sink cannot be reached.
+    pub fn route_detailed(&mut self) -> Option<RouteResult> {
+        let (parent_map, sink_found) = self.find_cheapest_path_dijkstra(self.source, self.sink);
 
         if !sink_found {
-            return (0, None);
+            return None;
         }
 
-        // --- Reconstruct the path ---
-        let mut path = vec![self.sink];
-        let mut current = self.sink;
-        while current != self.source {
-            current = parent_map[&current];
-            path.push(current);
-        }
-        path.reverse();
-        let path_clone = path.clone(); // Clone it to return later
+        // --- Reconstruct the path, along with the edge taken at each step ---
+        let steps = trace_steps(&parent_map, self.source, self.sink);
+        let mut path = vec![self.source];
+        path.extend(steps.iter().map(|&(_, _, v)| v));
 
-        // --- Calculate bottleneck and push flow ---
+        // --- Calculate bottleneck and total cost ---
         let mut path_flow = u64::MAX;
-        for i in 0..path.len() - 1 {
-            let u = path[i];
-            let v = path[i+1];
-            let edge = self.adj.get(&u).unwrap().iter().find(|e| e.to == v).unwrap();
-            path_flow = path_flow.min(edge.capacity - edge.flow);
+        let mut total_cost = 0.0;
+        for &(_, arc, _) in &steps {
+            path_flow = path_flow.min(self.residual_capacity(arc));
+            total_cost += self.edge_cost(arc);
         }
 
-        for i in 0..path.len() - 1 {
-            let u = path[i];
-            let v = path[i+1];
-            if let Some(edge) = self.adj.get_mut(&u).unwrap().iter_mut().find(|e| e.to == v) {
-                edge.flow += path_flow;
+        // --- Push flow ---
+        let mut edge_flows = Vec::with_capacity(steps.len());
+        for &(u, arc, v) in &steps {
+            self.push_flow(arc, path_flow);
+            edge_flows.push((u, v, path_flow));
+        }
+
+        Some(RouteResult {
+            path,
+            flow: path_flow,
+            total_cost,
+            edge_flows,
+        })
+    }
+
+    /// Returns the total cost of the flow in the graph: the sum over every
+    /// edge of its flow times its cost.
+    pub fn total_cost(&self) -> f64 {
+        self.adj.values().flatten().map(|e| e.flow as f64 * e.cost).sum()
+    }
+
+    /// Returns how full each edge is, as `(from, to, flow / capacity)`, sorted
+    /// by `from` and then `to`. An edge with no capacity reports 0.0.
+    pub fn utilization(&self) -> Vec<(Point, Point, f64)> {
+        let mut report: Vec<(Point, Point, f64)> = self
+            .adj
+            .iter()
+            .flat_map(|(&from, edges)| {
+                edges.iter().map(move |e| {
+                    let ratio = if e.capacity == 0 { 0.0 } else { e.flow as f64 / e.capacity as f64 };
+                    (from, e.to, ratio)
+                })
+            })
+            .collect();
+        report.sort_by_key(|&(from, to, _)| (from, to));
+        report
+    }
+
+    /// Renders the graph in Graphviz DOT format, e.g. for `dot -Tpng`.
+    ///
+    /// Nodes are named after their coordinates, and each edge is labeled with
+    /// its `flow/capacity` and cost. The source is drawn in green and the sink
+    /// in red. Nodes and edges are sorted, so the output is the same on every
+    /// run.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph flow {\n");
+
+        let mut nodes: Vec<&Point> = self.adj.keys().collect();
+        nodes.sort();
+        for &node in &nodes {
+            let style = if *node == self.source {
+                " [style=filled, fillcolor=palegreen]"
+            } else if *node == self.sink {
+                " [style=filled, fillcolor=lightcoral]"
+            } else {
+                ""
+            };
+            writeln!(dot, "    {}{};", dot_id(*node), style).unwrap();
+        }
+
+        for &node in &nodes {
+            let mut edges: Vec<&Edge> = self.get_edges(node).iter().collect();
+            edges.sort_by_key(|e| e.to);
+            for edge in edges {
+                let label = format!("{}/{}, cost {}", edge.flow, edge.capacity, edge.cost);
+                writeln!(dot, "    {} -> {} [label={}];", dot_id(*node), dot_id(edge.to), dot_quote(&label)).unwrap();
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Segments a grid into foreground and background using a minimum graph cut.
+    ///
+    /// Every cell becomes a node linked to its four neighbors in both directions.
+    /// Neighbors holding the same `Cell` value are linked with capacity `smoothness`,
+    /// while differing neighbors get a capacity of 1, so the cut prefers to follow
+    /// the grid's own boundaries. Foreground seeds are tied to a super-source and
+    /// background seeds to a super-sink with capacities too large to cut.
+    ///
+    /// `smoothness` is rounded to a whole number and clamped to at least 1, so
+    /// equal neighbors are never cheaper to separate than differing ones, and to
+    /// at most `u32::MAX`, so the capacities cannot overflow.
+    ///
+    /// # Returns
+    /// One entry per cell, indexed by `y * width + x`, that is `true` when the cell
+    /// lies on the source (foreground) side of the cut.
+    pub fn min_cut_segmentation(
+        grid: &Grid,
+        foreground_seeds: &[Point],
+        background_seeds: &[Point],
+        smoothness: f64,
+    ) -> Vec<bool> {
+        let (width, height) = (grid.width(), grid.height());
+        // The super-source and super-sink sit just outside the grid.
+        let source = Point::new(width, 0);
+        let sink = Point::new(width, 1);
+        let mut graph = Graph::new(source, sink);
+
+        // Written so that NaN also falls back to 1.
+        let strong = if smoothness >= 1.0 { smoothness.round().min(u32::MAX as f64) as u64 } else { 1 };
+        let mut total_capacity: u64 = 0;
+        for y in 0..height {
+            for x in 0..width {
+                let p = Point::new(x, y);
+                graph.add_node(p);
+                for q in [Point::new(x + 1, y), Point::new(x, y + 1)] {
+                    if q.x < width && q.y < height {
+                        let capacity = if grid[p] == grid[q] { strong } else { 1 };
+                        graph.add_edge(p, q, capacity, 1.0);
+                        graph.add_edge(q, p, capacity, 1.0);
+                        total_capacity = total_capacity.saturating_add(2 * capacity);
+                    }
+                }
+            }
+        }
+
+        // Seed links must never be the cheapest thing to cut.
+        let hard = total_capacity.saturating_add(1);
+        for &seed in foreground_seeds {
+            graph.add_edge(source, seed, hard, 0.0);
+        }
+        for &seed in background_seeds {
+            graph.add_edge(seed, sink, hard, 0.0);
+        }
+
+        graph.edmonds_karp();
+        let source_side = graph.residual_reachable();
+
+        (0..width * height)
+            .map(|i| source_side.contains(&Point::new(i % width, i / width)))
+            .collect()
+    }
+
+    /// Returns every node reachable from the source in the residual graph,
+    /// following only arcs with spare residual capacity.
+    fn residual_reachable(&self) -> HashSet<Point> {
+        let mut visited = HashSet::from([self.source]);
+        let mut queue = VecDeque::from([self.source]);
+        while let Some(u) = queue.pop_front() {
+            for arc in self.residual_arcs(u) {
+                let v = self.arc_target(arc);
+                if self.residual_capacity(arc) > 0 && visited.insert(v) {
+                    queue.push_back(v);
+                }
             }
         }
-        
-        (path_flow, Some(path_clone))
+        visited
     }
-}
\ No newline at end of file
+}
+
+/// Follows parent pointers back from `to` to `from`, returning each step as
+/// `(previous node, residual arc, node)` in path order.
+fn trace_steps(parent_map: &ParentMap, from: Point, to: Point) -> Vec<(Point, ResidualArc, Point)> {
+    let mut steps = Vec::new();
+    let mut current = to;
+    while current != from {
+        let (prev, arc) = parent_map[&current];
+        steps.push((prev, arc, current));
+        current = prev;
+    }
+    steps.reverse();
+    steps
+}
+
+/// The DOT identifier for a node, derived from its coordinates.
+fn dot_id(point: Point) -> String {
+    dot_quote(&format!("({}, {})", point.x, point.y))
+}
+
+/// Quotes a string as a DOT identifier, escaping quotes and backslashes.
+fn dot_quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The cells of `grid` that `segmentation` puts in the foreground.
+    fn foreground(grid: &Grid, segmentation: &[bool]) -> Vec<Point> {
+        grid.iter_points().filter(|p| segmentation[p.y * grid.width() + p.x]).collect()
+    }
+
+    #[test]
+    fn segmentation_follows_the_boundary_between_regions() {
+        let grid = Grid::from_ascii("..##\n..##\n..##").unwrap();
+        let segmentation = Graph::min_cut_segmentation(&grid, &[Point::new(0, 1)], &[Point::new(3, 1)], 10.0);
+
+        let expected: Vec<Point> = grid.iter_points().filter(|p| p.x < 2).collect();
+        assert_eq!(foreground(&grid, &segmentation), expected);
+    }
+
+    #[test]
+    fn smoothness_below_one_still_prefers_cutting_differing_neighbors() {
+        // Cutting around the centre seed would cost four equal-neighbor links,
+        // against two differing ones around the blocked corner.
+        let grid = Grid::from_ascii("...\n...\n..#").unwrap();
+        let segmentation = Graph::min_cut_segmentation(&grid, &[Point::new(1, 1)], &[Point::new(2, 2)], 0.2);
+
+        let expected: Vec<Point> = grid.iter_points().filter(|&p| p != Point::new(2, 2)).collect();
+        assert_eq!(foreground(&grid, &segmentation), expected);
+    }
+
+    #[test]
+    fn unbounded_smoothness_does_not_overflow() {
+        let grid = Grid::from_ascii("..##\n..##").unwrap();
+        for smoothness in [f64::INFINITY, f64::MAX, 1e30] {
+            let segmentation = Graph::min_cut_segmentation(&grid, &[Point::new(0, 0)], &[Point::new(3, 1)], smoothness);
+            assert_eq!(segmentation, vec![true, true, false, false, true, true, false, false]);
+        }
+    }
+
+    /// The diamond network from the flow example: a cheap two-hop route
+    /// through `(1, 0)` and a longer three-hop one through `(1, 2)` and `(2, 2)`.
+    fn diamond() -> Graph {
+        let (source, sink) = (Point::new(0, 1), Point::new(3, 1));
+        let mut graph = Graph::new(source, sink);
+        graph.add_edge(source, Point::new(1, 0), 10, 1.0);
+        graph.add_edge(Point::new(1, 0), sink, 10, 1.0);
+        graph.add_edge(source, Point::new(1, 2), 7, 1.0);
+        graph.add_edge(Point::new(1, 2), Point::new(2, 2), 7, 1.0);
+        graph.add_edge(Point::new(2, 2), sink, 7, 1.0);
+        graph
+    }
+
+    #[test]
+    fn route_detailed_reports_the_flow_on_each_edge_of_the_path() {
+        let mut graph = diamond();
+        let route = graph.route_detailed().unwrap();
+
+        assert_eq!(route.path, vec![Point::new(0, 1), Point::new(1, 0), Point::new(3, 1)]);
+        assert_eq!(route.flow, 10);
+        assert_eq!(route.total_cost, 2.0);
+
+        // The listed edges walk the path, each carrying the bottleneck flow.
+        assert_eq!(route.edge_flows.len(), route.path.len() - 1);
+        for (&(from, to, flow), step) in route.edge_flows.iter().zip(route.path.windows(2)) {
+            assert_eq!((from, to), (step[0], step[1]));
+            assert_eq!(flow, route.flow);
+        }
+        let summed: u64 = route.edge_flows.iter().map(|&(_, _, flow)| flow).sum();
+        assert_eq!(summed, route.flow * route.edge_flows.len() as u64);
+    }
+
+    #[test]
+    fn route_detailed_takes_the_bottleneck_and_then_the_next_route() {
+        let mut graph = diamond();
+        graph.route_detailed().unwrap();
+
+        let second = graph.route_detailed().unwrap();
+        assert_eq!(second.path.len(), 4);
+        assert_eq!(second.flow, 7);
+        assert_eq!(second.total_cost, 3.0);
+        assert!(graph.route_detailed().is_none());
+    }
+
+    #[test]
+    fn a_graph_from_a_tiny_maze_carries_flow_from_entrance_to_exit() {
+        let grid = crate::maze::generate_maze_seeded(7, 7, 1);
+        let mut graph = Graph::from_grid(&grid, 3, 1.0);
+        assert_eq!((graph.source, graph.sink), (Point::new(0, 1), Point::new(6, 5)));
+
+        let passable = grid.iter().filter(|&(_, cell)| cell != Cell::Blocked).count();
+        assert_eq!(graph.adj.len(), passable);
+        // A perfect maze is a single corridor wide, so one edge's capacity gets through.
+        assert_eq!(graph.edmonds_karp(), 3);
+    }
+
+    #[test]
+    fn a_graph_from_an_open_grid_links_orthogonal_neighbors_both_ways() {
+        let grid = Grid::from_ascii("..\n.#").unwrap();
+        let graph = Graph::from_grid(&grid, 2, 0.5);
+        let targets = |p: Point| {
+            let mut to: Vec<Point> = graph.get_edges(&p).iter().map(|e| e.to).collect();
+            to.sort();
+            to
+        };
+        assert_eq!(targets(Point::new(0, 0)), vec![Point::new(0, 1), Point::new(1, 0)]);
+        assert_eq!(targets(Point::new(1, 0)), vec![Point::new(0, 0)]);
+        assert_eq!(targets(Point::new(0, 1)), vec![Point::new(0, 0)]);
+        assert!(!graph.adj.contains_key(&Point::new(1, 1)));
+    }
+
+    /// The classic network where the cheapest first path, through the middle
+    /// edge `(1, 0) -> (1, 2)`, blocks both routes that make up the maximum flow.
+    fn greedy_trap() -> Graph {
+        let (source, sink) = (Point::new(0, 1), Point::new(2, 1));
+        let (top, bottom) = (Point::new(1, 0), Point::new(1, 2));
+        let mut graph = Graph::new(source, sink);
+        graph.add_edge(source, top, 1, 1.0);
+        graph.add_edge(top, bottom, 1, 1.0);
+        graph.add_edge(bottom, sink, 1, 1.0);
+        graph.add_edge(source, bottom, 1, 5.0);
+        graph.add_edge(top, sink, 1, 5.0);
+        graph
+    }
+
+    /// The flow on the first edge from `from` to `to`.
+    fn flow(graph: &Graph, from: Point, to: Point) -> u64 {
+        graph.get_edges(&from).iter().find(|e| e.to == to).unwrap().flow
+    }
+
+    #[test]
+    fn max_flow_cancels_a_greedy_path_that_blocks_it() {
+        let mut graph = greedy_trap();
+        assert_eq!(graph.edmonds_karp(), 2);
+        assert_eq!(flow(&graph, Point::new(1, 0), Point::new(1, 2)), 0);
+        assert_eq!(flow(&graph, Point::new(0, 1), Point::new(1, 2)), 1);
+        assert_eq!(flow(&graph, Point::new(1, 0), Point::new(2, 1)), 1);
+    }
+
+    #[test]
+    fn routing_steps_backwards_along_an_edge_to_cancel_its_flow() {
+        let mut graph = greedy_trap();
+        let first = graph.route_detailed().unwrap();
+        assert_eq!(first.path, vec![Point::new(0, 1), Point::new(1, 0), Point::new(1, 2), Point::new(2, 1)]);
+
+        let second = graph.route_detailed().unwrap();
+        assert_eq!(second.path, vec![Point::new(0, 1), Point::new(1, 2), Point::new(1, 0), Point::new(2, 1)]);
+        assert_eq!(second.total_cost, 9.0);
+        assert_eq!(graph.total_cost(), 12.0);
+        assert!(graph.route_detailed().is_none());
+    }
+
+    #[test]
+    fn the_adjacency_list_holds_only_the_edges_that_were_added() {
+        let mut graph = greedy_trap();
+        graph.edmonds_karp();
+        let edges: usize = graph.adj.values().map(Vec::len).sum();
+        assert_eq!(edges, 5);
+        assert!(graph.get_edges(&Point::new(2, 1)).is_empty());
+    }
+
+    #[test]
+    fn edges_pushed_onto_the_adjacency_list_directly_carry_flow() {
+        let (source, sink) = (Point::new(0, 0), Point::new(1, 0));
+        let mut graph = Graph::new(source, sink);
+        graph.add_node(sink);
+        graph.adj.entry(source).or_default().push(Edge { to: sink, capacity: 4, cost: 1.0, flow: 0 });
+        assert_eq!(graph.edmonds_karp(), 4);
+
+        // Clearing the flow by hand frees the capacity again.
+        graph.adj.get_mut(&source).unwrap()[0].flow = 0;
+        assert_eq!(graph.edmonds_karp(), 4);
+    }
+
+    #[test]
+    fn parallel_edges_each_carry_their_own_flow() {
+        let (source, sink) = (Point::new(0, 0), Point::new(1, 0));
+        let mut graph = Graph::new(source, sink);
+        graph.add_edge(source, sink, 2, 3.0);
+        graph.add_edge(source, sink, 5, 1.0);
+
+        let first = graph.route_detailed().unwrap();
+        assert_eq!((first.flow, first.total_cost), (5, 1.0));
+        let flows: Vec<u64> = graph.get_edges(&source).iter().map(|e| e.flow).collect();
+        assert_eq!(flows, vec![0, 5]);
+
+        assert_eq!(graph.edmonds_karp(), 2);
+        assert_eq!(graph.total_cost(), 11.0);
+    }
+
+    #[test]
+    fn routing_reroutes_after_an_edge_is_removed() {
+        let mut graph = diamond();
+        assert!(graph.remove_edge(Point::new(0, 1), Point::new(1, 0)));
+        assert!(!graph.remove_edge(Point::new(0, 1), Point::new(1, 0)));
+        assert!(!graph.remove_edge(Point::new(9, 9), Point::new(1, 0)));
+
+        let (flow, path) = graph.route_cheapest_path();
+        assert_eq!(flow, 7);
+        assert_eq!(path.unwrap(), vec![Point::new(0, 1), Point::new(1, 2), Point::new(2, 2), Point::new(3, 1)]);
+    }
+
+    #[test]
+    fn removing_a_node_purges_the_edges_entering_it() {
+        let mut graph = diamond();
+        graph.remove_node(Point::new(2, 2));
+        assert!(!graph.adj.contains_key(&Point::new(2, 2)));
+        assert!(graph.get_edges(&Point::new(1, 2)).is_empty());
+        assert_eq!(graph.edmonds_karp(), 10);
+
+        // The sink can be removed too, after which nothing reaches it.
+        let mut graph = diamond();
+        graph.remove_node(graph.sink);
+        assert_eq!(graph.route_cheapest_path(), (0, None));
+        assert_eq!(graph.edmonds_karp(), 0);
+    }
+
+    /// Checks the endpoint index and incoming lists against a rebuild from `adj`.
+    fn assert_indexed(graph: &Graph) {
+        let mut edge_index = HashMap::new();
+        let mut incoming: IncomingEdges = HashMap::new();
+        for (&from, edges) in &graph.adj {
+            for (index, edge) in edges.iter().enumerate() {
+                edge_index.entry((from, edge.to)).or_insert(index);
+                incoming.entry(edge.to).or_default().push((from, index));
+            }
+        }
+        for entering in incoming.values_mut() {
+            entering.sort();
+        }
+        // Nodes whose entering edges were all removed may keep an empty list.
+        let maintained: IncomingEdges =
+            graph.incoming.iter().filter(|(_, entering)| !entering.is_empty()).map(|(&node, entering)| (node, entering.clone())).collect();
+
+        assert_eq!(graph.edge_index, edge_index);
+        assert_eq!(maintained, incoming);
+    }
+
+    #[test]
+    fn the_edge_index_follows_additions_and_removals() {
+        let grid = Grid::from_ascii("....\n.#..\n....").unwrap();
+        let mut graph = Graph::from_grid(&grid, 3, 1.0);
+        assert_indexed(&graph);
+
+        assert!(graph.remove_edge(Point::new(0, 0), Point::new(1, 0)));
+        assert_indexed(&graph);
+        graph.remove_node(Point::new(2, 1));
+        assert_indexed(&graph);
+        graph.add_edge(Point::new(3, 2), Point::new(0, 0), 1, 2.0);
+        graph.add_edge(Point::new(3, 2), Point::new(0, 0), 4, 1.0);
+        assert_indexed(&graph);
+        graph.remove_node(Point::new(3, 2));
+        assert_indexed(&graph);
+        assert!(graph.incoming.values().flatten().all(|&(from, _)| from != Point::new(3, 2)));
+    }
+
+    #[test]
+    fn removing_one_of_several_parallel_edges_keeps_the_rest() {
+        let (source, sink) = (Point::new(0, 0), Point::new(1, 0));
+        let mut graph = Graph::new(source, sink);
+        graph.add_edge(source, sink, 2, 3.0);
+        graph.add_edge(source, Point::new(0, 1), 1, 1.0);
+        graph.add_edge(source, sink, 5, 1.0);
+
+        assert!(graph.remove_edge(source, sink));
+        assert_indexed(&graph);
+        let capacities: Vec<u64> = graph.get_edges(&source).iter().map(|e| e.capacity).collect();
+        assert_eq!(capacities, vec![1, 5]);
+
+        assert!(graph.remove_edge(source, sink));
+        assert!(!graph.remove_edge(source, sink));
+        assert_eq!(graph.edmonds_karp(), 0);
+    }
+
+    #[test]
+    fn min_cost_max_flow_on_the_diamond() {
+        let mut graph = diamond();
+        // 10 units over the two-hop route at 2 each, 7 over the three-hop one at 3 each.
+        assert_eq!(graph.min_cost_max_flow(), (17, 41.0));
+        assert_eq!(graph.total_cost(), 41.0);
+        assert_eq!(graph.min_cost_max_flow(), (0, 0.0));
+    }
+
+    #[test]
+    fn min_cost_max_flow_cancels_flow_to_reach_the_maximum() {
+        let mut graph = greedy_trap();
+        assert_eq!(graph.min_cost_max_flow(), (2, 12.0));
+        assert_eq!(flow(&graph, Point::new(1, 0), Point::new(1, 2)), 0);
+    }
+
+    #[test]
+    fn bellman_ford_reports_a_negative_cycle() {
+        let (source, sink) = (Point::new(0, 0), Point::new(3, 0));
+        let mut graph = Graph::new(source, sink);
+        graph.add_edge(source, Point::new(1, 0), 1, 1.0);
+        graph.add_edge(Point::new(1, 0), Point::new(2, 0), 1, -3.0);
+        graph.add_edge(Point::new(2, 0), Point::new(1, 0), 1, 1.0);
+        graph.add_edge(Point::new(2, 0), sink, 1, 1.0);
+        assert_eq!(graph.find_cheapest_path_bellman_ford(), Err(GraphError::NegativeCycle));
+
+        graph.remove_edge(Point::new(2, 0), Point::new(1, 0));
+        let (parents, found) = graph.find_cheapest_path_bellman_ford().unwrap();
+        assert!(found);
+        assert_eq!(parents[&sink], Point::new(2, 0));
+    }
+
+    #[test]
+    fn dot_output_is_sorted_and_labels_flow_capacity_and_cost() {
+        let (source, sink) = (Point::new(0, 0), Point::new(2, 0));
+        let mut graph = Graph::new(source, sink);
+        graph.add_edge(Point::new(1, 0), sink, 2, 1.0);
+        graph.add_edge(source, Point::new(1, 0), 3, 1.5);
+        graph.route_cheapest_path();
+
+        let expected = "digraph flow {
+    \"(0, 0)\" [style=filled, fillcolor=palegreen];
+    \"(1, 0)\";
+    \"(2, 0)\" [style=filled, fillcolor=lightcoral];
+    \"(0, 0)\" -> \"(1, 0)\" [label=\"2/3, cost 1.5\"];
+    \"(1, 0)\" -> \"(2, 0)\" [label=\"2/2, cost 1\"];
+}
+";
+        assert_eq!(graph.to_dot(), expected);
+    }
+
+    #[test]
+    fn dot_quoting_escapes_quotes_and_backslashes() {
+        assert_eq!(dot_quote(r#"a "b" \c"#), r#""a \"b\" \\c""#);
+        assert_eq!(dot_id(Point::new(3, 4)), "\"(3, 4)\"");
+    }
+
+    /// Every edge as `(from, to, flow)`, sorted so graphs can be compared.
+    fn flows(graph: &Graph) -> Vec<(Point, Point, u64)> {
+        let mut flows: Vec<_> = graph
+            .adj
+            .iter()
+            .flat_map(|(&from, edges)| edges.iter().map(move |e| (from, e.to, e.flow)))
+            .collect();
+        flows.sort();
+        flows
+    }
+
+    #[test]
+    fn shortest_path_cost_finds_the_cheapest_route_without_pushing_flow() {
+        let graph = diamond();
+        let before = flows(&graph);
+        let (path, cost) = graph.shortest_path_cost(graph.source, graph.sink).unwrap();
+        assert_eq!(path, vec![Point::new(0, 1), Point::new(1, 0), Point::new(3, 1)]);
+        assert_eq!(cost, 2.0);
+        assert_eq!(flows(&graph), before);
+    }
+
+    #[test]
+    fn shortest_path_cost_matches_the_next_route() {
+        let mut graph = diamond();
+        graph.route_detailed().unwrap();
+        let (path, cost) = graph.shortest_path_cost(graph.source, graph.sink).unwrap();
+        let route = graph.route_detailed().unwrap();
+        assert_eq!((path, cost), (route.path, route.total_cost));
+        assert_eq!(cost, 3.0);
+
+        // Through the residual graph, a route may cancel flow at a negative cost.
+        let mut trap = greedy_trap();
+        trap.route_detailed().unwrap();
+        assert_eq!(trap.shortest_path_cost(trap.source, trap.sink).unwrap().1, 9.0);
+    }
+
+    #[test]
+    fn shortest_path_cost_is_none_once_the_sink_is_cut_off() {
+        let mut graph = diamond();
+        while graph.route_detailed().is_some() {}
+        assert_eq!(graph.shortest_path_cost(graph.source, graph.sink), None);
+        assert_eq!(graph.shortest_path_cost(graph.source, Point::new(9, 9)), None);
+    }
+
+    #[test]
+    fn bellman_ford_finds_a_cheaper_route_through_a_negative_edge_that_dijkstra_misses() {
+        let (source, sink) = (Point::new(0, 1), Point::new(3, 1));
+        let (a, b) = (Point::new(1, 1), Point::new(2, 0));
+        let mut graph = Graph::new(source, sink);
+        graph.add_edge(source, a, 1, 2.0);
+        graph.add_edge(source, b, 1, 4.0);
+        graph.add_edge(b, a, 1, -3.0);
+        graph.add_edge(a, sink, 1, 1.0);
+
+        // Dijkstra settles `a` at cost 2 before the detour through `b` lowers it to 1.
+        assert_eq!(graph.shortest_path_cost(source, sink), Some((vec![source, a, sink], 3.0)));
+
+        let (parents, found) = graph.find_cheapest_path_bellman_ford().unwrap();
+        assert!(found);
+        assert_eq!(parents[&sink], a);
+        assert_eq!(parents[&a], b);
+        assert_eq!(parents[&b], source);
+    }
+
+    #[test]
+    fn total_cost_sums_flow_times_cost_over_every_edge() {
+        let mut graph = diamond();
+        assert_eq!(graph.total_cost(), 0.0);
+
+        graph.route_cheapest_path();
+        // Ten units over the two-hop route.
+        assert_eq!(graph.total_cost(), 20.0);
+
+        graph.route_cheapest_path();
+        // Plus seven units over the three-hop route.
+        assert_eq!(graph.total_cost(), 41.0);
+    }
+
+    #[test]
+    fn utilization_reports_each_edge_as_a_fraction_of_its_capacity() {
+        let mut graph = diamond();
+        graph.route_cheapest_path();
+        let report = graph.utilization();
+
+        assert_eq!(report.len(), 5);
+        assert!(report.windows(2).all(|pair| (pair[0].0, pair[0].1) <= (pair[1].0, pair[1].1)));
+        assert!(report.iter().all(|&(_, _, ratio)| (0.0..=1.0).contains(&ratio)));
+        for &(from, to, ratio) in &report {
+            let on_cheap_route = from == Point::new(1, 0) || to == Point::new(1, 0);
+            assert_eq!(ratio, if on_cheap_route { 1.0 } else { 0.0 }, "{:?} -> {:?}", from, to);
+        }
+
+        graph.route_cheapest_path();
+        assert!(graph.utilization().iter().all(|&(_, _, ratio)| ratio == 1.0));
+    }
+
+    #[test]
+    fn utilization_reports_an_edge_without_capacity_as_empty() {
+        let (source, sink) = (Point::new(0, 0), Point::new(1, 0));
+        let mut graph = Graph::new(source, sink);
+        graph.add_edge(source, sink, 0, 1.0);
+        assert_eq!(graph.utilization(), vec![(source, sink, 0.0)]);
+    }
+}
@@ -2,12 +2,20 @@
 //
 // Provides a generic implementation of the A* search algorithm.
 
-use crate::grid::{Grid, Point};
-use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashMap};
+use crate::automaton::{CellState, Moma2dAutomaton};
+use crate::grid::{Cell, Connectivity, Grid, Point};
+use moma::core::{MomaRing, OriginStrategy};
+use ordered_float::OrderedFloat;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::f64::consts::{PI, TAU};
+use std::time::Instant;
 
 type Cost = u32;
 
+/// How many node expansions `a_star_deadline` performs between clock checks.
+const DEADLINE_CHECK_INTERVAL: usize = 1024;
+
 /// Represents a node in the A* search space.
 #[derive(Debug, Eq, PartialEq)]
 pub struct Node {
@@ -19,10 +27,14 @@ pub struct Node {
 }
 
 // The priority queue (BinaryHeap) needs `Ord`. We want to pop the node with the
-// lowest total cost (cost + heuristic), so we reverse the comparison.
+// lowest total cost (cost + heuristic), so we reverse the comparison. Ties go to
+// the node with the lower heuristic, i.e. the one closer to the goal, which
+// avoids expanding every node on an equal-cost front.
 impl Ord for Node {
     fn cmp(&self, other: &Self) -> Ordering {
-        (other.cost + other.heuristic).cmp(&(self.cost + self.heuristic))
+        (other.cost + other.heuristic)
+            .cmp(&(self.cost + self.heuristic))
+            .then_with(|| other.heuristic.cmp(&self.heuristic))
     }
 }
 
@@ -32,11 +44,77 @@ impl PartialOrd for Node {
     }
 }
 
+/// The base cost of a single move, by direction, for `a_star_move_costs`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MoveCosts {
+    /// The cost of a horizontal or vertical step.
+    pub ortho: f64,
+    /// The cost of a diagonal step.
+    pub diag: f64,
+}
+
+impl Default for MoveCosts {
+    /// Geometric step lengths: 1 for an orthogonal step and √2 for a diagonal one.
+    fn default() -> Self {
+        Self { ortho: 1.0, diag: std::f64::consts::SQRT_2 }
+    }
+}
+
+impl MoveCosts {
+    /// Returns the cost of the cheapest unobstructed route between two
+    /// points with these move costs: the octile distance, generalized.
+    ///
+    /// A diagonal step costing more than two orthogonal ones is never worth
+    /// taking, so it counts as two orthogonal steps. This keeps the estimate
+    /// admissible for every connectivity, as long as terrain costs are at
+    /// least 1.
+    pub fn distance(&self, a: Point, b: Point) -> f64 {
+        let dx = a.x.abs_diff(b.x) as f64;
+        let dy = a.y.abs_diff(b.y) as f64;
+        let diag = self.diag.min(2.0 * self.ortho);
+        (dx.max(dy) - dx.min(dy)) * self.ortho + dx.min(dy) * diag
+    }
+}
+
+/// Measures of the effort an A* search took, as reported by `a_star_stats`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SearchStats {
+    /// The number of nodes taken off the frontier and expanded, including the goal.
+    pub nodes_expanded: usize,
+    /// The largest number of entries the frontier held at once.
+    pub max_frontier_size: usize,
+    /// The total cost of the path found.
+    pub path_cost: Cost,
+}
+
 /// The Manhattan distance heuristic for a grid.
 pub fn manhattan_distance(a: Point, b: Point) -> Cost {
     ((a.x as i32 - b.x as i32).abs() + (a.y as i32 - b.y as i32).abs()) as Cost
 }
 
+/// The Euclidean (straight-line) distance heuristic, rounded down so it never
+/// overestimates.
+pub fn euclidean_distance(a: Point, b: Point) -> Cost {
+    let dx = a.x as f64 - b.x as f64;
+    let dy = a.y as f64 - b.y as f64;
+    dx.hypot(dy).floor() as Cost
+}
+
+/// The Chebyshev distance heuristic, exact for grids where a diagonal step
+/// costs the same as an orthogonal one.
+pub fn chebyshev_distance(a: Point, b: Point) -> Cost {
+    a.x.abs_diff(b.x).max(a.y.abs_diff(b.y)) as Cost
+}
+
+/// The Manhattan distance on a torus of the given size, where each axis
+/// wraps around. Along each axis the shorter of the direct and wrapped
+/// distances is taken.
+pub fn toroidal_manhattan(a: Point, b: Point, width: usize, height: usize) -> Cost {
+    let dx = a.x.abs_diff(b.x);
+    let dy = a.y.abs_diff(b.y);
+    (dx.min(width.saturating_sub(dx)) + dy.min(height.saturating_sub(dy))) as Cost
+}
+
 /// Finds the shortest path from a start to a goal point in a grid using the A* algorithm.
 ///
 /// # Arguments
@@ -48,6 +126,674 @@ pub fn manhattan_distance(a: Point, b: Point) -> Cost {
 /// `Some(Vec<Point>)` containing the path from start to goal if one is found,
 /// otherwise `None`.
 pub fn a_star(grid: &Grid, start: Point, goal: Point) -> Option<Vec<Point>> {
+    a_star_with(grid, start, goal, manhattan_distance)
+}
+
+/// Finds the shortest path like `a_star`, but estimates the remaining cost with
+/// `heuristic` instead of the Manhattan distance.
+///
+/// The heuristic is called as `heuristic(point, goal)`. It must never
+/// overestimate the true remaining cost, or the path found may not be the
+/// shortest.
+pub fn a_star_with(grid: &Grid, start: Point, goal: Point, heuristic: impl Fn(Point, Point) -> Cost) -> Option<Vec<Point>> {
+    a_star_search(grid, start, goal, Connectivity::Orthogonal, &terrain_cost(grid), &heuristic, None).map(|(path, _)| path)
+}
+
+/// Finds the shortest path like `a_star`, moving between cells as allowed by
+/// `connectivity`.
+///
+/// When diagonal moves are allowed, the Chebyshev distance is used as the
+/// heuristic, since the Manhattan distance would overestimate.
+pub fn a_star_connected(grid: &Grid, start: Point, goal: Point, connectivity: Connectivity) -> Option<Vec<Point>> {
+    let heuristic = match connectivity {
+        Connectivity::Orthogonal => manhattan_distance,
+        Connectivity::Diagonal | Connectivity::DiagonalNoCornerCutting => chebyshev_distance,
+    };
+    a_star_search(grid, start, goal, connectivity, &terrain_cost(grid), &heuristic, None).map(|(path, _)| path)
+}
+
+/// Finds the shortest path like `a_star`, and also returns its total cost.
+///
+/// # Returns
+/// `Some((path, cost))` if a path is found, otherwise `None`. The cost is the
+/// accumulated cost of reaching `goal`, as tracked by the search.
+pub fn a_star_cost(grid: &Grid, start: Point, goal: Point) -> Option<(Vec<Point>, Cost)> {
+    a_star_search(grid, start, goal, Connectivity::Orthogonal, &terrain_cost(grid), &manhattan_distance, None)
+        .map(|(path, stats)| (path, stats.path_cost))
+}
+
+/// Finds the shortest path like `a_star`, and also reports how much work the
+/// search did, e.g. to compare heuristics or tie-breaking rules on the same
+/// maze.
+///
+/// # Returns
+/// `Some((path, stats))` if a path is found, otherwise `None`.
+pub fn a_star_stats(grid: &Grid, start: Point, goal: Point) -> Option<(Vec<Point>, SearchStats)> {
+    a_star_search(grid, start, goal, Connectivity::Orthogonal, &terrain_cost(grid), &manhattan_distance, None)
+}
+
+/// Finds the shortest path like `a_star`, but gives up once `deadline` passes.
+///
+/// The clock is only read every 1024 node expansions, so the search may
+/// overrun the deadline by that much work. This keeps the check cheap enough
+/// for per-frame use in a game loop.
+///
+/// # Returns
+/// `Some(Vec<Point>)` containing the path if one is found in time, otherwise
+/// `None`, whether the goal is unreachable or the deadline expired.
+pub fn a_star_deadline(grid: &Grid, start: Point, goal: Point, deadline: Instant) -> Option<Vec<Point>> {
+    a_star_search(grid, start, goal, Connectivity::Orthogonal, &terrain_cost(grid), &manhattan_distance, Some(deadline)).map(|(path, _)| path)
+}
+
+/// Finds the lowest-cost path like `a_star`, with both the move cost and the
+/// heuristic supplied by the caller.
+///
+/// `cost_fn(from, to)` gives the cost of moving between two adjacent points,
+/// for example one derived from a MOMA ring. `heuristic_fn(point, goal)`
+/// estimates the remaining cost and must never overestimate it.
+pub fn a_star_generic(
+    grid: &Grid,
+    start: Point,
+    goal: Point,
+    cost_fn: impl Fn(Point, Point) -> Cost,
+    heuristic_fn: impl Fn(Point, Point) -> Cost,
+) -> Option<Vec<Point>> {
+    a_star_search(grid, start, goal, Connectivity::Orthogonal, &cost_fn, &heuristic_fn, None).map(|(path, _)| path)
+}
+
+/// Finds the lowest-cost path with float-valued costs, allowing the moves
+/// given by `connectivity` and guided by a float heuristic such as those in
+/// the `heuristics` module.
+///
+/// Each step costs the terrain cost of the cell it enters, as in `a_star`.
+/// `heuristic(point, goal)` must never overestimate the remaining cost for
+/// the path to be optimal; see each heuristic's notes on admissibility.
+///
+/// # Returns
+/// The path and its total cost, or `None` if the goal is unreachable.
+pub fn a_star_float(
+    grid: &Grid,
+    start: Point,
+    goal: Point,
+    connectivity: Connectivity,
+    heuristic: impl Fn(Point, Point) -> f64,
+) -> Option<(Vec<Point>, f64)> {
+    a_star_float_search(grid, start, goal, connectivity, &|_, to| grid.cost_at(to) as f64, &heuristic)
+}
+
+/// Finds the lowest-cost path with distinct costs for orthogonal and
+/// diagonal steps, so that with diagonal movement enabled the path is
+/// geometrically shortest rather than counting every step as 1.
+///
+/// Each step costs its base cost from `costs` times the terrain cost of the
+/// cell it enters. The search is guided by `MoveCosts::distance`.
+///
+/// # Returns
+/// The path and its total cost, or `None` if the goal is unreachable.
+pub fn a_star_move_costs(
+    grid: &Grid,
+    start: Point,
+    goal: Point,
+    connectivity: Connectivity,
+    costs: MoveCosts,
+) -> Option<(Vec<Point>, f64)> {
+    let step_cost = |from: Point, to: Point| {
+        let base = if from.x != to.x && from.y != to.y { costs.diag } else { costs.ortho };
+        base * grid.cost_at(to) as f64
+    };
+    a_star_float_search(grid, start, goal, connectivity, &step_cost, &|point, goal| costs.distance(point, goal))
+}
+
+/// Finds the shortest path like `a_star`, but searches from both ends at once
+/// and stops once the two frontiers meet on an optimal route.
+///
+/// On large mazes this typically explores around half as many cells as
+/// `a_star`. The path has the same cost as the one `a_star` finds, though it
+/// may take a different route between equally short options.
+pub fn a_star_bidirectional(grid: &Grid, start: Point, goal: Point) -> Option<Vec<Point>> {
+    if start == goal {
+        return Some(vec![start]);
+    }
+
+    let mut forward = Frontier::new(start, goal);
+    let mut backward = Frontier::new(goal, start);
+    // The cheapest complete route found so far, and the point where the two searches met on it.
+    let mut best: Option<(Cost, Point)> = None;
+
+    while let (Some(forward_min), Some(backward_min)) = (forward.min_priority(), backward.min_priority()) {
+        // With a consistent heuristic, neither search can improve on a route
+        // that costs no more than its cheapest unexpanded estimate.
+        if best.is_some_and(|(cost, _)| forward_min >= cost || backward_min >= cost) {
+            break;
+        }
+
+        // Grow the smaller frontier. Moving into a cell costs that cell's terrain
+        // cost, so the backward search pays for the cell it is leaving.
+        if forward.queue.len() <= backward.queue.len() {
+            forward.expand(grid, &backward, &mut best, |_, to| grid.cost_at(to));
+        } else {
+            backward.expand(grid, &forward, &mut best, |from, _| grid.cost_at(from));
+        }
+    }
+
+    let (_, meeting_point) = best?;
+    let mut path = vec![meeting_point];
+    let mut curr = meeting_point;
+    while curr != start {
+        curr = forward.came_from[&curr];
+        path.push(curr);
+    }
+    path.reverse();
+
+    let mut curr = meeting_point;
+    while curr != goal {
+        curr = backward.came_from[&curr];
+        path.push(curr);
+    }
+    Some(path)
+}
+
+/// One direction of a bidirectional A* search.
+struct Frontier {
+    target: Point,
+    queue: BinaryHeap<Node>,
+    came_from: HashMap<Point, Point>,
+    cost_so_far: HashMap<Point, Cost>,
+}
+
+impl Frontier {
+    fn new(source: Point, target: Point) -> Self {
+        let mut queue = BinaryHeap::new();
+        queue.push(Node {
+            point: source,
+            cost: 0,
+            heuristic: manhattan_distance(source, target),
+        });
+
+        Self {
+            target,
+            queue,
+            came_from: HashMap::new(),
+            cost_so_far: HashMap::from([(source, 0)]),
+        }
+    }
+
+    /// The lowest estimated total cost among the queued nodes.
+    fn min_priority(&self) -> Option<Cost> {
+        self.queue.peek().map(|node| node.cost + node.heuristic)
+    }
+
+    /// Expands the most promising node, recording in `best` any cheaper route
+    /// that joins up with the `other` search.
+    fn expand(&mut self, grid: &Grid, other: &Frontier, best: &mut Option<(Cost, Point)>, step_cost: impl Fn(Point, Point) -> Cost) {
+        let Some(current) = self.queue.pop() else {
+            return;
+        };
+        if current.cost > self.cost_so_far[&current.point] {
+            return; // A cheaper route to this point has already been expanded.
+        }
+
+        for next_point in grid.neighbors(current.point) {
+            let new_cost = current.cost + step_cost(current.point, next_point);
+
+            if !self.cost_so_far.contains_key(&next_point) || new_cost < self.cost_so_far[&next_point] {
+                self.cost_so_far.insert(next_point, new_cost);
+                self.queue.push(Node {
+                    point: next_point,
+                    cost: new_cost,
+                    heuristic: manhattan_distance(next_point, self.target),
+                });
+                self.came_from.insert(next_point, current.point);
+
+                if let Some(&other_cost) = other.cost_so_far.get(&next_point) {
+                    let total = new_cost + other_cost;
+                    if best.is_none_or(|(cost, _)| total < cost) {
+                        *best = Some((total, next_point));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Computes the minimum cost of reaching every reachable cell from `start`
+/// using Dijkstra's algorithm.
+///
+/// The resulting distance field can drive flow-field pathfinding, where many
+/// agents head to one goal by stepping to their cheapest neighbor.
+pub fn dijkstra(grid: &Grid, start: Point) -> HashMap<Point, Cost> {
+    let mut frontier = BinaryHeap::new();
+    let mut cost_so_far: HashMap<Point, Cost> = HashMap::new();
+
+    cost_so_far.insert(start, 0);
+    frontier.push(Node {
+        point: start,
+        cost: 0,
+        heuristic: 0,
+    });
+
+    while let Some(current) = frontier.pop() {
+        if current.cost > cost_so_far[&current.point] {
+            continue; // A cheaper route to this point has already been expanded.
+        }
+
+        for next_point in grid.neighbors(current.point) {
+            let new_cost = current.cost + grid.cost_at(next_point);
+
+            if !cost_so_far.contains_key(&next_point) || new_cost < cost_so_far[&next_point] {
+                cost_so_far.insert(next_point, new_cost);
+                frontier.push(Node {
+                    point: next_point,
+                    cost: new_cost,
+                    heuristic: 0,
+                });
+            }
+        }
+    }
+
+    cost_so_far
+}
+
+/// Finds the shortest path from `start` to `goal` using Dijkstra's algorithm,
+/// i.e. A* with a heuristic of zero.
+pub fn dijkstra_path(grid: &Grid, start: Point, goal: Point) -> Option<Vec<Point>> {
+    a_star_search(grid, start, goal, Connectivity::Orthogonal, &terrain_cost(grid), &|_, _| 0, None).map(|(path, _)| path)
+}
+
+/// Computes a flow field towards `goal`: for every cell that can reach the
+/// goal, the neighbor to step to next on a cheapest route.
+///
+/// The field is built with a single Dijkstra search outward from the goal,
+/// so any number of agents can then head for the goal by looking up their
+/// cell with `follow_flow_field`, instead of each running its own search.
+/// The goal itself has no entry.
+pub fn flow_field(grid: &Grid, goal: Point) -> HashMap<Point, Point> {
+    let mut frontier = BinaryHeap::new();
+    let mut cost_to_goal: HashMap<Point, Cost> = HashMap::new();
+    let mut next_step: HashMap<Point, Point> = HashMap::new();
+
+    cost_to_goal.insert(goal, 0);
+    frontier.push(Node {
+        point: goal,
+        cost: 0,
+        heuristic: 0,
+    });
+
+    while let Some(current) = frontier.pop() {
+        if current.cost > cost_to_goal[&current.point] {
+            continue; // A cheaper route to this point has already been expanded.
+        }
+
+        // Stepping from a neighbor into `current` costs `current`'s terrain cost.
+        let step_cost = grid.cost_at(current.point);
+        for previous in grid.neighbors(current.point) {
+            let new_cost = current.cost + step_cost;
+
+            if cost_to_goal.get(&previous).is_none_or(|&c| new_cost < c) {
+                cost_to_goal.insert(previous, new_cost);
+                next_step.insert(previous, current.point);
+                frontier.push(Node {
+                    point: previous,
+                    cost: new_cost,
+                    heuristic: 0,
+                });
+            }
+        }
+    }
+
+    next_step
+}
+
+/// Follows a flow field from `start` until it reaches `goal`.
+///
+/// # Returns
+/// The path from `start` to `goal` inclusive, or `None` if `start` is not in
+/// the field, i.e. it cannot reach the goal.
+pub fn follow_flow_field(field: &HashMap<Point, Point>, start: Point, goal: Point) -> Option<Vec<Point>> {
+    let mut path = vec![start];
+    let mut current = start;
+    while current != goal {
+        current = *field.get(&current)?;
+        path.push(current);
+        if path.len() > field.len() + 1 {
+            return None; // The field was built for another goal and loops.
+        }
+    }
+    Some(path)
+}
+
+/// Returns `true` if the straight line from `a` to `b`, traced with
+/// Bresenham's algorithm, passes only through cells that are not blocked.
+pub fn line_of_sight(grid: &Grid, a: Point, b: Point) -> bool {
+    let (mut x, mut y) = (a.x as isize, a.y as isize);
+    let (x1, y1) = (b.x as isize, b.y as isize);
+    let dx = (x1 - x).abs();
+    let dy = -(y1 - y).abs();
+    let sx = if x < x1 { 1 } else { -1 };
+    let sy = if y < y1 { 1 } else { -1 };
+    let mut error = dx + dy;
+
+    loop {
+        if grid[Point::new(x as usize, y as usize)] == Cell::Blocked {
+            return false;
+        }
+        if x == x1 && y == y1 {
+            return true;
+        }
+
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            x += sx;
+        }
+        if doubled <= dx {
+            error += dx;
+            y += sy;
+        }
+    }
+}
+
+/// Shortens a grid path by "string-pulling": every waypoint that can be skipped
+/// because the next one is in direct line of sight is removed.
+///
+/// The result is a list of corner points; consecutive points are no longer
+/// adjacent, but the straight lines between them avoid blocked cells.
+pub fn smooth_path(grid: &Grid, path: &[Point]) -> Vec<Point> {
+    let (Some(&first), Some(&last)) = (path.first(), path.last()) else {
+        return Vec::new();
+    };
+
+    let mut smoothed = vec![first];
+    let mut anchor = first;
+    for pair in path.windows(2) {
+        if !line_of_sight(grid, anchor, pair[1]) {
+            anchor = pair[0];
+            smoothed.push(anchor);
+        }
+    }
+    if anchor != last {
+        smoothed.push(last);
+    }
+    smoothed
+}
+
+/// Counts the direction changes along a path.
+///
+/// A cheaper measure of how winding a path is than the Gowers norms: a
+/// straight line has no turns, while a staircase turns at every step.
+/// Repeated points are skipped.
+pub fn turn_count(path: &[Point]) -> usize {
+    heading_changes(path).filter(|change| change.abs() > 1e-9).count()
+}
+
+/// Sums the absolute change of heading, in radians, over every turn of a path.
+///
+/// A right-angle turn adds π/2 and a reversal adds π, so unlike `turn_count`
+/// this also tells gentle any-angle bends from sharp ones.
+pub fn total_curvature(path: &[Point]) -> f64 {
+    heading_changes(path).map(f64::abs).sum()
+}
+
+/// Yields the signed change of heading, in `(-π, π]`, between each pair of
+/// consecutive steps of a path.
+fn heading_changes(path: &[Point]) -> impl Iterator<Item = f64> + '_ {
+    let headings: Vec<f64> = path
+        .windows(2)
+        .filter(|pair| pair[0] != pair[1])
+        .map(|pair| (pair[1].y as f64 - pair[0].y as f64).atan2(pair[1].x as f64 - pair[0].x as f64))
+        .collect();
+    (1..headings.len()).map(move |i| {
+        let change = (headings[i] - headings[i - 1]).rem_euclid(TAU);
+        if change > PI { change - TAU } else { change }
+    })
+}
+
+/// Finds an any-angle path from `start` to `goal` using Theta*.
+///
+/// Like A* over 8-connected moves, except that each reached cell is linked
+/// straight to its parent's parent whenever there is line of sight, so the
+/// path is not restricted to grid headings. Costs are Euclidean lengths, and
+/// terrain costs are ignored.
+///
+/// # Returns
+/// `Some(Vec<Point>)` containing the path's waypoints from start to goal if
+/// one is found, otherwise `None`. Consecutive waypoints are joined by
+/// unobstructed straight lines.
+pub fn theta_star(grid: &Grid, start: Point, goal: Point) -> Option<Vec<Point>> {
+    let distance = |a: Point, b: Point| {
+        let dx = a.x as f64 - b.x as f64;
+        let dy = a.y as f64 - b.y as f64;
+        dx.hypot(dy)
+    };
+
+    // Ordered by estimated total length, then by length so far.
+    let mut frontier = BinaryHeap::new();
+    let mut parent: HashMap<Point, Point> = HashMap::new();
+    let mut cost_so_far: HashMap<Point, f64> = HashMap::new();
+    let mut closed: HashSet<Point> = HashSet::new();
+
+    parent.insert(start, start);
+    cost_so_far.insert(start, 0.0);
+    frontier.push(Reverse((OrderedFloat(distance(start, goal)), OrderedFloat(0.0), start)));
+
+    while let Some(Reverse((_, OrderedFloat(cost), current))) = frontier.pop() {
+        if cost > cost_so_far[&current] || !closed.insert(current) {
+            continue; // Already expanded via a shorter route.
+        }
+
+        if current == goal {
+            let mut path = vec![goal];
+            let mut curr = goal;
+            while curr != start {
+                curr = parent[&curr];
+                path.push(curr);
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        for next_point in grid.neighbors_with_connectivity(current, Connectivity::DiagonalNoCornerCutting) {
+            if closed.contains(&next_point) {
+                continue;
+            }
+
+            // Skip the current cell entirely if its parent can see the neighbor.
+            let grandparent = parent[&current];
+            let (via, new_cost) = if line_of_sight(grid, grandparent, next_point) {
+                (grandparent, cost_so_far[&grandparent] + distance(grandparent, next_point))
+            } else {
+                (current, cost + distance(current, next_point))
+            };
+
+            if cost_so_far.get(&next_point).is_none_or(|&c| new_cost < c) {
+                cost_so_far.insert(next_point, new_cost);
+                parent.insert(next_point, via);
+                let priority = new_cost + distance(next_point, goal);
+                frontier.push(Reverse((OrderedFloat(priority), OrderedFloat(new_cost), next_point)));
+            }
+        }
+    }
+
+    None // No path found
+}
+
+/// A* over the cells of a 2D automaton, where the cost of a move is given by a
+/// MOMA ring applied to the states of the two cells it connects.
+///
+/// Each orthogonal step costs `cost_ring.residue(from_state, to_state) + 1`.
+/// Continuing in the same direction as the previous step adds
+/// `structure_penalty_weight` on top, so larger weights reward paths that turn
+/// more often. The search state includes the direction of arrival, which keeps
+/// the penalty exact rather than depending on the first route found to a cell.
+///
+/// # Returns
+/// The path from `start` to `goal` inclusive, or `None` if either lies outside
+/// the automaton.
+pub fn a_star_moma<S: OriginStrategy + Clone, C: CellState, T: OriginStrategy>(
+    automaton: &Moma2dAutomaton<S, C>,
+    cost_ring: &MomaRing<T>,
+    start: Point,
+    goal: Point,
+    structure_penalty_weight: f64,
+) -> Option<Vec<Point>> {
+    a_star_moma_search(automaton, cost_ring, start, goal, structure_penalty_weight, false)
+}
+
+/// Like `a_star_moma`, but the search wraps around the automaton's edges the
+/// same way its toroidal neighborhood does, and is guided by
+/// `toroidal_manhattan`. Paths may step off one edge and reappear on the
+/// opposite one.
+pub fn a_star_moma_toroidal<S: OriginStrategy + Clone, C: CellState, T: OriginStrategy>(
+    automaton: &Moma2dAutomaton<S, C>,
+    cost_ring: &MomaRing<T>,
+    start: Point,
+    goal: Point,
+    structure_penalty_weight: f64,
+) -> Option<Vec<Point>> {
+    a_star_moma_search(automaton, cost_ring, start, goal, structure_penalty_weight, true)
+}
+
+/// The MOMA cost-aware A* shared by `a_star_moma` and `a_star_moma_toroidal`.
+fn a_star_moma_search<S: OriginStrategy + Clone, C: CellState, T: OriginStrategy>(
+    automaton: &Moma2dAutomaton<S, C>,
+    cost_ring: &MomaRing<T>,
+    start: Point,
+    goal: Point,
+    structure_penalty_weight: f64,
+    wrap: bool,
+) -> Option<Vec<Point>> {
+    // `None` is the direction of the start state, which has not moved yet.
+    type State = (Point, Option<(isize, isize)>);
+
+    let (width, height) = (automaton.width, automaton.height);
+    if start.x >= width || start.y >= height || goal.x >= width || goal.y >= height {
+        return None;
+    }
+    let residue_at = |p: Point| automaton.state[p.y * width + p.x].to_residue();
+    let heuristic = |p: Point| {
+        if wrap {
+            toroidal_manhattan(p, goal, width, height) as f64
+        } else {
+            manhattan_distance(p, goal) as f64
+        }
+    };
+
+    let mut frontier = BinaryHeap::new();
+    let mut came_from: HashMap<State, State> = HashMap::new();
+    let mut cost_so_far: HashMap<State, f64> = HashMap::new();
+
+    let start_state: State = (start, None);
+    cost_so_far.insert(start_state, 0.0);
+    frontier.push(Reverse((OrderedFloat(heuristic(start)), OrderedFloat(0.0), start_state)));
+
+    while let Some(Reverse((_, OrderedFloat(cost), current))) = frontier.pop() {
+        if cost > cost_so_far[&current] {
+            continue; // A cheaper route to this state has already been expanded.
+        }
+
+        let (point, direction) = current;
+        if point == goal {
+            let mut path = vec![goal];
+            let mut state = current;
+            while let Some(&prev) = came_from.get(&state) {
+                path.push(prev.0);
+                state = prev;
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        for step in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+            let mut nx = point.x as isize + step.0;
+            let mut ny = point.y as isize + step.1;
+            if wrap {
+                nx = nx.rem_euclid(width as isize);
+                ny = ny.rem_euclid(height as isize);
+            } else if nx < 0 || ny < 0 || nx >= width as isize || ny >= height as isize {
+                continue;
+            }
+            let next_point = Point { x: nx as usize, y: ny as usize };
+
+            let move_cost = (cost_ring.residue(residue_at(point), residue_at(next_point)) + 1) as f64;
+            let structure_penalty = if direction == Some(step) { structure_penalty_weight } else { 0.0 };
+            let new_cost = cost + move_cost + structure_penalty;
+            let next: State = (next_point, Some(step));
+
+            if cost_so_far.get(&next).is_none_or(|&c| new_cost < c) {
+                cost_so_far.insert(next, new_cost);
+                came_from.insert(next, current);
+                frontier.push(Reverse((OrderedFloat(new_cost + heuristic(next_point)), OrderedFloat(new_cost), next)));
+            }
+        }
+    }
+
+    None // No path found
+}
+
+/// The A* search behind `a_star_float`, with float move costs and heuristic.
+fn a_star_float_search(
+    grid: &Grid,
+    start: Point,
+    goal: Point,
+    connectivity: Connectivity,
+    cost: &impl Fn(Point, Point) -> f64,
+    heuristic: &impl Fn(Point, Point) -> f64,
+) -> Option<(Vec<Point>, f64)> {
+    // Ordered by estimated total cost, then by cost so far.
+    let mut frontier = BinaryHeap::new();
+    let mut came_from: HashMap<Point, Point> = HashMap::new();
+    let mut cost_so_far: HashMap<Point, f64> = HashMap::new();
+
+    cost_so_far.insert(start, 0.0);
+    frontier.push(Reverse((OrderedFloat(heuristic(start, goal)), OrderedFloat(0.0), start)));
+
+    while let Some(Reverse((_, OrderedFloat(current_cost), current))) = frontier.pop() {
+        if current_cost > cost_so_far[&current] {
+            continue; // A cheaper route to this point has already been expanded.
+        }
+
+        if current == goal {
+            let mut path = vec![goal];
+            let mut curr = goal;
+            while curr != start {
+                curr = came_from[&curr];
+                path.push(curr);
+            }
+            path.reverse();
+            return Some((path, current_cost));
+        }
+
+        for next in grid.neighbors_with_connectivity(current, connectivity) {
+            let new_cost = current_cost + cost(current, next);
+            if cost_so_far.get(&next).is_none_or(|&c| new_cost < c) {
+                cost_so_far.insert(next, new_cost);
+                came_from.insert(next, current);
+                let priority = new_cost + heuristic(next, goal);
+                frontier.push(Reverse((OrderedFloat(priority), OrderedFloat(new_cost), next)));
+            }
+        }
+    }
+
+    None // No path found
+}
+
+/// The default move cost: the terrain cost of the destination cell.
+fn terrain_cost(grid: &Grid) -> impl Fn(Point, Point) -> Cost + '_ {
+    move |_, to| grid.cost_at(to)
+}
+
+/// The A* search shared by the public entry points. Returns the path along
+/// with the search's statistics, which include its total cost.
+fn a_star_search(
+    grid: &Grid,
+    start: Point,
+    goal: Point,
+    connectivity: Connectivity,
+    cost: &impl Fn(Point, Point) -> Cost,
+    heuristic: &impl Fn(Point, Point) -> Cost,
+    deadline: Option<Instant>,
+) -> Option<(Vec<Point>, SearchStats)> {
+    let mut stats = SearchStats::default();
     let mut frontier = BinaryHeap::new();
     let mut came_from: HashMap<Point, Point> = HashMap::new();
     let mut cost_so_far: HashMap<Point, Cost> = HashMap::new();
@@ -56,10 +802,21 @@ pub fn a_star(grid: &Grid, start: Point, goal: Point) -> Option<Vec<Point>> {
     frontier.push(Node {
         point: start,
         cost: 0,
-        heuristic: manhattan_distance(start, goal),
+        heuristic: heuristic(start, goal),
     });
 
+    stats.max_frontier_size = 1;
     while let Some(current) = frontier.pop() {
+        if current.cost > cost_so_far[&current.point] {
+            continue; // A cheaper route to this point has already been expanded.
+        }
+
+        let check_clock = stats.nodes_expanded % DEADLINE_CHECK_INTERVAL == 0;
+        if check_clock && deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return None; // Out of time
+        }
+        stats.nodes_expanded += 1;
+
         if current.point == goal {
             // We found the goal, reconstruct the path.
             let mut path = vec![goal];
@@ -69,20 +826,22 @@ pub fn a_star(grid: &Grid, start: Point, goal: Point) -> Option<Vec<Point>> {
                 path.push(curr);
             }
             path.reverse();
-            return Some(path);
+            stats.path_cost = cost_so_far[&goal];
+            return Some((path, stats));
         }
 
-        for next_point in grid.neighbors(current.point) {
-            let new_cost = cost_so_far[&current.point] + 1; // Cost of moving is always 1.
+        for next_point in grid.neighbors_with_connectivity(current.point, connectivity) {
+            let new_cost = cost_so_far[&current.point] + cost(current.point, next_point);
 
             if !cost_so_far.contains_key(&next_point) || new_cost < cost_so_far[&next_point] {
                 cost_so_far.insert(next_point, new_cost);
-                let priority = manhattan_distance(next_point, goal);
+                let priority = heuristic(next_point, goal);
                 frontier.push(Node {
                     point: next_point,
                     cost: new_cost,
                     heuristic: priority,
                 });
+                stats.max_frontier_size = stats.max_frontier_size.max(frontier.len());
                 came_from.insert(next_point, current.point);
             }
         }
@@ -90,3 +849,485 @@ pub fn a_star(grid: &Grid, start: Point, goal: Point) -> Option<Vec<Point>> {
 
     None // No path found
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::DEFAULT_TERRAIN_COST;
+    use std::time::Duration;
+
+    #[test]
+    fn a_star_deadline_gives_up_once_the_deadline_has_passed() {
+        let grid = Grid::new(50, 50, Cell::Free);
+        let started = Instant::now();
+        assert_eq!(a_star_deadline(&grid, Point::new(0, 0), Point::new(49, 49), started), None);
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn a_star_deadline_matches_a_star_with_time_to_spare() {
+        let grid = Grid::new(50, 50, Cell::Free);
+        let (start, goal) = (Point::new(0, 0), Point::new(49, 49));
+        let deadline = Instant::now() + Duration::from_secs(60);
+        let path = a_star_deadline(&grid, start, goal, deadline).unwrap();
+        assert_eq!(path.len(), 99);
+        assert_eq!(Some(path), a_star(&grid, start, goal));
+    }
+
+    #[test]
+    fn distance_heuristics_measure_the_offset_between_points() {
+        let (a, b) = (Point::new(0, 0), Point::new(3, 4));
+        assert_eq!(manhattan_distance(a, b), 7);
+        assert_eq!(euclidean_distance(a, b), 5);
+        assert_eq!(chebyshev_distance(a, b), 4);
+        // Rounded down, so the estimate never exceeds the true length.
+        assert_eq!(euclidean_distance(a, Point::new(1, 1)), 1);
+    }
+
+    #[test]
+    fn admissible_heuristics_all_find_a_shortest_path() {
+        let grid = Grid::new(10, 10, Cell::Free);
+        let (start, goal) = (Point::new(0, 0), Point::new(6, 8));
+        for heuristic in [manhattan_distance, euclidean_distance, chebyshev_distance] {
+            assert_eq!(a_star_with(&grid, start, goal, heuristic).unwrap().len(), 15);
+        }
+    }
+
+    #[test]
+    fn chebyshev_with_diagonal_moves_shortens_a_diagonal_route() {
+        let grid = Grid::new(10, 10, Cell::Free);
+        let (start, goal) = (Point::new(0, 0), Point::new(5, 5));
+        let diagonal = a_star_connected(&grid, start, goal, Connectivity::Diagonal).unwrap();
+        let orthogonal = a_star(&grid, start, goal).unwrap();
+        assert_eq!(diagonal.len(), 6);
+        assert_eq!(orthogonal.len(), 11);
+    }
+
+    #[test]
+    fn a_star_cost_matches_the_manhattan_distance_on_an_open_grid() {
+        let grid = Grid::new(10, 10, Cell::Free);
+        let (path, cost) = a_star_cost(&grid, Point::new(1, 2), Point::new(7, 5)).unwrap();
+        assert_eq!(cost, 9);
+        assert_eq!(path.len(), 10);
+        assert_eq!(a_star_cost(&grid, Point::new(4, 4), Point::new(4, 4)), Some((vec![Point::new(4, 4)], 0)));
+    }
+
+    #[test]
+    fn a_cheap_detour_beats_a_short_expensive_route() {
+        let mut grid = Grid::new(5, 3, Cell::Free);
+        for x in 1..4 {
+            grid.set_cost(Point::new(x, 1), 10);
+        }
+        let (path, cost) = a_star_cost(&grid, Point::new(0, 1), Point::new(4, 1)).unwrap();
+        assert_eq!(cost, 6);
+        assert_eq!(path.len(), 7);
+        assert!(path.iter().all(|&p| grid.cost_at(p) == DEFAULT_TERRAIN_COST));
+    }
+
+    #[test]
+    fn dijkstra_agrees_with_a_star_on_a_maze() {
+        let grid = crate::maze::generate_maze_seeded(21, 21, 7);
+        let (start, goal) = (Point::new(0, 1), Point::new(20, 19));
+        let a_star_path = a_star(&grid, start, goal).unwrap();
+        let dijkstra_path = dijkstra_path(&grid, start, goal).unwrap();
+        assert_eq!(dijkstra_path.len(), a_star_path.len());
+
+        let distances = dijkstra(&grid, start);
+        assert_eq!(distances[&goal] as usize, a_star_path.len() - 1);
+        assert_eq!(distances[&start], 0);
+        // Every open cell of a perfect maze is reachable, and no wall is.
+        let open = grid.iter().filter(|&(_, cell)| cell != Cell::Blocked).count();
+        assert_eq!(distances.len(), open);
+    }
+
+    #[test]
+    fn a_star_generic_steers_around_a_costly_direction() {
+        let grid = Grid::new(5, 5, Cell::Free);
+        // Moving in +x is expensive everywhere except along the bottom row,
+        // so the cheapest route goes down first and only then across.
+        let cost_fn = |from: Point, to: Point| if to.x > from.x && to.y < 4 { 20 } else { 1 };
+        let path = a_star_generic(&grid, Point::new(0, 0), Point::new(4, 4), cost_fn, |_, _| 0).unwrap();
+        assert_eq!(path.len(), 9);
+        assert!(path[..5].iter().all(|p| p.x == 0));
+        assert!(path[4..].iter().all(|p| p.y == 4));
+    }
+
+    #[test]
+    fn equal_cost_nodes_pop_closest_to_the_goal_first() {
+        let mut frontier = BinaryHeap::new();
+        frontier.push(Node { point: Point::new(0, 0), cost: 2, heuristic: 8 });
+        frontier.push(Node { point: Point::new(1, 0), cost: 8, heuristic: 2 });
+        frontier.push(Node { point: Point::new(2, 0), cost: 5, heuristic: 5 });
+        frontier.push(Node { point: Point::new(3, 0), cost: 1, heuristic: 1 });
+        let order: Vec<usize> = std::iter::from_fn(|| frontier.pop()).map(|node| node.point.x).collect();
+        assert_eq!(order, vec![3, 1, 2, 0]);
+    }
+
+    /// Runs the same search as `a_star_stats`, but orders the frontier on
+    /// f-cost alone, taking equal f-costs first in, first out, and returns
+    /// the number of nodes expanded.
+    fn expansions_ordered_on_f_only(grid: &Grid, start: Point, goal: Point) -> usize {
+        let mut frontier = BinaryHeap::from([Reverse((manhattan_distance(start, goal), 0, start))]);
+        let mut cost_so_far = HashMap::from([(start, 0)]);
+        let (mut pushed, mut expanded) = (0, 0);
+        while let Some(Reverse((f, _, point))) = frontier.pop() {
+            let cost = cost_so_far[&point];
+            if f > cost + manhattan_distance(point, goal) {
+                continue;
+            }
+            expanded += 1;
+            if point == goal {
+                return expanded;
+            }
+            for next in grid.neighbors(point) {
+                let new_cost = cost + grid.cost_at(next);
+                if cost_so_far.get(&next).is_none_or(|&old| new_cost < old) {
+                    cost_so_far.insert(next, new_cost);
+                    pushed += 1;
+                    frontier.push(Reverse((new_cost + manhattan_distance(next, goal), pushed, next)));
+                }
+            }
+        }
+        expanded
+    }
+
+    #[test]
+    fn tie_breaking_expands_only_the_path_on_an_open_grid() {
+        let grid = Grid::new(20, 20, Cell::Free);
+        let (start, goal) = (Point::new(0, 0), Point::new(19, 19));
+        let (path, stats) = a_star_stats(&grid, start, goal).unwrap();
+        assert_eq!(path.len(), 39);
+        // Every cell of the 20x20 rectangle lies on a shortest path with the
+        // same f-cost. Preferring the lower heuristic walks straight to the goal.
+        assert_eq!(stats.nodes_expanded, path.len());
+
+        // Ordering on f alone works through that whole equal-cost front.
+        let without_tie_break = expansions_ordered_on_f_only(&grid, start, goal);
+        assert!(without_tie_break > stats.nodes_expanded, "{} expansions", without_tie_break);
+        assert!(without_tie_break > 300, "{} expansions", without_tie_break);
+    }
+
+    /// The cost of walking `path` on `grid`, paying each cell's terrain cost on entry.
+    fn path_cost(grid: &Grid, path: &[Point]) -> Cost {
+        path.iter().skip(1).map(|&p| grid.cost_at(p)).sum()
+    }
+
+    #[test]
+    fn bidirectional_matches_a_star_on_several_mazes() {
+        let (start, goal) = (Point::new(0, 1), Point::new(30, 29));
+        for seed in 0..5 {
+            let grid = crate::maze::generate_maze_seeded(31, 31, seed);
+            let path = a_star_bidirectional(&grid, start, goal).unwrap();
+            assert_eq!(path.len(), a_star(&grid, start, goal).unwrap().len());
+            assert!(path.windows(2).all(|w| manhattan_distance(w[0], w[1]) == 1));
+        }
+    }
+
+    #[test]
+    fn bidirectional_matches_a_star_cost_on_uneven_terrain() {
+        use rand::{Rng, SeedableRng};
+        let (start, goal) = (Point::new(0, 0), Point::new(24, 24));
+        for seed in 0..5 {
+            let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(seed);
+            let mut grid = Grid::new(25, 25, Cell::Free);
+            for point in grid.iter_points().collect::<Vec<_>>() {
+                grid.set_cost(point, rng.random_range(1..=9));
+                if point != start && point != goal && rng.random_bool(0.2) {
+                    grid[point] = Cell::Blocked;
+                }
+            }
+            // The routes may differ between equally cheap options, but never the cost.
+            let bidirectional = a_star_bidirectional(&grid, start, goal).map(|path| path_cost(&grid, &path));
+            assert_eq!(bidirectional, a_star_cost(&grid, start, goal).map(|(_, cost)| cost));
+        }
+    }
+
+    #[test]
+    fn bidirectional_handles_trivial_and_unreachable_goals() {
+        let grid = Grid::from_ascii("..#..\n..#..").unwrap();
+        assert_eq!(a_star_bidirectional(&grid, Point::new(1, 1), Point::new(1, 1)), Some(vec![Point::new(1, 1)]));
+        assert_eq!(a_star_bidirectional(&grid, Point::new(0, 0), Point::new(4, 0)), None);
+    }
+
+    #[test]
+    fn line_of_sight_is_broken_by_a_blocked_cell_on_the_line() {
+        let grid = Grid::from_ascii(".....\n..#..\n.....").unwrap();
+        assert!(line_of_sight(&grid, Point::new(0, 0), Point::new(4, 0)));
+        assert!(line_of_sight(&grid, Point::new(4, 2), Point::new(0, 2)));
+        assert!(!line_of_sight(&grid, Point::new(0, 1), Point::new(4, 1)));
+        assert!(!line_of_sight(&grid, Point::new(0, 0), Point::new(4, 2)));
+    }
+
+    #[test]
+    fn smoothing_a_staircase_across_an_open_room_leaves_its_endpoints() {
+        let grid = Grid::new(10, 10, Cell::Free);
+        let (start, goal) = (Point::new(0, 0), Point::new(9, 6));
+        let path = a_star(&grid, start, goal).unwrap();
+        assert_eq!(smooth_path(&grid, &path), vec![start, goal]);
+        assert!(smooth_path(&grid, &[]).is_empty());
+        assert_eq!(smooth_path(&grid, &[start]), vec![start]);
+    }
+
+    #[test]
+    fn smoothing_keeps_a_corner_around_an_obstacle() {
+        let grid = Grid::from_ascii("....\n###.\n....").unwrap();
+        let path = a_star(&grid, Point::new(0, 0), Point::new(0, 2)).unwrap();
+        let smoothed = smooth_path(&grid, &path);
+        assert_eq!(smoothed.first(), Some(&Point::new(0, 0)));
+        assert_eq!(smoothed.last(), Some(&Point::new(0, 2)));
+        assert!(smoothed.len() > 2);
+        assert!(smoothed.windows(2).all(|w| line_of_sight(&grid, w[0], w[1])));
+    }
+
+    /// The Euclidean length of a path through its waypoints.
+    fn euclidean_length(path: &[Point]) -> f64 {
+        path.windows(2)
+            .map(|w| (w[1].x as f64 - w[0].x as f64).hypot(w[1].y as f64 - w[0].y as f64))
+            .sum()
+    }
+
+    #[test]
+    fn theta_star_is_never_longer_than_a_star_and_shorter_on_a_diagonal() {
+        let grid = Grid::new(12, 12, Cell::Free);
+        let start = Point::new(0, 0);
+        for goal in [Point::new(11, 0), Point::new(0, 7), Point::new(11, 5), Point::new(6, 11)] {
+            let theta = theta_star(&grid, start, goal).unwrap();
+            let grid_path = a_star(&grid, start, goal).unwrap();
+            assert!(euclidean_length(&theta) <= euclidean_length(&grid_path) + 1e-12);
+        }
+
+        let goal = Point::new(11, 7);
+        let theta = theta_star(&grid, start, goal).unwrap();
+        assert_eq!(theta, vec![start, goal]);
+        assert!(euclidean_length(&theta) < euclidean_length(&a_star(&grid, start, goal).unwrap()));
+    }
+
+    #[test]
+    fn theta_star_waypoints_see_each_other_around_obstacles() {
+        let grid = Grid::from_ascii("........\n.######.\n........\n........").unwrap();
+        let (start, goal) = (Point::new(3, 0), Point::new(4, 3));
+        let path = theta_star(&grid, start, goal).unwrap();
+        assert_eq!((path[0], path[path.len() - 1]), (start, goal));
+        assert!(path.windows(2).all(|w| line_of_sight(&grid, w[0], w[1])));
+        assert_eq!(theta_star(&Grid::from_ascii(".#.").unwrap(), Point::new(0, 0), Point::new(2, 0)), None);
+    }
+
+    #[test]
+    fn a_star_moma_finds_a_path_across_a_small_automaton() {
+        let automaton = Moma2dAutomaton::new_seeded(8, 8, 16, moma::strategy::PrimeGap, 3);
+        let cost_ring = MomaRing::new(16, moma::strategy::CompositeMass);
+        let (start, goal) = (Point::new(0, 0), Point::new(7, 5));
+        let path = a_star_moma(&automaton, &cost_ring, start, goal, 0.0).unwrap();
+        assert_eq!((path[0], path[path.len() - 1]), (start, goal));
+        assert!(path.windows(2).all(|w| manhattan_distance(w[0], w[1]) == 1));
+        assert_eq!(a_star_moma(&automaton, &cost_ring, start, Point::new(8, 0), 0.0), None);
+    }
+
+    #[test]
+    fn a_heavy_straight_line_penalty_makes_a_moma_path_turn() {
+        // On a uniform automaton every step costs the same, so only the
+        // penalty can make one route cheaper than another.
+        let automaton = Moma2dAutomaton::from_state(7, 3, 16, moma::strategy::PrimeGap, vec![0; 21]).unwrap();
+        let cost_ring = MomaRing::new(16, moma::strategy::CompositeMass);
+        let step_cost = (cost_ring.residue(0, 0) + 1) as f64;
+        let (start, goal) = (Point::new(0, 1), Point::new(6, 1));
+
+        let straight = a_star_moma(&automaton, &cost_ring, start, goal, 0.0).unwrap();
+        assert_eq!(straight.len(), 7);
+        assert_eq!(turn_count(&straight), 0);
+
+        let winding = a_star_moma(&automaton, &cost_ring, start, goal, 100.0 * step_cost).unwrap();
+        assert!(winding.len() > straight.len());
+        assert_eq!(turn_count(&winding), winding.len() - 2);
+    }
+
+    #[test]
+    fn toroidal_manhattan_takes_the_shorter_way_round_each_axis() {
+        assert_eq!(toroidal_manhattan(Point::new(0, 0), Point::new(9, 0), 10, 10), 1);
+        assert_eq!(toroidal_manhattan(Point::new(1, 2), Point::new(8, 7), 10, 10), 3 + 5);
+        let (a, b) = (Point::new(2, 1), Point::new(4, 3));
+        assert_eq!(toroidal_manhattan(a, b, 10, 10), manhattan_distance(a, b));
+        assert_eq!(toroidal_manhattan(Point::new(3, 3), Point::new(3, 3), 10, 10), 0);
+    }
+
+    #[test]
+    fn toroidal_moma_search_wraps_across_the_edge_when_it_is_shorter() {
+        let automaton = Moma2dAutomaton::from_state(10, 5, 16, moma::strategy::PrimeGap, vec![0; 50]).unwrap();
+        let cost_ring = MomaRing::new(16, moma::strategy::CompositeMass);
+        let (start, goal) = (Point::new(0, 2), Point::new(9, 1));
+
+        let bounded = a_star_moma(&automaton, &cost_ring, start, goal, 0.0).unwrap();
+        assert_eq!(bounded.len(), 11);
+
+        let wrapped = a_star_moma_toroidal(&automaton, &cost_ring, start, goal, 0.0).unwrap();
+        assert_eq!(wrapped.len(), 3);
+        assert_eq!((wrapped[0], wrapped[2]), (start, goal));
+        assert!(wrapped.windows(2).all(|w| toroidal_manhattan(w[0], w[1], 10, 5) == 1));
+    }
+
+    #[test]
+    fn toroidal_moma_search_matches_the_bounded_one_away_from_the_edges() {
+        let automaton = Moma2dAutomaton::from_state(12, 12, 16, moma::strategy::PrimeGap, vec![0; 144]).unwrap();
+        let cost_ring = MomaRing::new(16, moma::strategy::CompositeMass);
+        let (start, goal) = (Point::new(4, 4), Point::new(7, 6));
+        let bounded = a_star_moma(&automaton, &cost_ring, start, goal, 0.0).unwrap();
+        let wrapped = a_star_moma_toroidal(&automaton, &cost_ring, start, goal, 0.0).unwrap();
+        assert_eq!(wrapped.len(), bounded.len());
+    }
+
+    #[test]
+    fn following_the_flow_field_is_as_short_as_dijkstra_from_every_start() {
+        let grid = crate::maze::generate_maze_seeded(21, 21, 12);
+        let goal = Point::new(19, 19);
+        let field = flow_field(&grid, goal);
+
+        for start in grid.iter_points().filter(|&p| grid[p] != Cell::Blocked) {
+            let followed = follow_flow_field(&field, start, goal).unwrap();
+            let shortest = dijkstra_path(&grid, start, goal).unwrap();
+            assert_eq!(followed.len(), shortest.len(), "from {:?}", start);
+            assert!(followed.windows(2).all(|w| manhattan_distance(w[0], w[1]) == 1));
+        }
+    }
+
+    #[test]
+    fn flow_field_routes_around_costly_terrain() {
+        let mut grid = Grid::new(7, 5, Cell::Free);
+        for y in 0..4 {
+            grid.set_cost(Point::new(3, y), 10);
+        }
+        let (start, goal) = (Point::new(0, 1), Point::new(6, 1));
+        let followed = follow_flow_field(&flow_field(&grid, goal), start, goal).unwrap();
+        let shortest = dijkstra_path(&grid, start, goal).unwrap();
+        assert_eq!(path_cost(&grid, &followed), path_cost(&grid, &shortest));
+        assert!(followed.contains(&Point::new(3, 4)));
+    }
+
+    #[test]
+    fn flow_field_leaves_out_the_goal_and_unreachable_cells() {
+        let grid = Grid::from_ascii("..#.\n..#.\n..#.").unwrap();
+        let goal = Point::new(0, 0);
+        let field = flow_field(&grid, goal);
+        assert!(!field.contains_key(&goal));
+        assert_eq!(field.len(), 5);
+        assert_eq!(follow_flow_field(&field, goal, goal), Some(vec![goal]));
+        assert_eq!(follow_flow_field(&field, Point::new(3, 1), goal), None);
+    }
+
+    #[test]
+    fn a_straight_line_has_no_turns_or_curvature() {
+        let line: Vec<Point> = (0..8).map(|x| Point::new(x, 3)).collect();
+        assert_eq!(turn_count(&line), 0);
+        assert_eq!(total_curvature(&line), 0.0);
+        assert_eq!(turn_count(&line[..1]), 0);
+        assert_eq!(turn_count(&[]), 0);
+    }
+
+    #[test]
+    fn a_staircase_turns_at_every_step() {
+        // Right, down, right, down, ...: each step after the first is a turn.
+        let mut staircase = vec![Point::new(0, 0)];
+        for i in 0..8 {
+            let last = staircase[staircase.len() - 1];
+            staircase.push(if i % 2 == 0 { Point::new(last.x + 1, last.y) } else { Point::new(last.x, last.y + 1) });
+        }
+        assert_eq!(turn_count(&staircase), 7);
+        assert!((total_curvature(&staircase) - 7.0 * std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn curvature_weighs_each_turn_by_its_angle() {
+        let reversal = [Point::new(0, 0), Point::new(1, 0), Point::new(0, 0)];
+        assert!((total_curvature(&reversal) - std::f64::consts::PI).abs() < 1e-12);
+
+        let diagonal = [Point::new(0, 0), Point::new(1, 0), Point::new(2, 1)];
+        assert_eq!(turn_count(&diagonal), 1);
+        assert!((total_curvature(&diagonal) - std::f64::consts::FRAC_PI_4).abs() < 1e-12);
+
+        // A repeated point is not a step, so it does not break up a straight run.
+        let pause = [Point::new(0, 0), Point::new(1, 0), Point::new(1, 0), Point::new(2, 0)];
+        assert_eq!(turn_count(&pause), 0);
+    }
+
+    #[test]
+    fn search_stats_are_populated_and_bounded_by_the_free_cells() {
+        let mut grid = crate::maze::generate_maze_seeded(31, 21, 4);
+        grid.set_cost(Point::new(1, 1), 5);
+        let (start, goal) = (Point::new(0, 1), Point::new(30, 19));
+        let (path, stats) = a_star_stats(&grid, start, goal).unwrap();
+
+        assert_eq!(Some(path.clone()), a_star(&grid, start, goal));
+        assert_eq!(stats.path_cost, path_cost(&grid, &path));
+        let free_cells = grid.iter().filter(|&(_, cell)| cell != Cell::Blocked).count();
+        assert!(stats.nodes_expanded >= path.len() && stats.nodes_expanded <= free_cells, "{:?}", stats);
+        assert!(stats.max_frontier_size >= 1);
+    }
+
+    #[test]
+    fn search_stats_are_not_reported_for_an_unreachable_goal() {
+        let grid = Grid::from_ascii("..#.\n..#.").unwrap();
+        assert_eq!(a_star_stats(&grid, Point::new(0, 0), Point::new(3, 1)), None);
+    }
+
+    #[test]
+    fn move_costs_distance_is_the_octile_distance_by_default() {
+        let costs = MoveCosts::default();
+        let origin = Point::new(0, 0);
+        assert!((costs.distance(origin, Point::new(5, 5)) - 5.0 * std::f64::consts::SQRT_2).abs() < 1e-12);
+        assert!((costs.distance(origin, Point::new(7, 3)) - (4.0 + 3.0 * std::f64::consts::SQRT_2)).abs() < 1e-12);
+        assert_eq!(costs.distance(Point::new(2, 4), Point::new(2, 4)), 0.0);
+    }
+
+    #[test]
+    fn move_costs_distance_never_charges_a_diagonal_more_than_two_orthogonal_steps() {
+        let costs = MoveCosts { ortho: 1.0, diag: 3.0 };
+        assert_eq!(costs.distance(Point::new(0, 0), Point::new(3, 3)), 6.0);
+    }
+
+    #[test]
+    fn a_star_move_costs_prices_diagonal_steps_geometrically() {
+        let grid = Grid::new(10, 10, Cell::Free);
+        let (start, goal) = (Point::new(0, 0), Point::new(6, 6));
+        let (path, cost) = a_star_move_costs(&grid, start, goal, Connectivity::Diagonal, MoveCosts::default()).unwrap();
+        assert_eq!(path.len(), 7);
+        assert_eq!((path[0], path[6]), (start, goal));
+        // Six diagonal steps, not the Manhattan count of twelve.
+        assert!((cost - 6.0 * std::f64::consts::SQRT_2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn a_star_move_costs_mixes_straight_and_diagonal_steps_on_an_uneven_offset() {
+        let grid = Grid::new(10, 10, Cell::Free);
+        let (path, cost) =
+            a_star_move_costs(&grid, Point::new(0, 0), Point::new(7, 3), Connectivity::Diagonal, MoveCosts::default()).unwrap();
+        assert_eq!(path.len(), 8);
+        assert!((cost - (4.0 + 3.0 * std::f64::consts::SQRT_2)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn a_star_move_costs_scales_each_step_by_the_terrain_it_enters() {
+        let mut grid = Grid::new(5, 5, Cell::Free);
+        for point in grid.iter_points().collect::<Vec<_>>() {
+            grid.set_cost(point, 3);
+        }
+        let (_, cost) =
+            a_star_move_costs(&grid, Point::new(0, 0), Point::new(4, 4), Connectivity::Diagonal, MoveCosts::default()).unwrap();
+        assert!((cost - 12.0 * std::f64::consts::SQRT_2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn a_star_move_costs_avoids_diagonals_costing_more_than_two_straight_steps() {
+        let grid = Grid::new(5, 5, Cell::Free);
+        let costs = MoveCosts { ortho: 1.0, diag: 3.0 };
+        let (path, cost) = a_star_move_costs(&grid, Point::new(0, 0), Point::new(3, 3), Connectivity::Diagonal, costs).unwrap();
+        assert_eq!(cost, 6.0);
+        assert!(path.windows(2).all(|step| step[0].x == step[1].x || step[0].y == step[1].y));
+    }
+
+    #[test]
+    fn a_star_move_costs_with_orthogonal_connectivity_costs_the_manhattan_distance() {
+        let grid = Grid::new(5, 5, Cell::Free);
+        let (_, cost) =
+            a_star_move_costs(&grid, Point::new(0, 0), Point::new(3, 4), Connectivity::Orthogonal, MoveCosts::default()).unwrap();
+        assert_eq!(cost, 7.0);
+    }
+}
@@ -0,0 +1,200 @@
+//! # Patterns Module
+//!
+//! Provides a library of well-known Life patterns (gliders, oscillators, still
+//! lifes and a glider gun) and helpers to stamp them, in any orientation, onto
+//! a flat toroidal grid of cells.
+
+/// A named Life pattern, stored as a list of live-cell offsets relative to
+/// its top-left corner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    cells: Vec<(isize, isize)>,
+}
+
+impl Pattern {
+    /// Creates a pattern from a list of relative `(dx, dy)` live-cell offsets.
+    pub fn new(cells: Vec<(isize, isize)>) -> Self {
+        Self { cells }
+    }
+
+    /// Returns the relative `(dx, dy)` offsets of the pattern's live cells.
+    pub fn cells(&self) -> &[(isize, isize)] {
+        &self.cells
+    }
+
+    /// Returns the pattern in one of its 8 orientations, shifted so its
+    /// top-left corner stays at `(0, 0)`.
+    ///
+    /// `orientation % 4` is the number of quarter turns clockwise, and values
+    /// from 4 to 7 mirror the turned pattern left-to-right. Orientation 0 is
+    /// the pattern as defined.
+    pub fn oriented(&self, orientation: u8) -> Self {
+        Self::new(orient(&self.cells, orientation))
+    }
+
+    /// A glider. Travels one cell down and to the right every 4 generations.
+    pub fn glider() -> Self {
+        Self::new(vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)])
+    }
+
+    /// A horizontal blinker. Oscillates with period 2.
+    pub fn blinker() -> Self {
+        Self::new(vec![(0, 0), (1, 0), (2, 0)])
+    }
+
+    /// A 2x2 block. A still life.
+    pub fn block() -> Self {
+        Self::new(vec![(0, 0), (1, 0), (0, 1), (1, 1)])
+    }
+
+    /// A toad. Oscillates with period 2.
+    pub fn toad() -> Self {
+        Self::new(vec![(1, 0), (2, 0), (3, 0), (0, 1), (1, 1), (2, 1)])
+    }
+
+    /// A beacon. Oscillates with period 2.
+    pub fn beacon() -> Self {
+        Self::new(vec![(0, 0), (1, 0), (0, 1), (3, 2), (2, 3), (3, 3)])
+    }
+
+    /// A lightweight spaceship. Travels two cells to the right every 4 generations.
+    pub fn lightweight_spaceship() -> Self {
+        Self::new(vec![
+            (0, 0), (3, 0),
+            (4, 1),
+            (0, 2), (4, 2),
+            (1, 3), (2, 3), (3, 3), (4, 3),
+        ])
+    }
+
+    /// Gosper's glider gun. Emits a new glider every 30 generations and needs
+    /// a 36x9 area.
+    pub fn gosper_glider_gun() -> Self {
+        Self::new(vec![
+            (24, 0),
+            (22, 1), (24, 1),
+            (12, 2), (13, 2), (20, 2), (21, 2), (34, 2), (35, 2),
+            (11, 3), (15, 3), (20, 3), (21, 3), (34, 3), (35, 3),
+            (0, 4), (1, 4), (10, 4), (16, 4), (20, 4), (21, 4),
+            (0, 5), (1, 5), (10, 5), (14, 5), (16, 5), (17, 5), (22, 5), (24, 5),
+            (10, 6), (16, 6), (24, 6),
+            (11, 7), (15, 7),
+            (12, 8), (13, 8),
+        ])
+    }
+}
+
+/// "Stamps" a pattern onto a flat grid of Life cells, setting each of its live
+/// cells to 1.
+///
+/// The pattern's top-left corner is placed at `origin`, and any cell that falls
+/// off an edge wraps around to the opposite side.
+///
+/// # Arguments
+/// * `cells` - The grid cells, stored row by row (`y * width + x`).
+/// * `width` - The width of the grid.
+/// * `height` - The height of the grid.
+/// * `pattern` - The pattern to stamp.
+/// * `origin` - The `(x, y)` position of the pattern's top-left corner.
+pub fn stamp_pattern(cells: &mut [u8], width: usize, height: usize, pattern: &Pattern, origin: (isize, isize)) {
+    stamp(cells, width, height, pattern.cells(), origin, 0);
+}
+
+/// Stamps a list of live-cell offsets onto a flat grid of Life cells after
+/// rotating or mirroring them, e.g. to aim a glider in any direction.
+///
+/// `orientation` is interpreted as in `Pattern::oriented`. As with
+/// `stamp_pattern`, the oriented pattern's top-left corner is placed at
+/// `origin` and cells that fall off an edge wrap around. Stamping onto a grid
+/// with no width or height does nothing.
+pub fn stamp(
+    cells: &mut [u8],
+    width: usize,
+    height: usize,
+    pattern: &[(isize, isize)],
+    origin: (isize, isize),
+    orientation: u8,
+) {
+    if width == 0 || height == 0 {
+        return; // There is no cell to wrap onto.
+    }
+    for (dx, dy) in orient(pattern, orientation) {
+        let x = (origin.0 + dx).rem_euclid(width as isize) as usize;
+        let y = (origin.1 + dy).rem_euclid(height as isize) as usize;
+        cells[y * width + x] = 1;
+    }
+}
+
+/// Applies an orientation to a list of offsets and shifts the result back so
+/// its smallest `dx` and `dy` are 0.
+fn orient(pattern: &[(isize, isize)], orientation: u8) -> Vec<(isize, isize)> {
+    let transformed: Vec<(isize, isize)> = pattern
+        .iter()
+        .map(|&(dx, dy)| {
+            // With y pointing down, a clockwise quarter turn maps (x, y) to (-y, x).
+            let (mut x, mut y) = (dx, dy);
+            for _ in 0..orientation % 4 {
+                (x, y) = (-y, x);
+            }
+            if orientation % 8 >= 4 { (-x, y) } else { (x, y) }
+        })
+        .collect();
+
+    let min_x = transformed.iter().map(|&(x, _)| x).min().unwrap_or(0);
+    let min_y = transformed.iter().map(|&(_, y)| y).min().unwrap_or(0);
+    transformed.into_iter().map(|(x, y)| (x - min_x, y - min_y)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the `(x, y)` positions of the live cells of a flat grid.
+    fn live_cells(cells: &[u8], width: usize) -> Vec<(usize, usize)> {
+        cells.iter().enumerate().filter(|&(_, &c)| c == 1).map(|(i, _)| (i % width, i / width)).collect()
+    }
+
+    #[test]
+    fn stamping_a_blinker_sets_exactly_its_three_cells() {
+        let mut cells = vec![0; 5 * 5];
+        stamp_pattern(&mut cells, 5, 5, &Pattern::blinker(), (1, 2));
+        assert_eq!(live_cells(&cells, 5), [(1, 2), (2, 2), (3, 2)]);
+
+        let mut vertical = vec![0; 5 * 5];
+        stamp(&mut vertical, 5, 5, Pattern::blinker().cells(), (2, 1), 1);
+        assert_eq!(live_cells(&vertical, 5), [(2, 1), (2, 2), (2, 3)]);
+    }
+
+    #[test]
+    fn stamped_cells_wrap_around_every_edge() {
+        let mut cells = vec![0; 4 * 3];
+        stamp_pattern(&mut cells, 4, 3, &Pattern::block(), (3, 2));
+        assert_eq!(live_cells(&cells, 4), [(0, 0), (3, 0), (0, 2), (3, 2)]);
+
+        let mut negative = vec![0; 4 * 3];
+        stamp_pattern(&mut negative, 4, 3, &Pattern::block(), (-1, -1));
+        assert_eq!(negative, cells);
+    }
+
+    #[test]
+    fn orientations_turn_clockwise_and_mirror() {
+        let glider = Pattern::glider();
+        assert_eq!(glider.oriented(0), glider);
+        assert_eq!(glider.oriented(1).oriented(3), glider);
+        assert_eq!(glider.oriented(8), glider);
+
+        // A quarter turn clockwise takes the top-right cell of an L to the bottom right.
+        let l_shape = Pattern::new(vec![(0, 0), (1, 0), (0, 1)]);
+        assert_eq!(l_shape.oriented(1), Pattern::new(vec![(1, 0), (1, 1), (0, 0)]));
+        // Orientation 4 mirrors the pattern left-to-right.
+        assert_eq!(l_shape.oriented(4), Pattern::new(vec![(1, 0), (0, 0), (1, 1)]));
+    }
+
+    #[test]
+    fn stamping_onto_an_empty_grid_does_nothing() {
+        let mut cells: Vec<u8> = Vec::new();
+        stamp(&mut cells, 0, 0, Pattern::glider().cells(), (2, 3), 5);
+        stamp_pattern(&mut cells, 0, 7, &Pattern::block(), (0, 0));
+        assert!(cells.is_empty());
+    }
+}
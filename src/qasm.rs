@@ -0,0 +1,239 @@
+//! # QASM Module
+//!
+//! Converts a circuit's recorded operations into an OpenQASM 2.0 program so it
+//! can be run on other simulators.
+
+use crate::circuit::Operation;
+use crate::gates;
+use num_complex::Complex;
+use std::fmt::Write;
+
+type F = f64;
+
+/// Magnitudes below this are treated as zero when decomposing a 2x2 gate.
+const EPSILON: F = 1e-12;
+
+/// The prefix of the opaque gates declared for arbitrary 4x4 gates, which
+/// QASM 2.0 cannot express with its standard library; each distinct matrix
+/// gets its own number.
+const OPAQUE_TWO_QUBIT_PREFIX: &str = "two_qubit_unitary";
+
+/// The prefix of the opaque gates declared for multi-controlled Z gates with
+/// three or more controls; the qubit count is appended.
+const OPAQUE_MCZ_PREFIX: &str = "mcz";
+
+/// Builds an OpenQASM 2.0 program for a register of `num_qubits` qubits that
+/// applies `operations` in order.
+///
+/// A classical register `c` is only declared if the circuit measures.
+/// Two-qubit matrices equal to `gates::CNOT`, `gates::CZ` or `gates::SWAP`
+/// become `cx`, `cz` and `swap`. Every other distinct two-qubit matrix is
+/// declared as its own numbered `opaque` gate, since QASM 2.0 has no way to
+/// spell out their matrices.
+pub fn to_qasm(num_qubits: usize, operations: &[Operation]) -> String {
+    let mut qasm = String::from("OPENQASM 2.0;\ninclude \"qelib1.inc\";\n");
+
+    // Opaque gates are numbered in the order their matrices first appear.
+    let mut opaque_matrices: Vec<&[[Complex<F>; 4]; 4]> = Vec::new();
+    for op in operations {
+        if let Operation::TwoQubit(_, _, ref matrix) = *op
+            && standard_two_qubit_gate(matrix).is_none()
+            && !opaque_matrices.iter().any(|known| same_matrix(known, matrix))
+        {
+            writeln!(qasm, "opaque {}{} a,b;", OPAQUE_TWO_QUBIT_PREFIX, opaque_matrices.len()).unwrap();
+            opaque_matrices.push(matrix);
+        }
+    }
+    // QASM 2.0 has no variadic gates, so every MCZ with three or more controls
+    // gets its own opaque declaration, named after its qubit count.
+    let mut mcz_sizes: Vec<usize> = operations
+        .iter()
+        .filter_map(|op| match op {
+            Operation::Mcz(controls, _) if controls.len() > 2 => Some(controls.len() + 1),
+            _ => None,
+        })
+        .collect();
+    mcz_sizes.sort_unstable();
+    mcz_sizes.dedup();
+    for size in mcz_sizes {
+        let args: Vec<String> = (0..size).map(|i| format!("q{}", i)).collect();
+        writeln!(qasm, "opaque {}{} {};", OPAQUE_MCZ_PREFIX, size, args.join(",")).unwrap();
+    }
+    writeln!(qasm, "qreg q[{}];", num_qubits).unwrap();
+    if operations.iter().any(|op| matches!(op, Operation::Measure(_) | Operation::MeasureAll)) {
+        writeln!(qasm, "creg c[{}];", num_qubits).unwrap();
+    }
+
+    for op in operations {
+        let line = match *op {
+            Operation::H(t) => format!("h q[{}];", t),
+            Operation::X(t) => format!("x q[{}];", t),
+            Operation::Y(t) => format!("y q[{}];", t),
+            Operation::Z(t) => format!("z q[{}];", t),
+            Operation::Rx(t, theta) => format!("rx({}) q[{}];", theta, t),
+            Operation::Ry(t, theta) => format!("ry({}) q[{}];", theta, t),
+            Operation::Rz(t, theta) => format!("rz({}) q[{}];", theta, t),
+            Operation::Phase(t, lambda) => format!("u1({}) q[{}];", lambda, t),
+            Operation::Unitary(t, ref matrix) => {
+                let (theta, phi, lambda, _) = u3_angles(matrix);
+                format!("u3({},{},{}) q[{}];", theta, phi, lambda, t)
+            }
+            Operation::ControlledUnitary(c, t, ref matrix) => {
+                // Once controlled, the global phase becomes a relative phase on
+                // the control qubit, so it has to be applied explicitly.
+                let (theta, phi, lambda, global) = u3_angles(matrix);
+                format!("u1({}) q[{}];\ncu3({},{},{}) q[{}],q[{}];", global, c, theta, phi, lambda, c, t)
+            }
+            Operation::Cnot(c, t) => format!("cx q[{}],q[{}];", c, t),
+            Operation::Cz(c, t) => format!("cz q[{}],q[{}];", c, t),
+            Operation::Swap(q1, q2) => format!("swap q[{}],q[{}];", q1, q2),
+            Operation::ControlledPhase(c, t, lambda) => format!("cu1({}) q[{}],q[{}];", lambda, c, t),
+            Operation::Toffoli(c1, c2, t) => format!("ccx q[{}],q[{}],q[{}];", c1, c2, t),
+            Operation::Mcz(ref controls, t) => match controls[..] {
+                [] => format!("z q[{}];", t),
+                [c] => format!("cz q[{}],q[{}];", c, t),
+                // CCZ is a Toffoli conjugated by Hadamards on the target.
+                [c1, c2] => format!("h q[{}];\nccx q[{}],q[{}],q[{}];\nh q[{}];", t, c1, c2, t, t),
+                _ => {
+                    let args: Vec<String> = controls.iter().chain([&t]).map(|q| format!("q[{}]", q)).collect();
+                    format!("{}{} {};", OPAQUE_MCZ_PREFIX, controls.len() + 1, args.join(","))
+                }
+            },
+            Operation::TwoQubit(q1, q2, ref matrix) => match standard_two_qubit_gate(matrix) {
+                Some(name) => format!("{} q[{}],q[{}];", name, q1, q2),
+                None => {
+                    let number = opaque_matrices.iter().position(|known| same_matrix(known, matrix)).unwrap();
+                    format!("{}{} q[{}],q[{}];", OPAQUE_TWO_QUBIT_PREFIX, number, q1, q2)
+                }
+            },
+            Operation::Reset(t) => format!("reset q[{}];", t),
+            Operation::Measure(t) => format!("measure q[{}] -> c[{}];", t, t),
+            Operation::MeasureAll => "measure q -> c;".to_string(),
+        };
+        qasm.push_str(&line);
+        qasm.push('\n');
+    }
+    qasm
+}
+
+/// Returns the `qelib1.inc` name of a two-qubit matrix from the `gates`
+/// registry, if it is one QASM 2.0 has a standard gate for.
+fn standard_two_qubit_gate(matrix: &[[Complex<F>; 4]; 4]) -> Option<&'static str> {
+    [(&gates::CNOT, "cx"), (&gates::CZ, "cz"), (&gates::SWAP, "swap")]
+        .into_iter()
+        .find(|(gate, _)| same_matrix(gate, matrix))
+        .map(|(_, name)| name)
+}
+
+/// Returns `true` if two 4x4 matrices agree entry by entry within `EPSILON`.
+fn same_matrix(a: &[[Complex<F>; 4]; 4], b: &[[Complex<F>; 4]; 4]) -> bool {
+    a.iter().flatten().zip(b.iter().flatten()).all(|(x, y)| (x - y).norm() < EPSILON)
+}
+
+/// Decomposes a 2x2 unitary into the `(θ, φ, λ)` angles of QASM's `u3` gate
+/// plus the global phase `α`, such that the matrix equals `e^{iα} u3(θ, φ, λ)`:
+///
+/// u3(θ, φ, λ) = | cos(θ/2)          -e^{iλ} sin(θ/2)     |
+///               | e^{iφ} sin(θ/2)    e^{i(φ+λ)} cos(θ/2) |
+fn u3_angles(matrix: &[[Complex<F>; 2]; 2]) -> (F, F, F, F) {
+    let [[a, b], [c, d]] = *matrix;
+    let theta = 2.0 * c.norm().atan2(a.norm());
+
+    if a.norm() > EPSILON {
+        // The global phase is fixed by making the top-left entry real.
+        let global = a.arg();
+        let phi = if c.norm() > EPSILON { c.arg() - global } else { 0.0 };
+        let lambda = if b.norm() > EPSILON { (-b).arg() - global } else { d.arg() - global - phi };
+        (theta, phi, lambda, global)
+    } else {
+        // θ = π, so only φ + λ is meaningful; fix φ = 0.
+        let global = c.arg();
+        (theta, 0.0, (-b).arg() - global, global)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::QuantumCircuit;
+    use crate::gates;
+
+    /// Builds the `u3(θ, φ, λ)` matrix from its angles.
+    fn u3(theta: F, phi: F, lambda: F) -> [[Complex<F>; 2]; 2] {
+        let (sin, cos) = (theta / 2.0).sin_cos();
+        [
+            [Complex::new(cos, 0.0), -Complex::from_polar(sin, lambda)],
+            [Complex::from_polar(sin, phi), Complex::from_polar(cos, phi + lambda)],
+        ]
+    }
+
+    #[test]
+    fn deutsch_circuit_exports_the_expected_program() {
+        let mut circuit = QuantumCircuit::new(2);
+        circuit.x(1).h(0).h(1).cnot(0, 1).h(0);
+        assert_eq!(
+            circuit.to_qasm(),
+            "OPENQASM 2.0;\ninclude \"qelib1.inc\";\nqreg q[2];\nx q[1];\nh q[0];\nh q[1];\ncx q[0],q[1];\nh q[0];\n"
+        );
+    }
+
+    #[test]
+    fn measurements_declare_a_classical_register() {
+        let mut circuit = QuantumCircuit::new(2);
+        circuit.h(0);
+        circuit.measure_qubit(0);
+        let qasm = circuit.to_qasm();
+        assert!(qasm.contains("creg c[2];\n"));
+        assert!(qasm.ends_with("measure q[0] -> c[0];\n"));
+    }
+
+    #[test]
+    fn registry_two_qubit_gates_use_their_standard_names() {
+        let mut circuit = QuantumCircuit::new(3);
+        circuit.apply_two_qubit_gate(0, 1, &gates::CNOT).apply_two_qubit_gate(2, 0, &gates::SWAP).apply_two_qubit_gate(1, 2, &gates::CZ);
+        assert_eq!(
+            circuit.to_qasm(),
+            "OPENQASM 2.0;\ninclude \"qelib1.inc\";\nqreg q[3];\ncx q[0],q[1];\nswap q[2],q[0];\ncz q[1],q[2];\n"
+        );
+    }
+
+    #[test]
+    fn each_distinct_two_qubit_matrix_gets_its_own_opaque_gate() {
+        let operations = [
+            Operation::TwoQubit(0, 1, gates::ISWAP),
+            Operation::TwoQubit(1, 2, gates::SQRT_ISWAP),
+            Operation::TwoQubit(2, 0, gates::ISWAP),
+        ];
+        assert_eq!(
+            to_qasm(3, &operations),
+            format!(
+                "OPENQASM 2.0;\ninclude \"qelib1.inc\";\nopaque {p}0 a,b;\nopaque {p}1 a,b;\nqreg q[3];\n{p}0 q[0],q[1];\n{p}1 q[1],q[2];\n{p}0 q[2],q[0];\n",
+                p = OPAQUE_TWO_QUBIT_PREFIX
+            )
+        );
+    }
+
+    #[test]
+    fn u3_angles_reproduce_the_gate_up_to_its_global_phase() {
+        let matrices = [
+            gates::HADAMARD,
+            gates::PAULI_X,
+            gates::PAULI_Y,
+            gates::PAULI_Z,
+            gates::rx(0.7),
+            gates::ry(1.1),
+            gates::rz(0.3),
+            gates::phase(2.0),
+        ];
+        for matrix in matrices {
+            let (theta, phi, lambda, global) = u3_angles(&matrix);
+            let rebuilt = u3(theta, phi, lambda);
+            for (row, entries) in matrix.iter().enumerate() {
+                for (col, &entry) in entries.iter().enumerate() {
+                    let expected = Complex::from_polar(1.0, global) * rebuilt[row][col];
+                    assert!((entry - expected).norm() < 1e-9, "{:?}", matrix);
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,8 @@
 //! Represents a single qubit.
 
+use crate::gates;
 use num_complex::Complex;
+use rand::Rng;
 use std::fmt;
 
 // We'll use 64-bit floats for our calculations.
@@ -49,10 +51,88 @@ impl Qubit {
         let new_alpha = g00 * alpha + g01 * beta;
         let new_beta = g10 * alpha + g11 * beta;
 
-        // We should ideally re-normalize here to handle floating point errors,
-        // but we can add that later.
+        // We don't re-normalize here; call `normalize` to correct any
+        // floating point drift.
         self.state = [new_alpha, new_beta];
     }
+
+    /// Applies a Hadamard gate. Returns `self` so gates can be chained.
+    pub fn h(&mut self) -> &mut Self {
+        self.apply_gate(&gates::HADAMARD);
+        self
+    }
+
+    /// Applies a Pauli-X (NOT) gate.
+    pub fn x(&mut self) -> &mut Self {
+        self.apply_gate(&gates::PAULI_X);
+        self
+    }
+
+    /// Applies a Pauli-Y gate.
+    pub fn y(&mut self) -> &mut Self {
+        self.apply_gate(&gates::PAULI_Y);
+        self
+    }
+
+    /// Applies a Pauli-Z gate.
+    pub fn z(&mut self) -> &mut Self {
+        self.apply_gate(&gates::PAULI_Z);
+        self
+    }
+
+    /// Measures the qubit in the computational basis, reading 1 with
+    /// probability |β|² (relative to the norm), and collapses it to |0⟩ or |1⟩
+    /// to match the outcome.
+    pub fn measure(&mut self, rng: &mut impl Rng) -> u8 {
+        let prob_zero = self.state[0].norm_sqr();
+        let prob_one = self.state[1].norm_sqr();
+
+        // Scaling the sample by the total keeps a slightly non-unit state fair.
+        let random_sample: F = rng.random();
+        let outcome = if random_sample * (prob_zero + prob_one) < prob_one { 1 } else { 0 };
+
+        let (zero, one) = (Complex::new(0.0, 0.0), Complex::new(1.0, 0.0));
+        self.state = if outcome == 1 { [zero, one] } else { [one, zero] };
+        outcome
+    }
+
+    /// Returns the norm of the state, sqrt(|α|² + |β|²), which should be 1.0.
+    pub fn norm(&self) -> F {
+        (self.state[0].norm_sqr() + self.state[1].norm_sqr()).sqrt()
+    }
+
+    /// Rescales the state to unit norm to counter floating point drift.
+    /// A zero state is left unchanged.
+    pub fn normalize(&mut self) {
+        let norm = self.norm();
+        if norm == 0.0 {
+            return;
+        }
+        self.state[0] /= norm;
+        self.state[1] /= norm;
+    }
+
+    /// Returns the qubit's position `(x, y, z)` on the Bloch sphere.
+    ///
+    /// The state is normalized first, so a slightly non-unit state still lands
+    /// on the sphere's surface:
+    ///
+    /// x = 2 Re(α* β),  y = 2 Im(α* β),  z = |α|² - |β|²
+    pub fn bloch_coords(&self) -> (F, F, F) {
+        let norm_sqr = self.state[0].norm_sqr() + self.state[1].norm_sqr();
+        if norm_sqr == 0.0 {
+            return (0.0, 0.0, 0.0);
+        }
+
+        let alpha = self.state[0];
+        let beta = self.state[1];
+        let coherence = alpha.conj() * beta / norm_sqr;
+
+        let x = 2.0 * coherence.re;
+        let y = 2.0 * coherence.im;
+        let z = (alpha.norm_sqr() - beta.norm_sqr()) / norm_sqr;
+        (x, y, z)
+    }
 }
 
 /// Implement the Display trait for pretty-printing the qubit's state.
@@ -74,3 +154,130 @@ impl Default for Qubit {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn normalize_rescales_a_drifted_qubit() {
+        let double = [
+            [Complex::new(2.0, 0.0), Complex::new(0.0, 0.0)],
+            [Complex::new(0.0, 0.0), Complex::new(2.0, 0.0)],
+        ];
+        let mut qubit = Qubit::new();
+        qubit.h().apply_gate(&double);
+        assert!((qubit.norm() - 2.0).abs() < 1e-12);
+
+        qubit.normalize();
+        assert!((qubit.norm() - 1.0).abs() < 1e-12);
+        assert!((qubit.get_state_vector()[1].re - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-12);
+    }
+
+    /// Asserts that two Bloch vectors agree to within rounding.
+    fn assert_bloch(actual: (F, F, F), expected: (F, F, F)) {
+        let close = (actual.0 - expected.0).abs() < 1e-12
+            && (actual.1 - expected.1).abs() < 1e-12
+            && (actual.2 - expected.2).abs() < 1e-12;
+        assert!(close, "{:?} != {:?}", actual, expected);
+    }
+
+    #[test]
+    fn basis_states_sit_on_the_poles() {
+        let mut qubit = Qubit::new();
+        assert_bloch(qubit.bloch_coords(), (0.0, 0.0, 1.0));
+        qubit.x();
+        assert_bloch(qubit.bloch_coords(), (0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn superpositions_sit_on_the_equator() {
+        let mut qubit = Qubit::new();
+        qubit.h();
+        assert_bloch(qubit.bloch_coords(), (1.0, 0.0, 0.0));
+        qubit.apply_gate(&gates::phase(std::f64::consts::FRAC_PI_2));
+        assert_bloch(qubit.bloch_coords(), (0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn bloch_coords_normalize_a_drifted_state() {
+        let mut qubit = Qubit::new();
+        qubit.h().apply_gate(&[
+            [Complex::new(1.5, 0.0), Complex::new(0.0, 0.0)],
+            [Complex::new(0.0, 0.0), Complex::new(1.5, 0.0)],
+        ]);
+        assert_bloch(qubit.bloch_coords(), (1.0, 0.0, 0.0));
+    }
+
+    /// Asserts that a qubit holds the given amplitudes, up to rounding.
+    fn assert_state(qubit: &Qubit, expected: [Complex<F>; 2]) {
+        for (actual, expected) in qubit.get_state_vector().iter().zip(expected) {
+            assert!((actual - expected).norm() < 1e-12, "{} != {}", qubit, expected);
+        }
+    }
+
+    #[test]
+    fn two_hadamards_chain_back_to_zero() {
+        let mut qubit = Qubit::new();
+        qubit.h().h();
+        assert_state(&qubit, [Complex::new(1.0, 0.0), Complex::new(0.0, 0.0)]);
+    }
+
+    #[test]
+    fn chained_paulis_match_their_matrices() {
+        let (zero, one, i) = (Complex::new(0.0, 0.0), Complex::new(1.0, 0.0), Complex::new(0.0, 1.0));
+
+        let mut qubit = Qubit::new();
+        qubit.x();
+        assert_state(&qubit, [zero, one]);
+        qubit.z();
+        assert_state(&qubit, [zero, -one]);
+        qubit.y();
+        // Y|1⟩ = -i|0⟩, so Y(-|1⟩) = i|0⟩.
+        assert_state(&qubit, [i, zero]);
+
+        let mut chained = Qubit::new();
+        chained.x().z().y();
+        assert_state(&chained, [i, zero]);
+    }
+
+    #[test]
+    fn measuring_a_hadamard_state_splits_evenly() {
+        let mut rng = ChaCha8Rng::seed_from_u64(17);
+        let ones: usize = (0..4000)
+            .map(|_| {
+                let mut qubit = Qubit::new();
+                qubit.h();
+                qubit.measure(&mut rng) as usize
+            })
+            .sum();
+        assert!(ones.abs_diff(2000) < 200, "got {} ones", ones);
+    }
+
+    #[test]
+    fn measurement_collapses_onto_its_outcome() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        let mut qubit = Qubit::new();
+        qubit.h();
+        let outcome = qubit.measure(&mut rng);
+        let collapsed = *qubit.get_state_vector();
+        assert_eq!(collapsed[outcome as usize], Complex::new(1.0, 0.0));
+        for _ in 0..10 {
+            assert_eq!(qubit.measure(&mut rng), outcome);
+        }
+    }
+
+    #[test]
+    fn basis_states_always_measure_to_themselves() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut zero = Qubit::new();
+        let mut one = Qubit::new();
+        one.x();
+        for _ in 0..20 {
+            assert_eq!(zero.measure(&mut rng), 0);
+            assert_eq!(one.measure(&mut rng), 1);
+        }
+    }
+}